@@ -13,9 +13,10 @@ use factorion_lib::influxdb::InfluxDbClient;
 use log::{error, info, warn};
 use serde::{Deserialize, Serialize};
 use serenity::all::{
-    ChannelId, Colour, CreateEmbed, CreateEmbedFooter, CreateMessage, GatewayIntents, Message,
-    MessageId, Ready, Timestamp,
+    ChannelId, CreateEmbed, CreateMessage, GatewayIntents, Message, MessageId, Ready,
 };
+#[cfg(not(test))]
+use serenity::all::{Colour, CreateEmbedFooter, Timestamp};
 use serenity::async_trait;
 use serenity::prelude::*;
 use tokio::sync::Mutex;
@@ -36,6 +37,7 @@ pub struct MessageMeta {
 pub struct Handler<'a> {
     processed_messages: Arc<Mutex<HashSet<MessageId>>>,
     channel_configs: Arc<Mutex<HashMap<u64, Config>>>,
+    #[cfg_attr(test, allow(dead_code))]
     config_path: PathBuf,
     consts: Consts<'a>,
     influx_client: Option<&'a InfluxDbClient>,
@@ -70,6 +72,7 @@ impl<'a> Handler<'a> {
         }
     }
 
+    #[cfg_attr(test, allow(dead_code))]
     fn load_configs(path: &PathBuf) -> HashMap<u64, Config> {
         if path.exists()
             && let Ok(content) = fs::read_to_string(path)
@@ -82,6 +85,7 @@ impl<'a> Handler<'a> {
         HashMap::new()
     }
 
+    #[cfg_attr(test, allow(dead_code))]
     async fn save_configs(&self) -> Result<(), Error> {
         let configs = self.channel_configs.lock().await;
         let content = serde_json::to_string_pretty(&*configs)?;
@@ -261,10 +265,12 @@ impl<'a> Handler<'a> {
             .get_formatted_reply(
                 &reply_text,
                 comment.calculation_list.len(),
-                comment
-                    .calculation_list
-                    .iter()
-                    .any(|x| x.is_digit_tower() || x.is_aproximate_digits() || x.is_approximate()),
+                comment.calculation_list.iter().any(|x| {
+                    x.is_digit_hyper()
+                        || x.is_digit_tower()
+                        || x.is_aproximate_digits()
+                        || x.is_approximate()
+                }),
             )
             .await?;
         Ok(Some((message_locale, reply, None)))
@@ -280,16 +286,24 @@ impl<'a> Handler<'a> {
         if parts.len() < 4 {
             let config = self.get_channel_config(channel_id).await;
             let status = format!(
-                "**Channel Configuration**\n```\nShorten: {}\nSteps: {}\nTermial: {}\nNo Note: {}\n Nested: {}\n Write Out: {}\nLocale: {}\n```\n\
+                "**Channel Configuration**\n```\nShorten: {}\nSteps: {}\nTermial: {}\nNo Note: {}\n Nested: {}\n Write Out: {}\n Factorion: {}\n Digits: {}\n Skip Code: {}\n Skip Quotes: {}\n Filter Code: {}\n Numerals: {}\n Functions: {}\n Detect Language: {}\nLocale: {}\n```\n\
                 Usage:\n\
                 `!factorion config <setting> <on/off>`\n\
-                Available settings: shorten, steps, termial, no_note, nested, write_out",
+                Available settings: shorten, steps, termial, no_note, nested, write_out, factorion, digits, skip_code, skip_quotes, filter_code, numerals, functions, detect_language",
                 config.commands.shorten,
                 config.commands.steps,
                 config.commands.termial,
                 config.commands.no_note,
                 config.commands.nested,
                 config.commands.write_out,
+                config.commands.factorion,
+                config.commands.digits,
+                config.commands.skip_code,
+                config.commands.skip_quotes,
+                config.commands.filter_code,
+                config.commands.numerals,
+                config.commands.functions,
+                config.commands.detect_language,
                 config.locale
             );
             return Ok((status.into(), None));
@@ -420,6 +434,150 @@ impl<'a> Handler<'a> {
                     None,
                 ))
             }
+            "factorion" | "digit_sum" | "digitsum" | "digit-sum" => {
+                let Setting::Command(enabled) = val else {
+                    return Ok((
+                        "Invalid value. Use: on/off, true/false, yes/no, or 1/0".into(),
+                        None,
+                    ));
+                };
+                config.commands.factorion = enabled;
+                self.set_channel_config(channel_id, config).await?;
+                Ok((
+                    format!(
+                        "Factorion has been turned **{}**",
+                        if enabled { "ON" } else { "OFF" }
+                    )
+                    .into(),
+                    None,
+                ))
+            }
+            "digits" | "digit_stats" => {
+                let Setting::Command(enabled) = val else {
+                    return Ok((
+                        "Invalid value. Use: on/off, true/false, yes/no, or 1/0".into(),
+                        None,
+                    ));
+                };
+                config.commands.digits = enabled;
+                self.set_channel_config(channel_id, config).await?;
+                Ok((
+                    format!(
+                        "Digits has been turned **{}**",
+                        if enabled { "ON" } else { "OFF" }
+                    )
+                    .into(),
+                    None,
+                ))
+            }
+            "skip_code" | "skip-code" => {
+                let Setting::Command(enabled) = val else {
+                    return Ok((
+                        "Invalid value. Use: on/off, true/false, yes/no, or 1/0".into(),
+                        None,
+                    ));
+                };
+                config.commands.skip_code = enabled;
+                self.set_channel_config(channel_id, config).await?;
+                Ok((
+                    format!(
+                        "Skip Code has been turned **{}**",
+                        if enabled { "ON" } else { "OFF" }
+                    )
+                    .into(),
+                    None,
+                ))
+            }
+            "skip_quotes" | "skip-quotes" => {
+                let Setting::Command(enabled) = val else {
+                    return Ok((
+                        "Invalid value. Use: on/off, true/false, yes/no, or 1/0".into(),
+                        None,
+                    ));
+                };
+                config.commands.skip_quotes = enabled;
+                self.set_channel_config(channel_id, config).await?;
+                Ok((
+                    format!(
+                        "Skip Quotes has been turned **{}**",
+                        if enabled { "ON" } else { "OFF" }
+                    )
+                    .into(),
+                    None,
+                ))
+            }
+            "filter_code" | "filter-code" => {
+                let Setting::Command(enabled) = val else {
+                    return Ok((
+                        "Invalid value. Use: on/off, true/false, yes/no, or 1/0".into(),
+                        None,
+                    ));
+                };
+                config.commands.filter_code = enabled;
+                self.set_channel_config(channel_id, config).await?;
+                Ok((
+                    format!(
+                        "Filter Code has been turned **{}**",
+                        if enabled { "ON" } else { "OFF" }
+                    )
+                    .into(),
+                    None,
+                ))
+            }
+            "numerals" | "roman" => {
+                let Setting::Command(enabled) = val else {
+                    return Ok((
+                        "Invalid value. Use: on/off, true/false, yes/no, or 1/0".into(),
+                        None,
+                    ));
+                };
+                config.commands.numerals = enabled;
+                self.set_channel_config(channel_id, config).await?;
+                Ok((
+                    format!(
+                        "Numerals has been turned **{}**",
+                        if enabled { "ON" } else { "OFF" }
+                    )
+                    .into(),
+                    None,
+                ))
+            }
+            "functions" => {
+                let Setting::Command(enabled) = val else {
+                    return Ok((
+                        "Invalid value. Use: on/off, true/false, yes/no, or 1/0".into(),
+                        None,
+                    ));
+                };
+                config.commands.functions = enabled;
+                self.set_channel_config(channel_id, config).await?;
+                Ok((
+                    format!(
+                        "Functions has been turned **{}**",
+                        if enabled { "ON" } else { "OFF" }
+                    )
+                    .into(),
+                    None,
+                ))
+            }
+            "detect_language" | "detect-language" => {
+                let Setting::Command(enabled) = val else {
+                    return Ok((
+                        "Invalid value. Use: on/off, true/false, yes/no, or 1/0".into(),
+                        None,
+                    ));
+                };
+                config.commands.detect_language = enabled;
+                self.set_channel_config(channel_id, config).await?;
+                Ok((
+                    format!(
+                        "Detect Language has been turned **{}**",
+                        if enabled { "ON" } else { "OFF" }
+                    )
+                    .into(),
+                    None,
+                ))
+            }
             "locale" | "lang" | "language" => {
                 let Setting::Locale(locale) = val else {
                     return Ok(("Invalid value. Use: <locale>".into(), None));
@@ -427,7 +585,7 @@ impl<'a> Handler<'a> {
                 config.locale = locale.clone();
                 self.set_channel_config(channel_id, config).await?;
                 let reply = format!("Locale has been set to **{}**", locale);
-                Ok(if !self.consts.locales.contains_key(&locale) {
+                Ok(if self.consts.get_locale(&locale).1 {
                     (
                         reply.into(),
                         Some(
@@ -1031,6 +1189,54 @@ mod tests {
             .unwrap();
         let content = "!factorion config write_out on";
         let processed = handler.processed_messages.lock().await;
+        handler
+            .process_message_inner(meta.clone(), content, processed, dummy_check)
+            .await
+            .unwrap();
+        let content = "!factorion config factorion on";
+        let processed = handler.processed_messages.lock().await;
+        handler
+            .process_message_inner(meta.clone(), content, processed, dummy_check)
+            .await
+            .unwrap();
+        let content = "!factorion config digits on";
+        let processed = handler.processed_messages.lock().await;
+        handler
+            .process_message_inner(meta.clone(), content, processed, dummy_check)
+            .await
+            .unwrap();
+        let content = "!factorion config skip_code on";
+        let processed = handler.processed_messages.lock().await;
+        handler
+            .process_message_inner(meta.clone(), content, processed, dummy_check)
+            .await
+            .unwrap();
+        let content = "!factorion config skip_quotes on";
+        let processed = handler.processed_messages.lock().await;
+        handler
+            .process_message_inner(meta.clone(), content, processed, dummy_check)
+            .await
+            .unwrap();
+        let content = "!factorion config filter_code on";
+        let processed = handler.processed_messages.lock().await;
+        handler
+            .process_message_inner(meta.clone(), content, processed, dummy_check)
+            .await
+            .unwrap();
+        let content = "!factorion config numerals on";
+        let processed = handler.processed_messages.lock().await;
+        handler
+            .process_message_inner(meta.clone(), content, processed, dummy_check)
+            .await
+            .unwrap();
+        let content = "!factorion config functions on";
+        let processed = handler.processed_messages.lock().await;
+        handler
+            .process_message_inner(meta.clone(), content, processed, dummy_check)
+            .await
+            .unwrap();
+        let content = "!factorion config detect_language on";
+        let processed = handler.processed_messages.lock().await;
         handler
             .process_message_inner(meta.clone(), content, processed, dummy_check)
             .await
@@ -1038,7 +1244,8 @@ mod tests {
         assert_eq!(
             *handler.channel_configs.lock().await.get(&1).unwrap(),
             Config {
-                commands: !Commands::NONE,
+                // why is only set per message
+                commands: !Commands::WHY,
                 locale: "ru".to_owned()
             }
         );
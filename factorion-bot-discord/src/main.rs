@@ -2,7 +2,6 @@
 use dotenvy::dotenv;
 use factorion_lib::Consts;
 use factorion_lib::influxdb::INFLUX_CLIENT;
-use factorion_lib::locale::Locale;
 use factorion_lib::rug::integer::IntegerExt64;
 use factorion_lib::rug::{Complete, Integer};
 use log::{error, info, warn};
@@ -80,6 +79,9 @@ fn get_consts() -> Consts<'static> {
         float_precision: std::env::var("FLOAT_PRECISION")
             .map(|s| s.parse().unwrap())
             .unwrap_or_else(|_| factorion_lib::recommended::FLOAT_PRECISION),
+        max_float_precision: std::env::var("MAX_FLOAT_PRECISION")
+            .map(|s| s.parse().unwrap())
+            .unwrap_or_else(|_| factorion_lib::recommended::MAX_FLOAT_PRECISION),
         upper_calculation_limit: std::env::var("UPPER_CALCULATION_LIMIT")
             .map(|s| s.parse().unwrap())
             .unwrap_or_else(|_| factorion_lib::recommended::UPPER_CALCULATION_LIMIT()),
@@ -89,12 +91,18 @@ fn get_consts() -> Consts<'static> {
         upper_subfactorial_limit: std::env::var("UPPER_SUBFACTORIAL_LIMIT")
             .map(|s| s.parse().unwrap())
             .unwrap_or_else(|_| factorion_lib::recommended::UPPER_SUBFACTORIAL_LIMIT()),
+        upper_combinatorial_limit: std::env::var("UPPER_COMBINATORIAL_LIMIT")
+            .map(|s| s.parse().unwrap())
+            .unwrap_or_else(|_| factorion_lib::recommended::UPPER_COMBINATORIAL_LIMIT()),
         upper_termial_limit: std::env::var("UPPER_TERMIAL_LIMIT")
             .map(|s| Integer::u64_pow_u64(10, s.parse().unwrap()).complete())
             .unwrap_or_else(|_| factorion_lib::recommended::UPPER_TERMIAL_LIMIT()),
         upper_termial_approximation_limit: std::env::var("UPPER_TERMIAL_APPROXIMATION_LIMIT")
             .map(|s| s.parse().unwrap())
             .unwrap_or_else(|_| factorion_lib::recommended::UPPER_TERMIAL_APPROXIMATION_LIMIT),
+        upper_tower_depth_limit: std::env::var("UPPER_TOWER_DEPTH_LIMIT")
+            .map(|s| s.parse().unwrap())
+            .unwrap_or_else(|_| factorion_lib::recommended::UPPER_TOWER_DEPTH_LIMIT()),
         integer_construction_limit: std::env::var("INTEGER_CONSTRUCTION_LIMIT")
             .map(|s| s.parse().unwrap())
             .unwrap_or_else(|_| factorion_lib::recommended::INTEGER_CONSTRUCTION_LIMIT()),
@@ -104,22 +112,25 @@ fn get_consts() -> Consts<'static> {
         locales: std::env::var("LOCALES_DIR")
             .map(|dir| {
                 let files = std::fs::read_dir(dir).unwrap();
-                let mut map = HashMap::new();
-                for (key, value) in files
+                // Variants (like de_AT) only have what is different from their base locale, so they are merged first
+                let jsons = files
                     .map(|file| {
                         let file = file.unwrap();
-                        let mut locale: Locale<'static> = serde_json::de::from_str(
-                            std::fs::read_to_string(file.path()).unwrap().leak(),
+                        let json = serde_json::de::from_str(
+                            &std::fs::read_to_string(file.path()).unwrap(),
                         )
                         .unwrap();
+                        (file.file_name().into_string().unwrap(), json)
+                    })
+                    .collect::<HashMap<_, _>>();
+                factorion_lib::locale::from_jsons(&jsons)
+                    .unwrap()
+                    .into_iter()
+                    .map(|(key, mut locale)| {
                         locale.bot_disclaimer = "".into();
-                        (file.file_name().into_string().unwrap(), locale)
+                        (key, locale)
                     })
-                    .collect::<Box<_>>()
-                {
-                    map.insert(key, value);
-                }
-                map
+                    .collect()
             })
             .unwrap_or_else(|_| {
                 factorion_lib::locale::get_all()
@@ -130,5 +141,6 @@ fn get_consts() -> Consts<'static> {
                     .collect()
             }),
         default_locale: "en".to_owned(),
+        language_profiles: Default::default(),
     }
 }
@@ -4,7 +4,6 @@ use factorion_lib::{
     Consts,
     comment::{Commands, Comment, CommentCalculated, CommentExtracted, Status},
     influxdb::{INFLUX_CLIENT, InfluxDbClient},
-    locale::Locale,
     rug::{Complete, Integer, integer::IntegerExt64},
 };
 use log::{debug, error, info, warn};
@@ -373,7 +372,7 @@ fn remove_repeated_calcs(
         thread
             .calcs
             .iter_mut()
-            .find(|(c, _)| c == calc)
+            .find(|(c, _)| c.cmp_calculation(calc).is_eq())
             .map(|(_, n)| {
                 *n += 1;
                 *n < MAX_REPETITIONS_PER_THREAD
@@ -384,15 +383,19 @@ fn remove_repeated_calcs(
         thread
             .calcs
             .iter()
-            .any(|(c, n)| c == calc && *n + 1 == MAX_REPETITIONS_PER_THREAD)
+            .any(|(c, n)| c.cmp_calculation(calc).is_eq() && *n + 1 == MAX_REPETITIONS_PER_THREAD)
     });
 
     thread
         .calcs
         .extend(comment.calculation_list.iter().map(|x| (x.clone(), 0)));
-    thread.calcs.sort_unstable();
+    thread
+        .calcs
+        .sort_unstable_by(|a, b| a.0.cmp_calculation(&b.0).then(a.1.cmp(&b.1)));
     thread.calcs.reverse();
-    thread.calcs.dedup_by(|a, b| a.0 == b.0);
+    thread
+        .calcs
+        .dedup_by(|a, b| a.0.cmp_calculation(&b.0).is_eq());
 
     thread_calcs.push(thread);
     true
@@ -429,6 +432,14 @@ fn get_sub_entries() -> HashMap<&'static str, SubredditEntry> {
                                 "no_note" => Commands::NO_NOTE,
                                 "nested" => Commands::NESTED,
                                 "write_out" => Commands::WRITE_OUT,
+                                "factorion" => Commands::FACTORION,
+                                "digits" => Commands::DIGITS,
+                                "skip_code" => Commands::SKIP_CODE,
+                                "skip_quotes" => Commands::SKIP_QUOTES,
+                                "filter_code" => Commands::FILTER_CODE,
+                                "numerals" => Commands::NUMERALS,
+                                "functions" => Commands::FUNCTIONS,
+                                "detect_language" => Commands::DETECT_LANGUAGE,
                                 "post_only" => {
                                     if mode != SubredditMode::None {
                                         mode = SubredditMode::PostOnly;
@@ -456,6 +467,9 @@ fn get_consts() -> Consts<'static> {
         float_precision: std::env::var("FLOAT_PRECISION")
             .map(|s| s.parse().unwrap())
             .unwrap_or_else(|_| factorion_lib::recommended::FLOAT_PRECISION),
+        max_float_precision: std::env::var("MAX_FLOAT_PRECISION")
+            .map(|s| s.parse().unwrap())
+            .unwrap_or_else(|_| factorion_lib::recommended::MAX_FLOAT_PRECISION),
         upper_calculation_limit: std::env::var("UPPER_CALCULATION_LIMIT")
             .map(|s| s.parse().unwrap())
             .unwrap_or_else(|_| factorion_lib::recommended::UPPER_CALCULATION_LIMIT()),
@@ -465,12 +479,18 @@ fn get_consts() -> Consts<'static> {
         upper_subfactorial_limit: std::env::var("UPPER_SUBFACTORIAL_LIMIT")
             .map(|s| s.parse().unwrap())
             .unwrap_or_else(|_| factorion_lib::recommended::UPPER_SUBFACTORIAL_LIMIT()),
+        upper_combinatorial_limit: std::env::var("UPPER_COMBINATORIAL_LIMIT")
+            .map(|s| s.parse().unwrap())
+            .unwrap_or_else(|_| factorion_lib::recommended::UPPER_COMBINATORIAL_LIMIT()),
         upper_termial_limit: std::env::var("UPPER_TERMIAL_LIMIT")
             .map(|s| Integer::u64_pow_u64(10, s.parse().unwrap()).complete())
             .unwrap_or_else(|_| factorion_lib::recommended::UPPER_TERMIAL_LIMIT()),
         upper_termial_approximation_limit: std::env::var("UPPER_TERMIAL_APPROXIMATION_LIMIT")
             .map(|s| s.parse().unwrap())
             .unwrap_or_else(|_| factorion_lib::recommended::UPPER_TERMIAL_APPROXIMATION_LIMIT),
+        upper_tower_depth_limit: std::env::var("UPPER_TOWER_DEPTH_LIMIT")
+            .map(|s| s.parse().unwrap())
+            .unwrap_or_else(|_| factorion_lib::recommended::UPPER_TOWER_DEPTH_LIMIT()),
         integer_construction_limit: std::env::var("INTEGER_CONSTRUCTION_LIMIT")
             .map(|s| s.parse().unwrap())
             .unwrap_or_else(|_| factorion_lib::recommended::INTEGER_CONSTRUCTION_LIMIT()),
@@ -480,21 +500,18 @@ fn get_consts() -> Consts<'static> {
         locales: std::env::var("LOCALES_DIR")
             .map(|dir| {
                 let files = std::fs::read_dir(dir).unwrap();
-                let mut map = HashMap::new();
-                for (key, value) in files
+                // Variants (like de_AT) only have what is different from their base locale, so they are merged first
+                let jsons = files
                     .map(|file| {
                         let file = file.unwrap();
-                        let locale: Locale<'static> = serde_json::de::from_str(
-                            std::fs::read_to_string(file.path()).unwrap().leak(),
+                        let json = serde_json::de::from_str(
+                            &std::fs::read_to_string(file.path()).unwrap(),
                         )
                         .unwrap();
-                        (file.file_name().into_string().unwrap(), locale)
+                        (file.file_name().into_string().unwrap(), json)
                     })
-                    .collect::<Box<_>>()
-                {
-                    map.insert(key, value);
-                }
-                map
+                    .collect::<HashMap<_, _>>();
+                factorion_lib::locale::from_jsons(&jsons).unwrap()
             })
             .unwrap_or_else(|_| {
                 factorion_lib::locale::get_all()
@@ -502,6 +519,7 @@ fn get_consts() -> Consts<'static> {
                     .collect()
             }),
         default_locale: "en".to_owned(),
+        language_profiles: Default::default(),
     }
 }
 
@@ -581,13 +599,15 @@ fn read_thread_calcs() -> Vec<Thread> {
     postcard::from_bytes(&file).expect("Malformed thread_calcs file")
 }
 
+#[allow(clippy::await_holding_lock)]
 #[cfg(test)]
 mod test {
     use std::collections::{HashMap, HashSet};
 
     use chrono::Utc;
     use factorion_lib::{
-        CalculationJob, Commands, Comment, Consts, calculation_tasks::CalculationBase,
+        CalculationJob, Commands, Comment, Consts,
+        calculation_tasks::{CalculationBase, Operation, Span},
         comment::Status,
     };
     use reqwest::Client;
@@ -609,7 +629,7 @@ mod test {
         unsafe {
             std::env::set_var(
                 "SUBREDDITS",
-                "somesub:en+othersub:ru+customsub:en:shorten+allsub:de:shorten,termial,no_note,steps,post_only,dont_check,nested,write_out+postsub:en:post_only",
+                "somesub:en+othersub:ru+customsub:en:shorten+allsub:de:shorten,termial,no_note,steps,post_only,dont_check,nested,write_out,factorion,digits,skip_code,skip_quotes,filter_code,numerals,functions,detect_language+postsub:en:post_only",
             );
             std::env::remove_var("SUBREDDITS_FILE");
         }
@@ -645,7 +665,8 @@ mod test {
                     "allsub",
                     SubredditEntry {
                         locale: "de",
-                        commands: !Commands::NONE,
+                        // why is only set per comment
+                        commands: !Commands::WHY,
                         mode: SubredditMode::None
                     }
                 ),
@@ -685,8 +706,9 @@ mod test {
                 (
                     CalculationJob {
                         base: CalculationBase::Num(5.into()),
-                        level: 1,
+                        operation: Operation::Factorial(1),
                         negative: 0,
+                        span: Default::default(),
                     },
                     3,
                 ),
@@ -694,11 +716,13 @@ mod test {
                     CalculationJob {
                         base: CalculationBase::Calc(Box::new(CalculationJob {
                             base: CalculationBase::Num(0.into()),
-                            level: -1,
+                            operation: Operation::Factorial(-1),
                             negative: 10,
+                            span: Default::default(),
                         })),
-                        level: 1,
+                        operation: Operation::Factorial(1),
                         negative: 0,
+                        span: Default::default(),
                     },
                     MAX_REPETITIONS_PER_THREAD,
                 ),
@@ -710,8 +734,9 @@ mod test {
             comment.calculation_list,
             [(CalculationJob {
                 base: CalculationBase::Num(5.into()),
-                level: 1,
-                negative: 0
+                operation: Operation::Factorial(1),
+                negative: 0,
+                span: Span::new(13, 15),
             })]
         );
         assert_eq!(
@@ -720,8 +745,9 @@ mod test {
                 (
                     CalculationJob {
                         base: CalculationBase::Num(5.into()),
-                        level: 1,
+                        operation: Operation::Factorial(1),
                         negative: 0,
+                        span: Default::default(),
                     },
                     4,
                 ),
@@ -729,11 +755,13 @@ mod test {
                     CalculationJob {
                         base: CalculationBase::Calc(Box::new(CalculationJob {
                             base: CalculationBase::Num(0.into()),
-                            level: -1,
+                            operation: Operation::Factorial(-1),
                             negative: 10,
+                            span: Default::default(),
                         })),
-                        level: 1,
+                        operation: Operation::Factorial(1),
                         negative: 0,
+                        span: Default::default(),
                     },
                     MAX_REPETITIONS_PER_THREAD + 1,
                 ),
@@ -747,8 +775,9 @@ mod test {
             comment.calculation_list,
             [(CalculationJob {
                 base: CalculationBase::Num(10.into()),
-                level: 1,
-                negative: 0
+                operation: Operation::Factorial(1),
+                negative: 0,
+                span: Span::new(5, 8),
             })]
         );
         assert_eq!(
@@ -757,8 +786,9 @@ mod test {
                 (
                     CalculationJob {
                         base: CalculationBase::Num(5.into()),
-                        level: 1,
+                        operation: Operation::Factorial(1),
                         negative: 0,
+                        span: Default::default(),
                     },
                     4,
                 ),
@@ -766,19 +796,22 @@ mod test {
                     CalculationJob {
                         base: CalculationBase::Calc(Box::new(CalculationJob {
                             base: CalculationBase::Num(0.into()),
-                            level: -1,
+                            operation: Operation::Factorial(-1),
                             negative: 10,
+                            span: Default::default(),
                         })),
-                        level: 1,
+                        operation: Operation::Factorial(1),
                         negative: 0,
+                        span: Default::default(),
                     },
                     MAX_REPETITIONS_PER_THREAD + 1,
                 ),
                 (
                     CalculationJob {
                         base: CalculationBase::Num(10.into()),
-                        level: 1,
+                        operation: Operation::Factorial(1),
                         negative: 0,
+                        span: Span::new(5, 8),
                     },
                     0,
                 ),
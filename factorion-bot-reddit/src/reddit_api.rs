@@ -30,6 +30,7 @@ struct TokenResponse {
 
 pub struct Token {
     pub access_token: String,
+    #[cfg_attr(test, allow(dead_code))]
     pub expiration_time: DateTime<Utc>,
 }
 
@@ -409,6 +410,7 @@ impl RedditClient {
         current_reset_timer
     }
 
+    #[cfg_attr(test, allow(dead_code))]
     fn is_token_expired(&self) -> bool {
         let now = Utc::now() + TimeDelta::seconds(1);
         now > self.token.expiration_time
@@ -762,7 +764,7 @@ impl RedditClient {
                     continue;
                 }
             };
-            let Some(extracted_comment) = extracted_comment else {
+            let Some(mut extracted_comment) = extracted_comment else {
                 continue;
             };
             if is_mention
@@ -780,6 +782,8 @@ impl RedditClient {
                         extracted_comment.meta.author.clone(),
                     ),
                 ));
+                // The parent explains why, not the summon itself
+                extracted_comment.commands.why = false;
             }
             comments.push(extracted_comment);
         }
@@ -797,6 +801,7 @@ impl RedditClient {
             id,
         ))
     }
+    #[allow(clippy::too_many_arguments)]
     fn extract_comment(
         comment: &Value,
         already_replied_to_comments: &mut Vec<DenseId>,
@@ -865,6 +870,9 @@ impl RedditClient {
                 comment.commands = *commands;
                 comment.notify = Some(format!("u/{author}"));
                 comment.meta.author = mention_author.clone();
+            } else if do_termial {
+                // Summoned, so even without a known command, the ones in brackets are meant for the bot
+                comment.unknown_commands = Commands::unknown_from_comment_text(&body);
             }
 
             comment.add_status(Status::NOT_REPLIED);
@@ -968,6 +976,7 @@ pub mod tests {
     use factorion_lib::{
         Consts,
         calculation_results::{Calculation, CalculationResult, Number},
+        calculation_tasks::{Operation, Span},
     };
 
     use super::*;
@@ -1134,7 +1143,10 @@ pub mod tests {
         assert_eq!(comments[0].commands, Commands::TERMIAL);
         assert_eq!(
             comments[0].calculation_list[0].steps,
-            [(1, false), (-1, false)]
+            [
+                (Operation::Factorial(1), false),
+                (Operation::Factorial(-1), false)
+            ]
         );
         assert_eq!(rate, (170.0, 7.0))
     }
@@ -1312,24 +1324,27 @@ pub mod tests {
             comments[0].calculation_list,
             [Calculation {
                 value: Number::Exact(1.into()),
-                steps: vec![(2, false)],
-                result: CalculationResult::Exact(1.into())
+                steps: vec![(Operation::Factorial(2), false)],
+                result: CalculationResult::Exact(1.into()),
+                span: Span::new(22, 25),
             }]
         );
         assert_eq!(
             comments[1].calculation_list,
             [Calculation {
                 value: Number::Exact(2.into()),
-                steps: vec![(1, false)],
-                result: CalculationResult::Exact(2.into())
+                steps: vec![(Operation::Factorial(1), false)],
+                result: CalculationResult::Exact(2.into()),
+                span: Span::new(0, 2),
             }]
         );
         assert_eq!(
             comments[2].calculation_list,
             [Calculation {
                 value: Number::Exact(10.into()),
-                steps: vec![(0, false)],
-                result: CalculationResult::Exact(1334961.into())
+                steps: vec![(Operation::Factorial(0), false)],
+                result: CalculationResult::Exact(1334961.into()),
+                span: Span::new(10, 13),
             }]
         );
         println!("{comments:?}");
@@ -1,12 +1,14 @@
 //! This module handles the formatting of the calculations (`The factorial of Subfactorial of 5 is`, etc.)
 
+use crate::calculation_tasks::{Operation, Span};
 use crate::format::{
-    format_approximate, format_approximate_digits, format_approximate_digits_tower,
-    format_complex_infinity, format_exact, format_float, get_factorial_level_string, replace,
+    format_approximate, format_approximate_digits, format_approximate_digits_hyper,
+    format_approximate_digits_tower, format_complex_infinity, format_digit_factorial_chain,
+    format_exact, format_float, get_operation_string, get_write_out, radix_string, replace,
 };
 use crate::impl_all_bitwise;
 use crate::impl_bitwise;
-use factorion_math::length;
+use factorion_math::{factorial_trailing_zeros, length};
 #[cfg(any(feature = "serde", test))]
 use serde::{Deserialize, Serialize};
 use std::ops::BitAnd;
@@ -59,6 +61,9 @@ impl fmt::Debug for CalculationResult {
                     truncate(n)
                 )
             }
+            CalculationResult::ApproximateDigitsHyper(i, a, n) => {
+                write!(f, "ApproximateDigitsHyper({}, {}, {})", i, a, truncate(n))
+            }
             CalculationResult::Float(of) => write!(f, "Float({})", truncate(&of.as_float())),
             CalculationResult::ComplexInfinity => write!(f, "ComplexInfinity"),
         }
@@ -76,6 +81,8 @@ pub enum CalculationResult {
     ApproximateDigits(bool, Integer),
     /// (^(c)10)^d digits (a is whether is negative, b is negative number of digits (super small))
     ApproximateDigitsTower(bool, bool, Integer, Integer),
+    /// Between 10↑...↑c and 10↑...↑(c+1) digits with b arrows (a is whether the number is negative)
+    ApproximateDigitsHyper(bool, u32, Integer),
     Float(OrdFloat),
     ComplexInfinity,
 }
@@ -88,9 +95,9 @@ impl Number {
         match self {
             Self::Approximate(x, _) | Self::Float(x) => x.as_float_mut().neg_assign(),
             Self::Exact(n) => n.neg_assign(),
-            Self::ApproximateDigitsTower(n, _, _, _) | Self::ApproximateDigits(n, _) => {
-                n.not_assign()
-            }
+            Self::ApproximateDigitsTower(n, _, _, _)
+            | Self::ApproximateDigitsHyper(n, _, _)
+            | Self::ApproximateDigits(n, _) => n.not_assign(),
             Self::ComplexInfinity => {}
         }
     }
@@ -99,7 +106,8 @@ impl Number {
             CalculationResult::Exact(n)
             | CalculationResult::ApproximateDigits(_, n)
             | CalculationResult::Approximate(_, n)
-            | CalculationResult::ApproximateDigitsTower(_, _, _, n) => n,
+            | CalculationResult::ApproximateDigitsTower(_, _, _, n)
+            | CalculationResult::ApproximateDigitsHyper(_, _, n) => n,
             CalculationResult::Float(_) | CalculationResult::ComplexInfinity => return false,
         };
         n > too_big_number
@@ -127,11 +135,20 @@ pub struct FormatOptions {
     pub force_shorten: bool,
     pub agressive_shorten: bool,
     pub write_out: bool,
+    /// The naming system for large numbers when writing out, instead of the locale's
+    pub naming: Option<locale::NamingSystem>,
+    /// The style of scientific notation, instead of the locale's
+    pub scientific: Option<locale::ScientificStyle>,
+    /// The base to write exact numbers in, instead of decimal
+    pub base: Option<u32>,
 }
 impl_all_bitwise!(FormatOptions {
     force_shorten,
     agressive_shorten,
-    write_out,
+    write_out;
+    naming,
+    scientific,
+    base,
 });
 #[allow(dead_code)]
 impl FormatOptions {
@@ -139,6 +156,9 @@ impl FormatOptions {
         force_shorten: false,
         agressive_shorten: false,
         write_out: false,
+        naming: None,
+        scientific: None,
+        base: None,
     };
     pub const FORCE_SHORTEN: Self = Self {
         force_shorten: true,
@@ -164,32 +184,72 @@ impl CalculationResult {
         opts: FormatOptions,
         is_value: bool,
         consts: &Consts,
-        locale: &locale::NumFormat,
+        locale: &locale::Format<'_>,
     ) -> std::fmt::Result {
-        let mut start = acc.len();
+        let start = acc.len();
+        let number_format = &locale.number_format;
+        let scientific = opts.scientific.or(number_format.scientific);
+        let opts = FormatOptions {
+            // The number calculated with reads better in full (`3000`, not `3 k`), so it gets no SI prefix
+            scientific: if is_value && scientific == Some(locale::ScientificStyle::Si) {
+                Some(locale::ScientificStyle::Engineering)
+            } else {
+                scientific
+            },
+            ..opts
+        };
+        let write_out = opts
+            .write_out
+            .then(|| get_write_out(locale, consts))
+            .flatten();
         match &self {
-            CalculationResult::Exact(factorial) => {
-                format_exact(acc, rough, &opts, consts, factorial)?
-            }
+            CalculationResult::Exact(factorial) => format_exact(
+                acc,
+                rough,
+                &opts,
+                consts,
+                number_format,
+                write_out,
+                factorial,
+            )?,
             CalculationResult::Approximate(base, exponent) => {
-                format_approximate(acc, &opts, consts, base, exponent)?
+                format_approximate(acc, &opts, consts, number_format, base, exponent)?
             }
             CalculationResult::ApproximateDigits(_, digits) => {
-                format_approximate_digits(acc, &opts, is_value, consts, digits)?
+                format_approximate_digits(acc, &opts, is_value, consts, write_out, digits)?
             }
             CalculationResult::ApproximateDigitsTower(_, negative, depth, exponent) => {
                 format_approximate_digits_tower(
                     acc, &opts, is_value, consts, negative, depth, exponent,
                 )?
             }
-            CalculationResult::Float(gamma) => format_float(acc, gamma.as_float(), consts)?,
-            CalculationResult::ComplexInfinity => format_complex_infinity(acc, opts)?,
-        }
-        if locale.decimal != '.' {
-            let decimal = locale.decimal.to_string();
-            while start < acc.len() {
-                start = replace(acc, start, ".", &decimal);
+            CalculationResult::ApproximateDigitsHyper(negative, arrows, height) => {
+                format_approximate_digits_hyper(
+                    acc, &opts, is_value, consts, negative, arrows, height,
+                )?
             }
+            CalculationResult::Float(gamma) => format_float(
+                acc,
+                gamma.as_float(),
+                consts,
+                number_format,
+                opts.scientific.unwrap_or_default(),
+            )?,
+            CalculationResult::ComplexInfinity => format_complex_infinity(acc, write_out)?,
+        }
+        // Localize in one pass, as the group separator may be the canonical decimal (or the other way around)
+        let group = number_format.group_separator();
+        if number_format.decimal != '.' || group.is_some() {
+            let localized = acc[start..]
+                .chars()
+                .map(|c| match (c, group) {
+                    ('.', _) => number_format.decimal,
+                    (',', Some(group)) => group,
+                    (c, _) => c,
+                })
+                .collect::<String>();
+            acc.truncate(start);
+            acc.push_str(&localized);
         }
         Ok(())
     }
@@ -200,12 +260,19 @@ impl CalculationResult {
 pub struct Calculation {
     /// The base number
     pub value: Number,
-    /// Steps taken during calculation (level, negation)
-    pub steps: Vec<(i32, bool)>,
+    /// Steps taken during calculation (operation, negation)
+    pub steps: Vec<(Operation, bool)>,
     pub result: CalculationResult,
+    /// Where the last step was written in the parsed text
+    #[cfg_attr(any(feature = "serde", test), serde(default))]
+    pub span: Span,
 }
 
 impl Calculation {
+    /// Compares what was calculated, ignoring where it was written (the span), so the same calculation can be deduplicated.
+    pub fn cmp_calculation(&self, other: &Self) -> std::cmp::Ordering {
+        (&self.value, &self.steps, &self.result).cmp(&(&other.value, &other.steps, &other.result))
+    }
     pub fn is_digit_tower(&self) -> bool {
         matches!(
             self,
@@ -215,6 +282,15 @@ impl Calculation {
             }
         )
     }
+    pub fn is_digit_hyper(&self) -> bool {
+        matches!(
+            self,
+            Calculation {
+                result: CalculationResult::ApproximateDigitsHyper(_, _, _),
+                ..
+            }
+        )
+    }
     pub fn is_aproximate_digits(&self) -> bool {
         matches!(
             self,
@@ -282,6 +358,26 @@ impl Calculation {
         locale: &locale::Format<'_>,
     ) -> Result<(), std::fmt::Error> {
         let frame_start = acc.len();
+        let base = self.number_format_options(&options, too_big_number).base;
+        // Shortened, both bounds may be written the same, so then only the order is given
+        let same_bounds = match &self.result {
+            CalculationResult::ApproximateDigitsHyper(negative, arrows, height) => {
+                let bound = |height: Integer| {
+                    let mut bound = String::new();
+                    CalculationResult::ApproximateDigitsHyper(*negative, *arrows, height).format(
+                        &mut bound,
+                        &mut false,
+                        self.number_format_options(&options, too_big_number),
+                        false,
+                        consts,
+                        locale,
+                    )?;
+                    Ok::<_, fmt::Error>(bound)
+                };
+                bound(height.clone())? == bound(height.clone() + 1)?
+            }
+            _ => false,
+        };
         acc.write_str(
             match (
                 &self.value,
@@ -290,8 +386,25 @@ impl Calculation {
             ) {
                 // All that
                 (_, _, true) => &locale.all_that,
+                // modulo
+                (_, CalculationResult::Exact(_), _)
+                    if matches!(self.steps.last(), Some((Operation::FactorialModulo(_), _))) =>
+                {
+                    locale.modulo.as_ref().map_or(
+                        "{factorial} of {number} modulo {operand} is {result}",
+                        AsRef::as_ref,
+                    )
+                }
+                // between
+                (_, CalculationResult::ApproximateDigitsHyper(_, _, _), _) if !same_bounds => {
+                    locale.between.as_ref().map_or(
+                        "{factorial} of {number} has between {result} and {upper} digits",
+                        AsRef::as_ref,
+                    )
+                }
                 // on the order
-                (_, CalculationResult::ApproximateDigitsTower(_, _, _, _), _) => &locale.order,
+                (_, CalculationResult::ApproximateDigitsTower(_, _, _, _), _)
+                | (_, CalculationResult::ApproximateDigitsHyper(_, _, _), _) => &locale.order,
                 // digits
                 (_, CalculationResult::ApproximateDigits(_, _), _) => &locale.digits,
                 // approximately
@@ -302,6 +415,11 @@ impl Calculation {
                 _ => &locale.exact,
             },
         )?;
+        if let (Some((Operation::DigitFactorialSum, _)), CalculationResult::Exact(sum)) =
+            (self.steps.last(), &self.result)
+        {
+            format_digit_factorial_chain(acc, sum)?;
+        }
         acc.write_str(" \n\n")?;
 
         self.format_number(
@@ -321,7 +439,7 @@ impl Calculation {
             &self.result,
             acc,
             &FormatNumberOptions {
-                format_opts: options,
+                format_opts: options.clone(),
                 frame_start,
                 is_value: false,
             },
@@ -329,8 +447,25 @@ impl Calculation {
             consts,
             locale,
         )?;
+        if let CalculationResult::ApproximateDigitsHyper(negative, arrows, height) = &self.result
+            && !same_bounds
+        {
+            replace(acc, frame_start, "{upper}", "{number}");
+            self.format_number(
+                &CalculationResult::ApproximateDigitsHyper(*negative, *arrows, height.clone() + 1),
+                acc,
+                &FormatNumberOptions {
+                    format_opts: options,
+                    frame_start,
+                    is_value: false,
+                },
+                too_big_number,
+                consts,
+                locale,
+            )?;
+        }
 
-        self.format_operations(acc, locale, frame_start);
+        self.format_operations(acc, locale, frame_start, base);
         let mut ind = acc[frame_start..].char_indices();
         if let Some((start, _)) = ind.next()
             && let Some((end, _)) = ind.next()
@@ -341,15 +476,114 @@ impl Calculation {
         Ok(())
     }
 
-    fn format_operations(&self, acc: &mut String, locale: &locale::Format<'_>, frame_start: usize) {
+    /// Formats statistics about the digits of the result, if any can be given. \
+    /// Exact results get their digit sum, digital root, count of each digit, trailing zeros and whether they are a palindrome. \
+    /// Approximate factorials of integers get their trailing zeros (Legendre) and digital root.
+    pub fn format_digit_stats(
+        &self,
+        acc: &mut String,
+        consts: &Consts,
+        locale: &locale::Format<'_>,
+    ) -> Result<(), std::fmt::Error> {
+        let frame_start = acc.len();
+        match &self.result {
+            CalculationResult::Exact(n) => {
+                let digits = n.to_string_radix(10);
+                let digits = digits.trim_start_matches('-');
+                let mut counts = [0u64; 10];
+                for digit in digits.bytes() {
+                    counts[(digit - b'0') as usize] += 1;
+                }
+                let sum = counts
+                    .iter()
+                    .enumerate()
+                    .map(|(digit, count)| digit as u64 * count)
+                    .sum::<u64>();
+                let root = if sum == 0 { 0 } else { 1 + (sum - 1) % 9 };
+                let zeros = if *n == 0 {
+                    0
+                } else {
+                    digits.len() - digits.trim_end_matches('0').len()
+                };
+                let mut counts_text = String::new();
+                for (digit, count) in counts.iter().enumerate() {
+                    if digit != 0 {
+                        counts_text.push_str(", ");
+                    }
+                    write!(counts_text, "{digit}×{count}")?;
+                }
+                acc.write_str(locale.digit_stats.as_ref().map(AsRef::as_ref).unwrap_or(
+                    "The digits of the {factorial} of {number} sum to {sum} with digital root {root}, it ends in {zeros} zeros and has the digits {counts}",
+                ))?;
+                replace(acc, frame_start, "{sum}", &sum.to_string());
+                replace(acc, frame_start, "{root}", &root.to_string());
+                replace(acc, frame_start, "{zeros}", &zeros.to_string());
+                replace(acc, frame_start, "{counts}", &counts_text);
+                if digits.len() > 1 && digits.bytes().eq(digits.bytes().rev()) {
+                    acc.write_str(". ")?;
+                    acc.write_str(
+                        locale
+                            .digit_palindrome
+                            .as_ref()
+                            .map(AsRef::as_ref)
+                            .unwrap_or("It is a palindrome!"),
+                    )?;
+                }
+            }
+            CalculationResult::Approximate(_, _) | CalculationResult::ApproximateDigits(_, _) => {
+                // Only plain factorials of integers have known trailing zeros
+                let (Number::Exact(n), [(Operation::Factorial(1), false)]) =
+                    (&self.value, self.steps.as_slice())
+                else {
+                    return Ok(());
+                };
+                if *n < 0 {
+                    return Ok(());
+                }
+                acc.write_str(locale.digit_zeros.as_ref().map(AsRef::as_ref).unwrap_or(
+                    "The {factorial} of {number} ends in {zeros} zeros and has digital root {root}",
+                ))?;
+                replace(
+                    acc,
+                    frame_start,
+                    "{zeros}",
+                    &factorial_trailing_zeros(n).to_string(),
+                );
+                // All factorials from 6! on are divisible by 9
+                replace(acc, frame_start, "{root}", "9");
+            }
+            _ => return Ok(()),
+        }
+        let mut number = String::new();
+        self.value.format(
+            &mut number,
+            &mut false,
+            FormatOptions::FORCE_SHORTEN,
+            true,
+            consts,
+            locale,
+        )?;
+        replace(acc, frame_start, "{number}", &number);
+        self.format_operations(acc, locale, frame_start, None);
+        acc.write_str(" \n\n")
+    }
+
+    /// Writes the operations into the template, with their operands in base (if any)
+    fn format_operations(
+        &self,
+        acc: &mut String,
+        locale: &locale::Format<'_>,
+        frame_start: usize,
+        base: Option<u32>,
+    ) {
         let len = self.steps.len();
         let mut start = frame_start;
-        for (i, (level, neg)) in self.steps.iter().copied().rev().enumerate() {
+        for (i, (operation, neg)) in self.steps.iter().rev().enumerate() {
             if i != len - 1 {
                 replace(acc, start, "{factorial}", &locale.nest);
             }
 
-            if neg {
+            if *neg {
                 replace(acc, start, "{factorial}", "negative {factorial}");
             }
 
@@ -357,14 +591,22 @@ impl Calculation {
                 acc,
                 start,
                 "{factorial}",
-                &get_factorial_level_string(level.abs(), locale),
+                &get_operation_string(operation, locale),
             );
+            // Each step has its own operand, the modulus is in the template right after the step
+            if let Some(operand) = operation.operand() {
+                let operand = match base {
+                    Some(base) => radix_string(operand, base),
+                    None => operand.to_string(),
+                };
+                replace(acc, calc_start, "{operand}", &operand);
+            }
 
             replace(
                 acc,
                 start,
                 "{factorial}",
-                if level < 0 {
+                if matches!(operation, Operation::Factorial(..0)) {
                     &locale.termial
                 } else {
                     &locale.factorial
@@ -385,6 +627,25 @@ impl Calculation {
         }
     }
 
+    /// The options numbers are formatted with, shortened if the result is too long. \
+    /// Only exact results, that are written in full, are in another base, so the calculation is never in mixed bases.
+    fn number_format_options(
+        &self,
+        options: &FormatOptions,
+        too_big_number: &Integer,
+    ) -> FormatOptions {
+        let force_shorten = options.force_shorten
+            || self.result.is_too_long(too_big_number)
+            || options.agressive_shorten;
+        FormatOptions {
+            force_shorten,
+            base: options.base.filter(|base| {
+                *base != 10 && !force_shorten && matches!(self.result, CalculationResult::Exact(_))
+            }),
+            ..options.clone()
+        }
+    }
+
     fn format_number(
         &self,
         num: &Number,
@@ -399,15 +660,10 @@ impl Calculation {
         num.format(
             &mut number,
             &mut rough,
-            FormatOptions {
-                force_shorten: options.format_opts.force_shorten
-                    || self.result.is_too_long(too_big_number)
-                    || options.format_opts.agressive_shorten,
-                ..options.format_opts
-            },
+            self.number_format_options(&options.format_opts, too_big_number),
             options.is_value,
             consts,
-            &locale.number_format,
+            locale,
         )?;
         if rough {
             replace(acc, options.frame_start, "{number}", &locale.rough_number);
@@ -434,8 +690,9 @@ mod test {
         let mut acc = String::new();
         let factorial = Calculation {
             value: 5.into(),
-            steps: vec![(1, false)],
+            steps: vec![(Operation::Factorial(1), false)],
             result: CalculationResult::Exact(Integer::from(120)),
+            span: Default::default(),
         };
         factorial
             .format(
@@ -451,8 +708,9 @@ mod test {
         let mut acc = String::new();
         let factorial = Calculation {
             value: 5.into(),
-            steps: vec![(0, false)],
+            steps: vec![(Operation::Factorial(0), false)],
             result: CalculationResult::Exact(Integer::from(120)),
+            span: Default::default(),
         };
         factorial
             .format(
@@ -468,11 +726,12 @@ mod test {
         let mut acc = String::new();
         let factorial = Calculation {
             value: 5.into(),
-            steps: vec![(1, false)],
+            steps: vec![(Operation::Factorial(1), false)],
             result: CalculationResult::Approximate(
                 Float::with_val(FLOAT_PRECISION, Float::parse("1.2").unwrap()).into(),
                 5.into(),
             ),
+            span: Default::default(),
         };
         factorial
             .format(
@@ -488,8 +747,9 @@ mod test {
         let mut acc = String::new();
         let factorial = Calculation {
             value: 5.into(),
-            steps: vec![(1, false)],
+            steps: vec![(Operation::Factorial(1), false)],
             result: CalculationResult::ApproximateDigits(false, 3.into()),
+            span: Default::default(),
         };
         factorial
             .format(
@@ -505,8 +765,9 @@ mod test {
         let mut acc = String::new();
         let factorial = Calculation {
             value: 5.into(),
-            steps: vec![(1, false)],
+            steps: vec![(Operation::Factorial(1), false)],
             result: CalculationResult::Exact(Integer::from(120)),
+            span: Default::default(),
         };
         factorial
             .format(
@@ -522,8 +783,9 @@ mod test {
         let consts = Consts::default();
         let fact = Calculation {
             value: 10.into(),
-            steps: vec![(3, false)],
+            steps: vec![(Operation::Factorial(3), false)],
             result: CalculationResult::Exact(280.into()),
+            span: Default::default(),
         };
         let mut s = String::new();
         fact.format(
@@ -541,8 +803,9 @@ mod test {
         let consts = Consts::default();
         let fact = Calculation {
             value: Number::Float(Float::with_val(FLOAT_PRECISION, 0.5).into()),
-            steps: vec![(3, false)],
+            steps: vec![(Operation::Factorial(3), false)],
             result: CalculationResult::Exact(280.into()),
+            span: Default::default(),
         };
         let mut s = String::new();
         fact.format(
@@ -556,12 +819,56 @@ mod test {
         assert_eq!(s, "Triple-factorial of 0.5 is approximately 280 \n\n");
     }
     #[test]
+    fn test_format_grouped() {
+        let consts = Consts::default();
+        let mut locale = consts.locales.get("de").unwrap().format.clone();
+        locale.number_format.group = Some('.');
+        let mut acc = String::new();
+        CalculationResult::Exact(3628800.into())
+            .format(
+                &mut acc,
+                &mut false,
+                FormatOptions::NONE,
+                false,
+                &consts,
+                &locale,
+            )
+            .unwrap();
+        assert_eq!(acc, "3.628.800");
+        acc.clear();
+        CalculationResult::Float(Float::with_val(FLOAT_PRECISION, 12345.5).into())
+            .format(
+                &mut acc,
+                &mut false,
+                FormatOptions::NONE,
+                false,
+                &consts,
+                &locale,
+            )
+            .unwrap();
+        assert_eq!(acc, "12.345,5");
+        locale.number_format.narrow_no_break_space = Some(true);
+        acc.clear();
+        CalculationResult::Exact(3628800.into())
+            .format(
+                &mut acc,
+                &mut false,
+                FormatOptions::NONE,
+                false,
+                &consts,
+                &locale,
+            )
+            .unwrap();
+        assert_eq!(acc, "3\u{202F}628\u{202F}800");
+    }
+    #[test]
     fn test_format_factorial_force_shorten_small() {
         let consts = Consts::default();
         let fact = Calculation {
             value: 10.into(),
-            steps: vec![(3, false)],
+            steps: vec![(Operation::Factorial(3), false)],
             result: CalculationResult::Exact(280.into()),
+            span: Default::default(),
         };
         let mut s = String::new();
         fact.format(
@@ -579,10 +886,11 @@ mod test {
         let consts = Consts::default();
         let fact = Calculation {
             value: 100.into(),
-            steps: vec![(1, false)],
+            steps: vec![(Operation::Factorial(1), false)],
             result: CalculationResult::Exact(
                 Integer::from_str("232019615953125000000000000000000").unwrap(),
             ),
+            span: Default::default(),
         };
         let mut s = String::new();
         fact.format(
@@ -603,10 +911,11 @@ mod test {
         let consts = Consts::default();
         let fact = Calculation {
             value: 3249.into(),
-            steps: vec![(1,false)],
+            steps: vec![(Operation::Factorial(1), false)],
             result: CalculationResult::Exact(
                 Integer::from_str("64123376882765521838840963030568127691878727205333658692200854486404915724268122521695176119279253635876611090137291969570276913721864797759577004121543081865516901512445483449601769965060634861857064173938704305418376606356891014609023859758096597956259938348528946750437026172549655426092377089294607836520057856104816993984697675759579496157280331714452191401635250556082973306115574519424960196953201395066132365440977075392087489735146885581823595966673458107135041749084983583726462930633422893526599365244406644257808664472819062579590372326362859263197427382391737724371130194668325697913147795807287917882271125437793075279592752221056089408917956641344121781056494896664298954714463291743622978314854242079926982168325256172879601086193725507405771749789801611825741625380077209528888301112734777086106637653242107578812065387025070985682845983714635115865868052531038040737170581029905537322341939002838113744745962782070030988628668438192063964391415488312555937962867645737183703289987989371752808444472206166983181218698452231772212240017445423758860236449146575513014084114116542491422920779703202877962388772371297148878539228082497149672927873860981295756607109411429871735683677151117763870227460722732815888175758276344884954699572217509595160880510811349033936358665103889507929390456055037630508759624182491412136058522758117862715726418213812122827526330257260872329993280938592007320434494018056858434839424498517707440601396194949605570023576625190771463278168007414358018195714385208103590743168343592988436427551751120123934640886569178657972642734992568217335134536548423867468448461752994160896483162496996197629537563875663545967947035030506174219867102227347745166308776568259737417457622753953177779829173739659562549005900681020920836575654282170728038645671253311902327576757877160190593437037925134089334990083104974051379653937615220306281104735360028696101767109606466502484676624025302461421267416025443536877684785195571046059926349413586237838043863850610251583618438829618642246353724734656122845609571531588284708710081901687161770748138296656576032229319208279032435434327330035540657667361558905445221013396376775953367966087790302411507662731788873698999846238792500590360394500083923341408008981770566937535640769993694293230514231436990415482012055539596871513163008100690298424743718490882019179903258642028365049142613374709689558800856050749214398290563852574062566904927777093160819034619946818734041081848355062039645388238813669985569729968236449074797273410844560761607809842265309788155248298117938165414543689689754240992067831705834383207309250573018855640140957274364918049364842508738871690383100660359882462072065885517245667353800113210423157317762013988734352812105163694758108035856505778854524789188318600594132430921277654972526820920812190785994887939816114878915385423211996897729890266102145491069991647131611614465930571202528403443141981609375073983780241828798986101030035167624885608168623694530984934856402415662119456280967778213695343026782085453754332973412779641743296676142192492849866399186979810426206090031375249707803725234273693273721779240257093247268647749842459507965336971004339619911629224227060334233904444450352505466038312828689977755744971204784911189528493222070017894145493878499832441010771999957866634720057779638435426615168763950876432375766350648344132624416041623318009761058787995614968607413528076499437020919653085121078341947075546317831737787160036257151637941590867306372647047747729689844801136819011517526975033214302293538465503160183447374945622710595033673253137034231320031041035890947260824330728621640030383790059199531556893062561713763583025693789382680375603227866194301270004745201382665157844733507781537231595412109690534099208802055220457258238249940538761563309465648945964188442431661762589082015016756223358648046396366827537498425276338958018446839292802529780142385903309447658806351362744163752044896322012923382835852429065564336560491610071025646451525782856813152304143339115660276089535216189729579966851236899105440783686498435516601131545345163557980985342246336986737955743799192164259513473592703473521185371309681754246866522812455448210758136891890444056252857117200446002038652603259983493405505521897860879586618028713025173570291196046254005672495787117170419665767607647184551353826735583363126537373726390620854105626900247296291639985561481625404296348051054604042180512892657285238147263167051884385297470314430200590079012539964786079859359747123150407661818942489735756835032462952010303051169237940063644470670372188286551571968317499183600768353941744706305961785518398629201507525785967571188931895809109770264983907551256060144219899670118351808815620474425273993244741972143504134827047237929839845492209316520698259428270901257484509899386082594602760813392081897348940617781009158927227690469330327639146118508499255466535663882163793101115885899345523332216762566667486023534622719542192198250458735391090024294254053186440646305309340840685145289223131431157156390489399333752075193525158125680201419183806547205312873264380358849214095835479613319512867197427682723250079990981586869733293245764804577570764831692705888317075918673294669326798053736223321604803330275717540789920865913177228227111643923604665959921096208765542277777829882980225810940866410254096689483571105776785837917708633884075471298045453873223073787369262426626913405098535070631297346400765749139515252242178612533747493270131589184346851060077512732273563896936880596142362061341020737937605198462006142952423931616201569440226926787162077801883794168906567939864710313203688516686488132607069944238278930371283198545637735863991249832218463680910774912311493673518088306563853170521159963238305666024221618323515872866318153226269712890565361382209276094137857215708859605439920538254391240145615109307534437972388439697355227469268959991826344643967606862639207957142695059497774782782862380576527665249011786632721781635858363134217267161265609789721847126531549373639397319541419174824349828634414533913160986280670700117904134971824878639490677063427559640621162799757094469987184056964512589036737188936656494184932005003301076625555129466247988108160104882718140259576746243025950653945267030862681712132414998384138315991964228278130346276982182371619123375659027762342810200791337975076096607162500887202849331840711439619934443487228446573730294798389422723901661778354768525095757656920903185278358954945675520361768231577076750321654682566951617894418024879897723932943778739392625374786945631297844013055183788373235917906391604745846654356151085578611880261515860397623972021392725059655970516681719822949498069366408864396412928494605832710960284204937215373010567096882590065428759248976242854170628853902061231484918006271406155707387649451852150396381227895427254475130432845540997751264574249884576973754475522081887586009543117655192564603663203594121977491966995919938707026254622729082886656923266824175261927609862131917883084745112234024557978747561458733390353402381353061864973111801478933098174668694254024372053350135966105816774315863351432700501507214833910835095241116220945368287364828423032249431110250529198415073098056537298790818802403747860478015395740166511031245261193793854201285682331906071528112005073514650997116494101706639070013374677115821301361236988511929513457351929738018793684759539098410509535113338894579685309152120362751957602730649344150813012563246391457667149097699631546631367291707994927436193366185835774355812730356484690902974319470019544218388669048171395399380611906621586431005917959473642252829970939300283923684023821586277795276767391621510747281802893209607052311085173753725616353413592446675522238914835135290803927878090361225614843018882327106532840756094139114333346621153175254833577042328095480536834801026590432360931424294133543336408702705440236553526213058195627059654976746315636170233701887454392139871178240463495036735780991998499617099173145932919728906603992606395026374552882029156921168342421270810263586384930758466962518032019544198713384832174173447126633137813741748004660781750992387224960402183367639878315847417040125065349322346833085734948541674565230896990919815801676540094611430605654337096768783494147476599630304276589463660992695730097812987784061106253993478908686689107637583574009574525664941872851644555317421340687668414081763994364249671165252652825318436095248164540239487724330276498957490699548343852181838068378612444949106850962864407345130509165857647406496109100001533123176834579856292423765079015513705518869769002090306548513909235083737585930276738943593954668225536658208962591163051195501324651032924378645456520478535714079874404144783894706654731307268880764144813567558473827034967105368425271973138213726718055181321006250745589786136935583735915890517993411416086214277469794370188740010736604373520529352427775875772577651690552630708696044935360500197728514057299685757816479040563926362665221456966339198099627395349937057349473111399655105587183432516687910987518148931239145857422059143761070545360054386871218955184209375241453611589548642653321253873363792347807426924575722280463634222994099258528815002881358362491008896204800000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000").unwrap()
             ),
+            span: Default::default(),
         };
         let mut s = String::new();
         fact.format(
@@ -627,8 +936,12 @@ mod test {
         let consts = Consts::default();
         let fact = Calculation {
             value: 5.into(),
-            steps: vec![(3, false), (1, false)],
+            steps: vec![
+                (Operation::Factorial(3), false),
+                (Operation::Factorial(1), false),
+            ],
             result: CalculationResult::Exact(3628800.into()),
+            span: Default::default(),
         };
         let mut s = String::new();
         fact.format(
@@ -642,12 +955,40 @@ mod test {
         assert_eq!(s, "Factorial of triple-factorial of 5 is 3628800 \n\n");
     }
     #[test]
+    fn test_format_operand_chain() {
+        let consts = Consts::default();
+        let fact = Calculation {
+            value: 3.into(),
+            steps: vec![
+                (Operation::FallingFactorial(2.into()), false),
+                (Operation::StirlingSecond(2.into()), false),
+                (Operation::FactorialModulo(7.into()), false),
+            ],
+            result: CalculationResult::Exact(0.into()),
+            span: Default::default(),
+        };
+        let mut s = String::new();
+        fact.format(
+            &mut s,
+            FormatOptions::NONE,
+            &TOO_BIG_NUMBER,
+            &consts,
+            &consts.locales.get("en").unwrap().format,
+        )
+        .unwrap();
+        assert_eq!(
+            s,
+            "Factorial of Stirling number of the second kind (k = 2) of falling factorial of order 2 of 3 modulo 7 is 0 \n\n"
+        );
+    }
+    #[test]
     fn test_format_factorial_negative() {
         let consts = Consts::default();
         let fact = Calculation {
             value: 0.into(),
-            steps: vec![(1, true)],
+            steps: vec![(Operation::Factorial(1), true)],
             result: CalculationResult::Exact(3628800.into()),
+            span: Default::default(),
         };
         let mut s = String::new();
         fact.format(
@@ -665,11 +1006,12 @@ mod test {
         let consts = Consts::default();
         let fact = Calculation {
             value: 0.into(),
-            steps: vec![(1, false)],
+            steps: vec![(Operation::Factorial(1), false)],
             result: CalculationResult::Approximate(
                 Float::with_val(FLOAT_PRECISION, Float::parse("2.83947").unwrap()).into(),
                 10043.into(),
             ),
+            span: Default::default(),
         };
         let mut s = String::new();
         fact.format(
@@ -687,8 +1029,9 @@ mod test {
         let consts = Consts::default();
         let fact = Calculation {
             value: 0.into(),
-            steps: vec![(1, false)],
+            steps: vec![(Operation::Factorial(1), false)],
             result: CalculationResult::ApproximateDigits(false, 10043394.into()),
+            span: Default::default(),
         };
         let mut s = String::new();
         fact.format(
@@ -702,12 +1045,70 @@ mod test {
         assert_eq!(s, "Factorial of 0 has approximately 10043394 digits \n\n");
     }
     #[test]
+    fn test_format_digit_stats() {
+        let consts = Consts::default();
+        let locale = &consts.locales.get("en").unwrap().format;
+        let fact = Calculation {
+            value: 10.into(),
+            steps: vec![(Operation::Factorial(1), false)],
+            result: CalculationResult::Exact(3628800.into()),
+            span: Default::default(),
+        };
+        let mut s = String::new();
+        fact.format_digit_stats(&mut s, &consts, locale).unwrap();
+        assert_eq!(
+            s,
+            "The digits of the factorial of 10 sum to 27 with digital root 9, it ends in 2 zeros and has the digits 0×2, 1×0, 2×1, 3×1, 4×0, 5×0, 6×1, 7×0, 8×2, 9×0 \n\n"
+        );
+        let fact = Calculation {
+            value: 11.into(),
+            steps: vec![(Operation::Factorial(-1), false)],
+            result: CalculationResult::Exact(66.into()),
+            span: Default::default(),
+        };
+        let mut s = String::new();
+        fact.format_digit_stats(&mut s, &consts, locale).unwrap();
+        assert_eq!(
+            s,
+            "The digits of the termial of 11 sum to 12 with digital root 3, it ends in 0 zeros and has the digits 0×0, 1×0, 2×0, 3×0, 4×0, 5×0, 6×2, 7×0, 8×0, 9×0. It is a palindrome! \n\n"
+        );
+        let fact = Calculation {
+            value: 100000.into(),
+            steps: vec![(Operation::Factorial(1), false)],
+            result: CalculationResult::Approximate(
+                Float::with_val(
+                    FLOAT_PRECISION,
+                    Float::parse("2.82422940796034787").unwrap(),
+                )
+                .into(),
+                456573.into(),
+            ),
+            span: Default::default(),
+        };
+        let mut s = String::new();
+        fact.format_digit_stats(&mut s, &consts, locale).unwrap();
+        assert_eq!(
+            s,
+            "The factorial of 100000 ends in 24999 zeros and has digital root 9 \n\n"
+        );
+        let fact = Calculation {
+            value: 100000.into(),
+            steps: vec![(Operation::Factorial(2), false)],
+            result: CalculationResult::ApproximateDigits(false, 228_000.into()),
+            span: Default::default(),
+        };
+        let mut s = String::new();
+        fact.format_digit_stats(&mut s, &consts, locale).unwrap();
+        assert_eq!(s, "");
+    }
+    #[test]
     fn test_format_complex_infinity_factorial() {
         let consts = Consts::default();
         let fact = Calculation {
             value: 0.into(),
-            steps: vec![(1, false)],
+            steps: vec![(Operation::Factorial(1), false)],
             result: CalculationResult::ComplexInfinity,
+            span: Default::default(),
         };
         let mut s = String::new();
         fact.format(
@@ -725,8 +1126,9 @@ mod test {
         let consts = Consts::default();
         let fact = Calculation {
             value: 0.into(),
-            steps: vec![(1, false)],
+            steps: vec![(Operation::Factorial(1), false)],
             result: CalculationResult::ApproximateDigitsTower(false, false, 9.into(), 10375.into()),
+            span: Default::default(),
         };
         let mut s = String::new();
         fact.format(
@@ -747,8 +1149,9 @@ mod test {
         let consts = Consts::default();
         let fact = Calculation {
             value: 0.into(),
-            steps: vec![(1, false)],
+            steps: vec![(Operation::Factorial(1), false)],
             result: CalculationResult::ApproximateDigitsTower(false, true, 9.into(), 10375.into()),
+            span: Default::default(),
         };
         let mut s = String::new();
         fact.format(
@@ -765,12 +1168,82 @@ mod test {
         );
     }
     #[test]
+    fn test_format_digits_hyper() {
+        let consts = Consts::default();
+        let fact = Calculation {
+            value: CalculationResult::ApproximateDigitsHyper(false, 2, 100000.into()),
+            steps: vec![(Operation::Factorial(1), false)],
+            result: CalculationResult::ApproximateDigitsHyper(false, 3, 12.into()),
+            span: Default::default(),
+        };
+        let mut s = String::new();
+        fact.format(
+            &mut s,
+            FormatOptions::NONE,
+            &TOO_BIG_NUMBER,
+            &consts,
+            &consts.locales.get("de").unwrap().format,
+        )
+        .unwrap();
+        assert_eq!(
+            s,
+            "Fakultät von 10↑↑100001 hat zwischen 10↑↑↑12 und 10↑↑↑13 Ziffern \n\n"
+        );
+        let fact = Calculation {
+            value: 0.into(),
+            steps: vec![(Operation::Factorial(1), false)],
+            result: CalculationResult::ApproximateDigitsHyper(
+                true,
+                7,
+                Integer::from_str(&format!("1{}", "0".repeat(50))).unwrap(),
+            ),
+            span: Default::default(),
+        };
+        let mut s = String::new();
+        fact.format(
+            &mut s,
+            FormatOptions::FORCE_SHORTEN,
+            &TOO_BIG_NUMBER,
+            &consts,
+            &consts.locales.get("en").unwrap().format,
+        )
+        .unwrap();
+        assert_eq!(
+            s,
+            "Factorial of 0 has on the order of -10→10^50→7 digits \n\n"
+        );
+        // Short heights still differ
+        let fact = Calculation {
+            value: 0.into(),
+            steps: vec![(Operation::Factorial(1), false)],
+            result: CalculationResult::ApproximateDigitsHyper(false, 3, 12.into()),
+            span: Default::default(),
+        };
+        let mut s = String::new();
+        fact.format(
+            &mut s,
+            FormatOptions::FORCE_SHORTEN,
+            &TOO_BIG_NUMBER,
+            &consts,
+            &consts.locales.get("en").unwrap().format,
+        )
+        .unwrap();
+        assert_eq!(
+            s,
+            "Factorial of 0 has between 10↑↑↑12 and 10↑↑↑13 digits \n\n"
+        );
+    }
+    #[test]
     fn test_format_digits_tower_tet() {
         let consts = Consts::default();
         let fact = Calculation {
             value: 0.into(),
-            steps: vec![(1, false), (1, false)],
+            steps: vec![
+                (Operation::Factorial(1), false),
+                (Operation::Factorial(1), false),
+            ],
             result: CalculationResult::ApproximateDigitsTower(false, false, 9.into(), 10375.into()),
+            span: Default::default(),
         };
         let mut s = String::new();
         fact.format(
@@ -790,10 +1263,11 @@ mod test {
             value: Number::Float(
                 Float::with_val(FLOAT_PRECISION, Float::parse("9.2").unwrap()).into(),
             ),
-            steps: vec![(1, false)],
+            steps: vec![(Operation::Factorial(1), false)],
             result: CalculationResult::Float(
                 Float::with_val(FLOAT_PRECISION, Float::parse("893.83924421").unwrap()).into(),
             ),
+            span: Default::default(),
         };
         let mut s = String::new();
         fact.format(
@@ -811,12 +1285,13 @@ mod test {
         let consts = Consts::default();
         let fact = Calculation {
             value: Number::Float(Float::with_val(FLOAT_PRECISION, 0).into()),
-            steps: vec![(1, false)],
+            steps: vec![(Operation::Factorial(1), false)],
             result: {
                 let mut m = Float::with_val(FLOAT_PRECISION, f64::MAX);
                 m.next_up();
                 CalculationResult::Float(m.into())
             },
+            span: Default::default(),
         };
         let mut s = String::new();
         fact.format(
@@ -839,11 +1314,12 @@ mod test {
             value: Number::Exact(
                 Integer::from_str("2018338437429423744923849374833232131").unwrap(),
             ),
-            steps: vec![(1, false)],
+            steps: vec![(Operation::Factorial(1), false)],
             result: CalculationResult::Approximate(
                 Float::with_val(FLOAT_PRECISION, Float::parse("2.8394792834").unwrap()).into(),
                 Integer::from_str("10094283492304894983443984102489842984271").unwrap(),
             ),
+            span: Default::default(),
         };
         let mut s = String::new();
         fact.format(
@@ -866,11 +1342,12 @@ mod test {
             value: Number::Exact(
                 Integer::from_str("2313820948092579283573259490834298719").unwrap(),
             ),
-            steps: vec![(1, false)],
+            steps: vec![(Operation::Factorial(1), false)],
             result: CalculationResult::ApproximateDigits(
                 false,
                 Integer::from_str("9842371208573508275237815084709374240128347012847").unwrap(),
             ),
+            span: Default::default(),
         };
         let mut s = String::new();
         fact.format(
@@ -893,13 +1370,14 @@ mod test {
             value: Number::Exact(
                 Integer::from_str("13204814708471087502685784603872164320053271").unwrap(),
             ),
-            steps: vec![(1, false)],
+            steps: vec![(Operation::Factorial(1), false)],
             result: CalculationResult::ApproximateDigitsTower(
                 false,
                 false,
                 9.into(),
                 Integer::from_str("7084327410873502875032857120358730912469148632").unwrap(),
             ),
+            span: Default::default(),
         };
         let mut s = String::new();
         fact.format(
@@ -920,12 +1398,13 @@ mod test {
         let consts = Consts::default();
         let fact = Calculation {
             value: 0.into(),
-            steps: vec![(1, false)],
+            steps: vec![(Operation::Factorial(1), false)],
             result: CalculationResult::Exact({
                 let mut r = Float::with_val(FLOAT_PRECISION, crate::rug::float::Special::Infinity);
                 r.next_down();
                 r.to_integer().unwrap()
             }),
+            span: Default::default(),
         };
         let mut s = String::new();
         fact.format(
@@ -947,8 +1426,9 @@ mod test {
         let consts = Consts::default();
         let fact = Calculation {
             value: 0.into(),
-            steps: vec![(1, false)],
+            steps: vec![(Operation::Factorial(1), false)],
             result: CalculationResult::ApproximateDigitsTower(false, false, 4.into(), 1.into()),
+            span: Default::default(),
         };
         let mut s = String::new();
         fact.format(
@@ -971,12 +1451,14 @@ mod test {
                 Float::with_val(FLOAT_PRECISION, 2.0).into(),
                 1.into(),
             ),
+            span: Default::default(),
         };
         assert!(c1.is_approximate());
         let c2 = Calculation {
             value: 0.into(),
             steps: vec![],
             result: CalculationResult::Exact(1.into()),
+            span: Default::default(),
         };
         assert!(!c2.is_approximate());
     }
@@ -990,18 +1472,21 @@ mod test {
                 Float::with_val(FLOAT_PRECISION, 0.0).into(),
                 0.into(),
             ),
+            span: Default::default(),
         };
         assert!(c1.is_rounded());
         let c2 = Calculation {
             value: Number::Float(Float::with_val(FLOAT_PRECISION, 1.23).into()),
             steps: vec![],
             result: CalculationResult::Float(Float::with_val(FLOAT_PRECISION, 1.23).into()),
+            span: Default::default(),
         };
         assert!(!c2.is_rounded());
         let c3 = Calculation {
             value: 1.into(),
             steps: vec![],
             result: CalculationResult::Exact(1.into()),
+            span: Default::default(),
         };
         assert!(!c3.is_rounded());
     }
@@ -1012,26 +1497,31 @@ mod test {
             value: 1.into(),
             steps: vec![],
             result: CalculationResult::Exact(1.into()),
+            span: Default::default(),
         };
         assert!(!small.is_too_long(&TOO_BIG_NUMBER));
         let big = Calculation {
             value: 1.into(),
             steps: vec![],
             result: CalculationResult::Exact((*TOO_BIG_NUMBER).clone() + 1),
+            span: Default::default(),
         };
         assert!(big.is_too_long(&TOO_BIG_NUMBER));
         let fl = Calculation {
             value: 1.into(),
             steps: vec![],
             result: CalculationResult::Float(Float::with_val(FLOAT_PRECISION, 1.0).into()),
+            span: Default::default(),
         };
         assert!(!fl.is_too_long(&TOO_BIG_NUMBER));
     }
 
     #[test]
     fn test_number_decimals_scientific_respected() {
-        let mut consts = Consts::default();
-        consts.number_decimals_scientific = 10;
+        let mut consts = Consts {
+            number_decimals_scientific: 10,
+            ..Default::default()
+        };
         let mut acc = String::new();
         CalculationResult::Exact(
             Integer::u_pow_u(10, 1000).complete() * 498_149_837_492_347_328u64,
@@ -1042,7 +1532,7 @@ mod test {
             FormatOptions::FORCE_SHORTEN,
             false,
             &consts,
-            &locale::NumFormat { decimal: '.' },
+            &locale::Format::default(),
         )
         .unwrap();
         assert_eq!(acc, "4.9814983749 × 10^1017");
@@ -1057,7 +1547,7 @@ mod test {
             FormatOptions::FORCE_SHORTEN,
             false,
             &consts,
-            &locale::NumFormat { decimal: '.' },
+            &locale::Format::default(),
         )
         .unwrap();
         assert_eq!(acc, "4.9814983749 × 10^(1017)");
@@ -1072,7 +1562,7 @@ mod test {
             FormatOptions::FORCE_SHORTEN,
             false,
             &consts,
-            &locale::NumFormat { decimal: '.' },
+            &locale::Format::default(),
         )
         .unwrap();
         assert_eq!(acc, "4.9814983749234732849839849898438493843 × 10^1037");
@@ -1091,7 +1581,7 @@ mod test {
             FormatOptions::FORCE_SHORTEN,
             false,
             &consts,
-            &locale::NumFormat { decimal: '.' },
+            &locale::Format::default(),
         )
         .unwrap();
         assert_eq!(acc, "4.9814983749234732849839849898438493843 × 10^(1037)");
@@ -1107,6 +1597,8 @@ mod test {
                 Float::parse("0.999999999999999999999999999999999").unwrap(),
             ),
             &consts,
+            &locale::NumFormat::default(),
+            Default::default(),
         )
         .unwrap();
         assert_eq!(acc, "1");
@@ -1118,6 +1610,8 @@ mod test {
                 Float::parse("0.000000000000000000000000000000009").unwrap(),
             ),
             &consts,
+            &locale::NumFormat::default(),
+            Default::default(),
         )
         .unwrap();
         assert_eq!(acc, "9 × 10^-33");
@@ -1127,6 +1621,8 @@ mod test {
             &mut acc,
             &Float::with_val(FLOAT_PRECISION, Float::parse("0.10").unwrap()),
             &consts,
+            &locale::NumFormat::default(),
+            Default::default(),
         )
         .unwrap();
         assert_eq!(acc, "0.1");
@@ -1137,6 +1633,8 @@ mod test {
             &mut acc,
             &Float::with_val(FLOAT_PRECISION, Float::parse("6.631537423e-34").unwrap()),
             &consts,
+            &locale::NumFormat::default(),
+            Default::default(),
         )
         .unwrap();
         assert_eq!(acc, "6.631537423 × 10^-34");
@@ -1146,6 +1644,8 @@ mod test {
             &mut acc,
             &Float::with_val(FLOAT_PRECISION, Float::parse("6.631537423e34").unwrap()),
             &consts,
+            &locale::NumFormat::default(),
+            Default::default(),
         )
         .unwrap();
         assert_eq!(acc, "6.631537423 × 10^34");
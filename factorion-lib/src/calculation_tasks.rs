@@ -16,7 +16,10 @@ use crate::{
     math,
 };
 
-use crate::rug::{Float, ops::Pow};
+use crate::rug::{
+    Float,
+    ops::{Pow, RemRounding},
+};
 
 pub mod recommended {
     use factorion_math::rug::Complete;
@@ -30,23 +33,96 @@ pub mod recommended {
         || Integer::u64_pow_u64(10, 300).complete();
     // Limit for exact subfactorial calculation, set to limit calculation time
     pub static UPPER_SUBFACTORIAL_LIMIT: fn() -> Integer = || 100_000.into();
+    // Limit for exact Bell and Stirling number calculation (quadratic), set to limit calculation time
+    pub static UPPER_COMBINATORIAL_LIMIT: fn() -> Integer = || 2_000.into();
     // Limit for exact termial calculation, set to limit calculation time (absurdly high)
     pub static UPPER_TERMIAL_LIMIT: fn() -> Integer = || Integer::u64_pow_u64(10, 10000).complete();
     // Limit for approximation, set to ensure enough accuracy (5 decimals)
     // Based on max float. (bits)
     pub static UPPER_TERMIAL_APPROXIMATION_LIMIT: u32 = 1073741822;
+    // Limit for the depth of power towers, above that only bounds in up-arrow notation are given (towers would be too long to post)
+    pub static UPPER_TOWER_DEPTH_LIMIT: fn() -> Integer = || 10_000.into();
+    // Limit for float precision (bits), set to limit calculation time (also caps the precision command)
+    pub static MAX_FLOAT_PRECISION: u32 = 16384;
 }
 
+/// Bits to calculate with on top of what the input and output need
+const GUARD_BITS: u32 = 64;
+
 /// Representation of the calculation to be done
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(any(feature = "serde", test), derive(Serialize, Deserialize))]
 pub struct CalculationJob {
     pub base: CalculationBase,
     /// Type of the calculation
-    pub level: i32,
+    pub operation: Operation,
     /// Number of negations encountered
     pub negative: u32,
+    /// Where this step was written in the parsed text
+    #[cfg_attr(any(feature = "serde", test), serde(default))]
+    pub span: Span,
 }
+
+/// Byte range of a calculation in the text it was parsed from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(any(feature = "serde", test), derive(Serialize, Deserialize))]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+    /// Gets the snippet of the text, if the span is in it
+    pub fn slice<'a>(&self, text: &'a str) -> Option<&'a str> {
+        text.get(self.start..self.end)
+    }
+}
+/// Type of a calculation, with its second operand if it takes two
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(any(feature = "serde", test), derive(Serialize, Deserialize))]
+pub enum Operation {
+    /// By level: k-factorial (k > 0), subfactorial (0) or k-termial (-k)
+    Factorial(i32),
+    /// Factorial modulo the operand (n! mod m)
+    FactorialModulo(Integer),
+    /// Sum of the factorials of the digits (1! + 4! + 5! = 145)
+    DigitFactorialSum,
+    /// Rencontres numbers (D(n, k), permutations of n elements with exactly k fixed points)
+    Rencontres(Integer),
+    /// Signed Stirling numbers of the first kind (s(n, k), permutations of n elements with k cycles, signed)
+    StirlingFirst(Integer),
+    /// Stirling numbers of the second kind (S(n, k), partitions of n elements into k subsets)
+    StirlingSecond(Integer),
+    /// Bell numbers (B(n), the number of partitions of a set of n elements)
+    Bell,
+    /// Catalan numbers (C(n) = binomial(2n, n)/(n+1))
+    Catalan,
+    /// Falling factorial ((x)_n = x(x-1)...(x-n+1))
+    FallingFactorial(Integer),
+    /// Rising factorial (x^(n) = x(x+1)...(x+n-1))
+    RisingFactorial(Integer),
+    /// Compositorial (n!/n#, the product of all composite numbers up to n)
+    Compositorial,
+    /// Exponential factorial (n^(n-1)^...^1)
+    ExponentialFactorial,
+}
+impl Operation {
+    /// The second operand, if the operation takes two
+    pub fn operand(&self) -> Option<&Integer> {
+        match self {
+            Operation::FactorialModulo(operand)
+            | Operation::Rencontres(operand)
+            | Operation::StirlingFirst(operand)
+            | Operation::StirlingSecond(operand)
+            | Operation::FallingFactorial(operand)
+            | Operation::RisingFactorial(operand) => Some(operand),
+            _ => None,
+        }
+    }
+}
+
 /// The basis of a calculation, whether [Number] or [CalculationJob].
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(any(feature = "serde", test), derive(Serialize, Deserialize))]
@@ -55,14 +131,44 @@ pub enum CalculationBase {
     Calc(Box<CalculationJob>),
 }
 
+impl CalculationBase {
+    /// Compares what is calculated, ignoring where it was written (see [CalculationJob::cmp_calculation])
+    pub fn cmp_calculation(&self, other: &Self) -> std::cmp::Ordering {
+        match (self, other) {
+            (CalculationBase::Num(a), CalculationBase::Num(b)) => a.cmp(b),
+            (CalculationBase::Calc(a), CalculationBase::Calc(b)) => a.cmp_calculation(b),
+            (CalculationBase::Num(_), CalculationBase::Calc(_)) => std::cmp::Ordering::Less,
+            (CalculationBase::Calc(_), CalculationBase::Num(_)) => std::cmp::Ordering::Greater,
+        }
+    }
+}
+
 impl CalculationJob {
+    /// Compares what is calculated, ignoring where it was written (the spans), so the same calculation can be deduplicated.
+    pub fn cmp_calculation(&self, other: &Self) -> std::cmp::Ordering {
+        self.base
+            .cmp_calculation(&other.base)
+            .then_with(|| self.operation.cmp(&other.operation))
+            .then_with(|| self.negative.cmp(&other.negative))
+    }
     /// Execute the calculation. \
     /// If include_steps is enabled, will return all intermediate results.
     pub fn execute(self, include_steps: bool, consts: &Consts) -> Vec<Option<Calculation>> {
+        self.execute_with_precision(include_steps, None, consts)
+    }
+    /// Execute the calculation with a float precision (in bits), capped by `max_float_precision` and at least [min_float_precision]. \
+    /// If precision is None, it is chosen for each step based on the size of the input.
+    pub fn execute_with_precision(
+        self,
+        include_steps: bool,
+        precision: Option<u32>,
+        consts: &Consts,
+    ) -> Vec<Option<Calculation>> {
         let CalculationJob {
             mut base,
-            mut level,
+            mut operation,
             mut negative,
+            mut span,
         } = self;
         let size = {
             let mut n = 1;
@@ -79,26 +185,33 @@ impl CalculationJob {
             match base {
                 CalculationBase::Num(num) => {
                     break vec![
-                        calculate_appropriate_factorial(num.clone(), level, negative, consts).map(
-                            |res| Calculation {
-                                value: num,
-                                steps: vec![(level, negative % 2 == 1)],
-                                result: res,
-                            },
-                        ),
+                        calculate_appropriate_factorial(
+                            num.clone(),
+                            &operation,
+                            negative,
+                            precision,
+                            consts,
+                        )
+                        .map(|res| Calculation {
+                            value: num,
+                            steps: vec![(operation, negative % 2 == 1)],
+                            result: res,
+                            span,
+                        }),
                     ];
                 }
                 CalculationBase::Calc(calc) => {
-                    steps.push((level, negative));
+                    steps.push((operation, negative, span));
                     CalculationJob {
                         base,
-                        level,
+                        operation,
                         negative,
+                        span,
                     } = *calc;
                 }
             }
         };
-        for (i, (level, negative)) in steps.into_iter().rev().enumerate() {
+        for (i, (operation, negative, span)) in steps.into_iter().rev().enumerate() {
             let calc = if include_steps && i < 30 {
                 calcs.last().cloned()
             } else {
@@ -109,16 +222,20 @@ impl CalculationJob {
                     result: res,
                     mut steps,
                     value: number,
+                    ..
                 })) => {
-                    let factorial = calculate_appropriate_factorial(res, level, negative, consts)
-                        .map(|res| {
-                            steps.push((level, negative % 2 == 1));
-                            Calculation {
-                                value: number,
-                                steps,
-                                result: res,
-                            }
-                        });
+                    let factorial = calculate_appropriate_factorial(
+                        res, &operation, negative, precision, consts,
+                    )
+                    .map(|res| {
+                        steps.push((operation, negative % 2 == 1));
+                        Calculation {
+                            value: number,
+                            steps,
+                            result: res,
+                            span,
+                        }
+                    });
                     calcs.push(factorial);
                 }
                 _ => return calcs,
@@ -130,11 +247,65 @@ impl CalculationJob {
 
 fn calculate_appropriate_factorial(
     num: Number,
-    level: i32,
+    operation: &Operation,
     negative: u32,
+    precision: Option<u32>,
     consts: &Consts,
 ) -> Option<CalculationResult> {
-    let prec = consts.float_precision;
+    let prec = precision
+        .unwrap_or_else(|| appropriate_precision(&num, operation, consts))
+        .min(consts.max_float_precision)
+        .max(min_float_precision(consts));
+    let level = match operation {
+        Operation::Factorial(level) => *level,
+        Operation::DigitFactorialSum => {
+            // The digits are only known for exact numbers
+            return match num {
+                Number::Exact(num) => Some(CalculationResult::Exact(
+                    math::digit_factorial_sum(&num)
+                        * if !negative.is_multiple_of(2) { -1 } else { 1 },
+                )),
+                _ => None,
+            };
+        }
+        Operation::FactorialModulo(modulus) => {
+            return calculate_factorial_modulo(num, negative, modulus, consts);
+        }
+        Operation::RisingFactorial(order) => {
+            return calculate_pochhammer(num, true, order, negative, prec, consts);
+        }
+        Operation::FallingFactorial(order) => {
+            return calculate_pochhammer(num, false, order, negative, prec, consts);
+        }
+        Operation::ExponentialFactorial
+        | Operation::Compositorial
+        | Operation::Catalan
+        | Operation::Bell
+        | Operation::StirlingSecond(_)
+        | Operation::StirlingFirst(_)
+        | Operation::Rencontres(_) => {
+            let calc_num = match num {
+                CalculationResult::ComplexInfinity => {
+                    return Some(CalculationResult::ComplexInfinity);
+                }
+                Number::Exact(num) => num,
+                CalculationResult::Approximate(base, exponent)
+                    if exponent <= consts.integer_construction_limit =>
+                {
+                    (base.as_float() * Float::with_val(prec, 10).pow(&exponent)).to_integer()?
+                }
+                // Only defined for integers, and we can't know larger ones exactly
+                _ => return None,
+            };
+            return match operation {
+                Operation::ExponentialFactorial => {
+                    Some(calculate_exponential_factorial(negative, prec, calc_num))
+                }
+                Operation::Compositorial => calculate_compositorial(negative, consts, &calc_num),
+                _ => calculate_combinatorial(operation, negative, prec, consts, calc_num),
+            };
+        }
+    };
     let calc_num = match num {
         CalculationResult::ComplexInfinity => return Some(CalculationResult::ComplexInfinity),
         Number::Float(num) | CalculationResult::Approximate(num, _)
@@ -156,19 +327,24 @@ fn calculate_appropriate_factorial(
             }
         }
         CalculationResult::ApproximateDigitsTower(was_neg, neg, depth, exponent) => {
-            return calculate_approximate_digits_tower(level, prec, was_neg, neg, depth, exponent);
+            return calculate_approximate_digits_tower(
+                level, prec, consts, was_neg, neg, depth, exponent,
+            );
         }
-        Number::Float(num) => match calculate_or_extract_float(level, negative, num) {
+        CalculationResult::ApproximateDigitsHyper(was_neg, arrows, height) => {
+            return calculate_approximate_digits_hyper(level, was_neg, arrows, height);
+        }
+        Number::Float(num) => match calculate_or_extract_float(level, negative, prec, num) {
             ControlFlow::Continue(num) => num,
             ControlFlow::Break(val) => return val,
         },
         Number::Exact(num) if num.significant_bits() >= math::rug::float::exp_max() as u32 => {
-            return calculate_exact_as_approximate(level, negative, consts, num);
+            return calculate_exact_as_approximate(level, negative, precision, consts, num);
         }
         Number::Exact(num) => num,
     };
     Some(if level > 0 {
-        calculate_k_factorial(level, negative, consts, prec, &calc_num)?
+        calculate_k_factorial(level, negative, precision, consts, prec, &calc_num)?
     } else if level == 0 {
         calculate_subfactorial(negative, consts, prec, &calc_num)
     } else if level < 0 {
@@ -178,6 +354,250 @@ fn calculate_appropriate_factorial(
     })
 }
 
+/// Bits needed for the decimals shown in scientific notation
+fn output_bits(consts: &Consts) -> f64 {
+    consts.number_decimals_scientific as f64 * std::f64::consts::LOG2_10
+}
+
+/// The lowest float precision (in bits) to calculate with, as anything lower can't give all the shown decimals. \
+/// Requested precisions are raised to it.
+pub fn min_float_precision(consts: &Consts) -> u32 {
+    output_bits(consts).ceil() as u32 + GUARD_BITS
+}
+
+/// Chooses the float precision (in bits) to calculate num with.
+///
+/// The output needs enough bits for the shown decimals.
+/// Factorial approximations subtract numbers about as large as the input from each other,
+/// so they additionally need the bits of the input (and its logarithm), to not lose any digits.
+/// Termials don't, as their error only depends on the output.
+fn appropriate_precision(num: &Number, operation: &Operation, consts: &Consts) -> u32 {
+    let output_bits = output_bits(consts);
+    let input_bits = if matches!(operation, Operation::Factorial(..0)) {
+        0.0
+    } else {
+        match num {
+            Number::Exact(n) => n.significant_bits() as f64,
+            Number::Float(n) => n.as_float().get_exp().unwrap_or(0).max(0) as f64,
+            Number::Approximate(_, e) | Number::ApproximateDigits(_, e) => {
+                e.to_f64() * std::f64::consts::LOG2_10
+            }
+            Number::ApproximateDigitsTower(..)
+            | Number::ApproximateDigitsHyper(..)
+            | Number::ComplexInfinity => 0.0,
+        }
+    };
+    let bits = output_bits + input_bits + 2.0 * (input_bits + 1.0).log2() + GUARD_BITS as f64;
+    if bits >= u32::MAX as f64 {
+        u32::MAX
+    } else {
+        bits.ceil() as u32
+    }
+}
+
+fn calculate_exponential_factorial(
+    negative: u32,
+    prec: u32,
+    calc_num: Integer,
+) -> CalculationResult {
+    if calc_num < 0 {
+        CalculationResult::ComplexInfinity
+    } else if let Some(factorial) = calc_num
+        .to_u64()
+        .filter(|n| *n <= 5)
+        .and_then(math::exponential_factorial)
+    {
+        CalculationResult::Exact(factorial * if !negative.is_multiple_of(2) { -1 } else { 1 })
+    } else {
+        let (depth, exponent) = math::approximate_exponential_factorial_digits(calc_num, prec);
+        if depth == 0 {
+            CalculationResult::ApproximateDigits(!negative.is_multiple_of(2), exponent)
+        } else {
+            CalculationResult::ApproximateDigitsTower(
+                !negative.is_multiple_of(2),
+                false,
+                depth,
+                exponent,
+            )
+        }
+    }
+}
+
+fn calculate_compositorial(
+    negative: u32,
+    consts: &Consts<'_>,
+    calc_num: &Integer,
+) -> Option<CalculationResult> {
+    Some(if *calc_num < 0 {
+        CalculationResult::ComplexInfinity
+    } else if *calc_num > consts.upper_calculation_limit {
+        // The product of primes can't be approximated well enough
+        return None;
+    } else {
+        let calc_num = calc_num
+            .to_u64()
+            .unwrap_or_else(|| panic!("Failed to convert BigInt to u64: {calc_num}"));
+        let compositorial =
+            math::compositorial(calc_num) * if !negative.is_multiple_of(2) { -1 } else { 1 };
+        CalculationResult::Exact(compositorial)
+    })
+}
+
+fn calculate_factorial_modulo(
+    num: Number,
+    negative: u32,
+    modulus: &Integer,
+    consts: &Consts<'_>,
+) -> Option<CalculationResult> {
+    // Only defined for integers
+    let Number::Exact(num) = num else {
+        return None;
+    };
+    if num < 0 {
+        return Some(CalculationResult::ComplexInfinity);
+    }
+    let remainder = math::factorial_mod(&num, modulus, consts.upper_calculation_limit.to_u64()?)?;
+    Some(CalculationResult::Exact(if !negative.is_multiple_of(2) {
+        (-remainder).rem_euc(modulus)
+    } else {
+        remainder
+    }))
+}
+
+fn calculate_combinatorial(
+    operation: &Operation,
+    negative: u32,
+    prec: u32,
+    consts: &Consts<'_>,
+    calc_num: Integer,
+) -> Option<CalculationResult> {
+    // Only defined for natural numbers
+    if calc_num < 0 {
+        return None;
+    }
+    let mut sign = if !negative.is_multiple_of(2) { -1 } else { 1 };
+    let (unary, k) = match operation {
+        Operation::StirlingSecond(k) | Operation::StirlingFirst(k) | Operation::Rencontres(k) => {
+            (false, k.to_u32()?)
+        }
+        _ => (true, 0),
+    };
+    if !unary && calc_num < k {
+        return Some(CalculationResult::Exact(Integer::ZERO));
+    }
+    let exact_limit = match operation {
+        Operation::Catalan => &consts.upper_calculation_limit,
+        Operation::Rencontres(_) => &consts.upper_subfactorial_limit,
+        _ => &consts.upper_combinatorial_limit,
+    };
+    if calc_num <= *exact_limit {
+        let n = calc_num.to_u32()?;
+        let res = match operation {
+            Operation::Catalan => math::catalan(n),
+            Operation::Bell => math::bell(n),
+            Operation::StirlingSecond(_) => math::stirling_second(n, k),
+            Operation::StirlingFirst(_) => math::stirling_first(n, k),
+            _ => math::rencontres(n, k),
+        };
+        return Some(CalculationResult::Exact(res * sign));
+    }
+    // Whether the approximation is good enough to show the decimals, or only the digits
+    let (approximation, accurate) = match operation {
+        Operation::Catalan => (math::approximate_catalan(calc_num.clone(), prec), true),
+        Operation::Bell => (math::approximate_bell(calc_num.clone(), prec), false),
+        Operation::StirlingSecond(_) => {
+            if k <= 1 {
+                return Some(CalculationResult::Exact(Integer::from(k) * sign));
+            }
+            // The relative error k((k-1)/k)^n has to be below the shown decimals
+            let error_exponent = calc_num.to_f64() * (k as f64 / (k - 1) as f64).ln();
+            let needed_exponent = (k as f64).ln()
+                + (consts.number_decimals_scientific + 1) as f64 * std::f64::consts::LN_10;
+            if error_exponent <= needed_exponent {
+                return None;
+            }
+            (
+                math::approximate_stirling_second(calc_num.clone(), k, prec),
+                true,
+            )
+        }
+        Operation::StirlingFirst(_) => {
+            if k == 0 {
+                return Some(CalculationResult::Exact(Integer::ZERO));
+            }
+            // The asymptotic only holds for k much smaller than ln(n)
+            if (k as f64).powi(2) > calc_num.to_f64().ln() {
+                return None;
+            }
+            if (calc_num.clone() - k).is_odd() {
+                sign = -sign;
+            }
+            // Exact for k = 1, as |s(n, 1)| = (n-1)!
+            (
+                math::approximate_stirling_first(calc_num.clone(), k, prec),
+                k == 1,
+            )
+        }
+        _ => (
+            math::approximate_rencontres(calc_num.clone(), k, prec),
+            true,
+        ),
+    };
+    let (x, e) = approximation;
+    Some(
+        if accurate && calc_num <= consts.upper_approximation_limit {
+            CalculationResult::Approximate(((x * sign) as Float).into(), e)
+        } else {
+            CalculationResult::ApproximateDigits(sign < 0, e + 1)
+        },
+    )
+}
+
+fn calculate_pochhammer(
+    num: Number,
+    rising: bool,
+    order: &Integer,
+    negative: u32,
+    prec: u32,
+    consts: &Consts<'_>,
+) -> Option<CalculationResult> {
+    if *order > consts.upper_calculation_limit {
+        return None;
+    }
+    let order = order.to_u32()?;
+    Some(match num {
+        CalculationResult::ComplexInfinity => CalculationResult::ComplexInfinity,
+        Number::Float(x) if !x.as_float().is_finite() => CalculationResult::ComplexInfinity,
+        Number::Float(x) => {
+            let x = Float::with_val(prec, x.as_float());
+            let res: Float = if rising {
+                math::fractional_rising_factorial(x, order)
+            } else {
+                math::fractional_falling_factorial(x, order)
+            } * if !negative.is_multiple_of(2) { -1 } else { 1 };
+            if res.is_finite() {
+                CalculationResult::Float(res.into())
+            } else {
+                CalculationResult::ComplexInfinity
+            }
+        }
+        // Don't calculate anything larger than the factorial at the calculation limit
+        Number::Exact(x)
+            if order as u64 * x.significant_bits() as u64
+                <= consts.upper_calculation_limit.to_u64()?
+                    * consts.upper_calculation_limit.significant_bits() as u64 =>
+        {
+            let res = if rising {
+                math::rising_factorial(x, order)
+            } else {
+                math::falling_factorial(x, order)
+            } * if !negative.is_multiple_of(2) { -1 } else { 1 };
+            CalculationResult::Exact(res)
+        }
+        _ => return None,
+    })
+}
+
 fn calculate_termial(
     level: i32,
     negative: u32,
@@ -235,6 +655,7 @@ fn calculate_subfactorial(
 fn calculate_k_factorial(
     level: i32,
     negative: u32,
+    precision: Option<u32>,
     consts: &Consts<'_>,
     prec: u32,
     calc_num: &Integer,
@@ -247,8 +668,9 @@ fn calculate_k_factorial(
             (Some(factor), true) => {
                 let mut res = calculate_appropriate_factorial(
                     Number::Exact(-calc_num.clone() - level),
-                    level,
+                    &Operation::Factorial(level),
                     negative,
+                    precision,
                     consts,
                 )?;
                 res = match res {
@@ -266,6 +688,8 @@ fn calculate_k_factorial(
                     CalculationResult::ApproximateDigitsTower(wn, negative, depth, base) => {
                         CalculationResult::ApproximateDigitsTower(wn, !negative, depth, base)
                     }
+                    // Reciprocals that small can't be written
+                    CalculationResult::ApproximateDigitsHyper(..) => return None,
                     CalculationResult::ComplexInfinity => CalculationResult::Exact(0.into()),
                     CalculationResult::Float(f) => {
                         CalculationResult::Float((factor / Float::from(f)).into())
@@ -307,6 +731,7 @@ fn calculate_k_factorial(
 fn calculate_exact_as_approximate(
     level: i32,
     negative: u32,
+    precision: Option<u32>,
     consts: &Consts<'_>,
     num: Integer,
 ) -> Option<CalculationResult> {
@@ -318,8 +743,9 @@ fn calculate_exact_as_approximate(
                 .into(),
             sig_bits.into(),
         ),
-        level,
+        &Operation::Factorial(level),
         negative,
+        precision,
         consts,
     )
 }
@@ -327,8 +753,10 @@ fn calculate_exact_as_approximate(
 fn calculate_or_extract_float(
     level: i32,
     negative: u32,
+    prec: u32,
     num: OrdFloat,
 ) -> ControlFlow<Option<CalculationResult>, Integer> {
+    let num: OrdFloat = Float::with_val(prec, num.as_float()).into();
     ControlFlow::Continue(match level {
         ..-1 => {
             // We don't support multitermials of decimals
@@ -380,6 +808,7 @@ fn calculate_or_extract_float(
 fn calculate_approximate_digits_tower(
     level: i32,
     prec: u32,
+    consts: &Consts<'_>,
     was_neg: bool,
     neg: bool,
     depth: Integer,
@@ -389,10 +818,31 @@ fn calculate_approximate_digits_tower(
         CalculationResult::Float(Float::new(prec).into())
     } else if was_neg {
         CalculationResult::ComplexInfinity
-    } else if level < 0 {
-        CalculationResult::ApproximateDigitsTower(false, false, depth, exponent)
     } else {
-        CalculationResult::ApproximateDigitsTower(false, false, depth + 1, exponent)
+        let depth = if level < 0 { depth } else { depth + 1 };
+        if depth > consts.upper_tower_depth_limit {
+            // Too tall to write out, so only give its tetration bounds
+            let height = math::tetration_height(depth, Float::with_val(prec, exponent));
+            CalculationResult::ApproximateDigitsHyper(false, 2, height)
+        } else {
+            CalculationResult::ApproximateDigitsTower(false, false, depth, exponent)
+        }
+    })
+}
+
+/// From three arrows on, factorials (and termials) stay within the same bounds.
+fn calculate_approximate_digits_hyper(
+    level: i32,
+    was_neg: bool,
+    arrows: u32,
+    height: Integer,
+) -> Option<CalculationResult> {
+    Some(if was_neg {
+        CalculationResult::ComplexInfinity
+    } else if level < 0 || arrows > 2 {
+        CalculationResult::ApproximateDigitsHyper(false, arrows, height)
+    } else {
+        CalculationResult::ApproximateDigitsHyper(false, arrows, height + 1)
     })
 }
 
@@ -469,22 +919,227 @@ mod tests {
         // Subfactorial
         let job = CalculationJob {
             base: CalculationBase::Num(Number::Float(Float::with_val(FLOAT_PRECISION, 1.5).into())),
-            level: 0,
+            operation: Operation::Factorial(0),
             negative: 0,
+            span: Default::default(),
         };
         assert_eq!(job.execute(false, &consts), vec![None]);
         // Multitermial
         let job = CalculationJob {
             base: CalculationBase::Num(Number::Float(Float::with_val(FLOAT_PRECISION, 1.5).into())),
-            level: -2,
+            operation: Operation::Factorial(-2),
+            negative: 0,
+            span: Default::default(),
+        };
+        assert_eq!(job.execute(false, &consts), vec![None]);
+        let job = CalculationJob {
+            base: CalculationBase::Num(Number::Float(Float::with_val(FLOAT_PRECISION, 1.5).into())),
+            operation: Operation::Factorial(-51),
+            negative: 0,
+            span: Default::default(),
+        };
+        assert_eq!(job.execute(false, &consts), vec![None]);
+        // Exponential factorial
+        let job = CalculationJob {
+            base: CalculationBase::Num(Number::Float(Float::with_val(FLOAT_PRECISION, 1.5).into())),
+            operation: Operation::ExponentialFactorial,
             negative: 0,
+            span: Default::default(),
         };
         assert_eq!(job.execute(false, &consts), vec![None]);
+        // Compositorial
         let job = CalculationJob {
             base: CalculationBase::Num(Number::Float(Float::with_val(FLOAT_PRECISION, 1.5).into())),
-            level: -51,
+            operation: Operation::Compositorial,
             negative: 0,
+            span: Default::default(),
         };
         assert_eq!(job.execute(false, &consts), vec![None]);
+        let job = CalculationJob {
+            base: CalculationBase::Num(Number::Exact(consts.upper_calculation_limit.clone() + 1)),
+            operation: Operation::Compositorial,
+            negative: 0,
+            span: Default::default(),
+        };
+        assert_eq!(job.execute(false, &consts), vec![None]);
+        // Falling factorial with too large order
+        let job = CalculationJob {
+            base: CalculationBase::Num(Number::Exact(5.into())),
+            operation: Operation::FallingFactorial(consts.upper_calculation_limit.clone() + 1),
+            negative: 0,
+            span: Default::default(),
+        };
+        assert_eq!(job.execute(false, &consts), vec![None]);
+        // Stirling numbers with too large k
+        let job = CalculationJob {
+            base: CalculationBase::Num(Number::Exact(5.into())),
+            operation: Operation::StirlingSecond(u64::MAX.into()),
+            negative: 0,
+            span: Default::default(),
+        };
+        assert_eq!(job.execute(false, &consts), vec![None]);
+        // Stirling numbers of the second kind, where k^n/k! is not close enough
+        let job = CalculationJob {
+            base: CalculationBase::Num(Number::Exact(consts.upper_combinatorial_limit.clone() + 1)),
+            operation: Operation::StirlingSecond(1000.into()),
+            negative: 0,
+            span: Default::default(),
+        };
+        assert_eq!(job.execute(false, &consts), vec![None]);
+        // Catalan numbers of floats
+        let job = CalculationJob {
+            base: CalculationBase::Num(Number::Float(Float::with_val(FLOAT_PRECISION, 1.5).into())),
+            operation: Operation::Catalan,
+            negative: 0,
+            span: Default::default(),
+        };
+        assert_eq!(job.execute(false, &consts), vec![None]);
+    }
+
+    #[test]
+    fn test_nested_operands() {
+        let consts = Consts::default();
+        // S((3)_2, 2) mod 7 = S(6, 2) mod 7 = 31! mod 7
+        let job = CalculationJob {
+            base: CalculationBase::Calc(Box::new(CalculationJob {
+                base: CalculationBase::Calc(Box::new(CalculationJob {
+                    base: CalculationBase::Num(Number::Exact(3.into())),
+                    operation: Operation::FallingFactorial(2.into()),
+                    negative: 0,
+                    span: Default::default(),
+                })),
+                operation: Operation::StirlingSecond(2.into()),
+                negative: 0,
+                span: Default::default(),
+            })),
+            operation: Operation::FactorialModulo(7.into()),
+            negative: 0,
+            span: Default::default(),
+        };
+        assert_eq!(
+            job.execute(true, &consts),
+            vec![
+                Some(Calculation {
+                    value: 3.into(),
+                    steps: vec![(Operation::FallingFactorial(2.into()), false)],
+                    result: CalculationResult::Exact(6.into()),
+                    span: Default::default(),
+                }),
+                Some(Calculation {
+                    value: 3.into(),
+                    steps: vec![
+                        (Operation::FallingFactorial(2.into()), false),
+                        (Operation::StirlingSecond(2.into()), false),
+                    ],
+                    result: CalculationResult::Exact(31.into()),
+                    span: Default::default(),
+                }),
+                Some(Calculation {
+                    value: 3.into(),
+                    steps: vec![
+                        (Operation::FallingFactorial(2.into()), false),
+                        (Operation::StirlingSecond(2.into()), false),
+                        (Operation::FactorialModulo(7.into()), false),
+                    ],
+                    result: CalculationResult::Exact(0.into()),
+                    span: Default::default(),
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_appropriate_precision() {
+        let consts = Consts::default();
+        let small =
+            appropriate_precision(&Number::Exact(5.into()), &Operation::Factorial(1), &consts);
+        let large = appropriate_precision(
+            &Number::Exact(Integer::from(10).pow(200u32)),
+            &Operation::Factorial(1),
+            &consts,
+        );
+        assert!(small < FLOAT_PRECISION);
+        assert!(small > 100);
+        assert!(large > small + 600);
+        // Termials only need the output precision
+        let termial = appropriate_precision(
+            &Number::Exact(Integer::from(10).pow(200u32)),
+            &Operation::Factorial(-1),
+            &consts,
+        );
+        assert!(termial < small);
+        let huge = appropriate_precision(
+            &Number::ApproximateDigits(false, Integer::from(10).pow(20u32)),
+            &Operation::Factorial(1),
+            &consts,
+        );
+        assert!(huge > consts.max_float_precision);
+    }
+
+    #[test]
+    fn test_execute_with_precision() {
+        let consts = Consts::default();
+        let job = CalculationJob {
+            base: CalculationBase::Num(Number::Float(Float::with_val(FLOAT_PRECISION, 1.5).into())),
+            operation: Operation::Factorial(1),
+            negative: 0,
+            span: Default::default(),
+        };
+        let [
+            Some(Calculation {
+                result: CalculationResult::Float(auto),
+                ..
+            }),
+        ] = &job.clone().execute(false, &consts)[..]
+        else {
+            panic!("Expected float result");
+        };
+        assert!(auto.as_float().prec() < FLOAT_PRECISION);
+        let [
+            Some(Calculation {
+                result: CalculationResult::Float(set),
+                ..
+            }),
+        ] = &job
+            .clone()
+            .execute_with_precision(false, Some(2048), &consts)[..]
+        else {
+            panic!("Expected float result");
+        };
+        assert_eq!(set.as_float().prec(), 2048);
+        assert_eq!(
+            Float::with_val(auto.as_float().prec(), set.as_float()),
+            *auto.as_float()
+        );
+        let [
+            Some(Calculation {
+                result: CalculationResult::Float(capped),
+                ..
+            }),
+        ] = &job.execute_with_precision(false, Some(u32::MAX), &consts)[..]
+        else {
+            panic!("Expected float result");
+        };
+        assert_eq!(capped.as_float().prec(), consts.max_float_precision);
+        let job = CalculationJob {
+            base: CalculationBase::Num(Number::Float(Float::with_val(FLOAT_PRECISION, 1.5).into())),
+            operation: Operation::Factorial(1),
+            negative: 0,
+            span: Default::default(),
+        };
+        let [
+            Some(Calculation {
+                result: CalculationResult::Float(raised),
+                ..
+            }),
+        ] = &job.execute_with_precision(false, Some(0), &consts)[..]
+        else {
+            panic!("Expected float result");
+        };
+        assert_eq!(raised.as_float().prec(), min_float_precision(&consts));
+        assert!(
+            Float::with_val(FLOAT_PRECISION, raised.as_float() - auto.as_float()).abs()
+                < Float::with_val(FLOAT_PRECISION, 10).pow(-30)
+        );
     }
 }
@@ -7,20 +7,31 @@ use crate::rug::integer::IntegerExt64;
 use crate::rug::{Complete, Integer};
 
 use crate::Consts;
-use crate::calculation_results::{Calculation, FormatOptions};
-use crate::calculation_tasks::{CalculationBase, CalculationJob};
-use crate::parse::parse;
+use crate::calculation_results::{Calculation, FormatOptions, Number};
+use crate::calculation_tasks::{CalculationBase, CalculationJob, Operation, min_float_precision};
+use crate::language;
+use crate::locale::{self, NamingSystem, ScientificStyle};
+use crate::markdown;
+use crate::math;
+use crate::parse::{
+    Diagnostic, DiagnosticKind, might_have_numeral, normalize_digits, parse_with_diagnostics,
+};
 
 use std::fmt::Write;
 use std::ops::*;
 #[macro_export]
 macro_rules! impl_bitwise {
     ($s_name:ident {$($s_fields:ident),*}, $t_name:ident, $fn_name:ident) => {
+        impl_bitwise!($s_name {$($s_fields),*;}, $t_name, $fn_name, or);
+    };
+    // value fields (Option) are combined with $v_fn_name instead
+    ($s_name:ident {$($s_fields:ident),*; $($v_fields:ident),*}, $t_name:ident, $fn_name:ident, $v_fn_name:ident) => {
         impl $t_name for $s_name {
             type Output = Self;
             fn $fn_name(self, rhs: Self) -> Self {
                 Self {
                     $($s_fields: self.$s_fields.$fn_name(rhs.$s_fields),)*
+                    $($v_fields: self.$v_fields.$v_fn_name(rhs.$v_fields),)*
                 }
             }
         }
@@ -29,15 +40,18 @@ macro_rules! impl_bitwise {
 #[macro_export]
 macro_rules! impl_all_bitwise {
     ($s_name:ident {$($s_fields:ident,)*}) => {impl_all_bitwise!($s_name {$($s_fields),*});};
-    ($s_name:ident {$($s_fields:ident),*}) => {
-        impl_bitwise!($s_name {$($s_fields),*}, BitOr, bitor);
-        impl_bitwise!($s_name {$($s_fields),*}, BitXor, bitxor);
-        impl_bitwise!($s_name {$($s_fields),*}, BitAnd, bitand);
+    ($s_name:ident {$($s_fields:ident),*}) => {impl_all_bitwise!($s_name {$($s_fields),*;});};
+    // value fields (Option) keep the left value if set (also for and), and are not affected by not
+    ($s_name:ident {$($s_fields:ident),* $(,)?; $($v_fields:ident),* $(,)?}) => {
+        impl_bitwise!($s_name {$($s_fields),*; $($v_fields),*}, BitOr, bitor, or);
+        impl_bitwise!($s_name {$($s_fields),*; $($v_fields),*}, BitXor, bitxor, xor);
+        impl_bitwise!($s_name {$($s_fields),*; $($v_fields),*}, BitAnd, bitand, or);
         impl Not for $s_name {
             type Output = Self;
             fn not(self) -> Self {
                 Self {
                     $($s_fields: self.$s_fields.not(),)*
+                    $($v_fields: self.$v_fields,)*
                 }
             }
         }
@@ -64,6 +78,18 @@ pub struct Comment<Meta, S> {
     /// How long the reply may at most be
     pub max_length: usize,
     pub locale: String,
+    /// Why things, that look like calculations, were not calculated. Collected when extracting.
+    #[cfg_attr(any(feature = "serde", test), serde(default))]
+    pub diagnostics: Vec<Diagnostic>,
+    /// Commands, that look misspelled, to point out in the reply.
+    #[cfg_attr(any(feature = "serde", test), serde(default))]
+    pub unknown_commands: Vec<UnknownCommand>,
+    /// Known commands, with an argument they can't use, to point out in the reply.
+    #[cfg_attr(any(feature = "serde", test), serde(default))]
+    pub invalid_commands: Vec<InvalidCommand>,
+    /// Locale asked for in the comment. Replaces locale when extracting if it is known, else it is kept to point that out.
+    #[cfg_attr(any(feature = "serde", test), serde(default))]
+    pub requested_locale: Option<String>,
 }
 /// Base [Comment], contains the comment text, if it might have a calculation. Use [extract](Comment::extract).
 pub type CommentConstructed<Meta> = Comment<Meta, String>;
@@ -164,6 +190,45 @@ pub struct Commands {
     /// Write out the number as a word if possible.
     #[cfg_attr(any(feature = "serde", test), serde(default))]
     pub write_out: bool,
+    /// Calculate the sum of the factorials of the digits of all numbers.
+    #[cfg_attr(any(feature = "serde", test), serde(default))]
+    pub factorion: bool,
+    /// Add statistics about the digits of the results.
+    #[cfg_attr(any(feature = "serde", test), serde(default))]
+    pub digits: bool,
+    /// Explain why nothing was calculated.
+    #[cfg_attr(any(feature = "serde", test), serde(default))]
+    pub why: bool,
+    /// Ignore calculations in markdown code (blocks and inline).
+    #[cfg_attr(any(feature = "serde", test), serde(default))]
+    pub skip_code: bool,
+    /// Ignore calculations in markdown blockquotes.
+    #[cfg_attr(any(feature = "serde", test), serde(default))]
+    pub skip_quotes: bool,
+    /// Ignore calculations that are more likely code (like `if (5!=x)`).
+    #[cfg_attr(any(feature = "serde", test), serde(default))]
+    pub filter_code: bool,
+    /// Parse Roman and CJK numerals (like `XII!` or `十二!`).
+    #[cfg_attr(any(feature = "serde", test), serde(default))]
+    pub numerals: bool,
+    /// Parse combinatorial functions (like `C(5)` or `S(5, 2)`).
+    #[cfg_attr(any(feature = "serde", test), serde(default))]
+    pub functions: bool,
+    /// Reply in the language the comment is written in (if it is clear), instead of the configured locale.
+    #[cfg_attr(any(feature = "serde", test), serde(default))]
+    pub detect_language: bool,
+    /// Float precision (in bits) to calculate with, instead of choosing one automatically.
+    #[cfg_attr(any(feature = "serde", test), serde(default))]
+    pub precision: Option<u32>,
+    /// Naming system for large numbers when writing out, instead of the locale's.
+    #[cfg_attr(any(feature = "serde", test), serde(default))]
+    pub naming: Option<NamingSystem>,
+    /// Style of scientific notation, instead of the locale's.
+    #[cfg_attr(any(feature = "serde", test), serde(default))]
+    pub scientific: Option<ScientificStyle>,
+    /// Base to write exact results in, instead of decimal.
+    #[cfg_attr(any(feature = "serde", test), serde(default))]
+    pub base: Option<u32>,
 }
 impl_all_bitwise!(Commands {
     shorten,
@@ -172,6 +237,19 @@ impl_all_bitwise!(Commands {
     termial,
     no_note,
     write_out,
+    factorion,
+    digits,
+    why,
+    skip_code,
+    skip_quotes,
+    filter_code,
+    numerals,
+    functions,
+    detect_language;
+    precision,
+    naming,
+    scientific,
+    base,
 });
 #[allow(dead_code)]
 impl Commands {
@@ -182,6 +260,19 @@ impl Commands {
         termial: false,
         no_note: false,
         write_out: false,
+        factorion: false,
+        digits: false,
+        why: false,
+        skip_code: false,
+        skip_quotes: false,
+        filter_code: false,
+        numerals: false,
+        functions: false,
+        detect_language: false,
+        precision: None,
+        naming: None,
+        scientific: None,
+        base: None,
     };
     pub const SHORTEN: Self = Self {
         shorten: true,
@@ -207,59 +298,401 @@ impl Commands {
         write_out: true,
         ..Self::NONE
     };
+    pub const FACTORION: Self = Self {
+        factorion: true,
+        ..Self::NONE
+    };
+    pub const DIGITS: Self = Self {
+        digits: true,
+        ..Self::NONE
+    };
+    pub const WHY: Self = Self {
+        why: true,
+        ..Self::NONE
+    };
+    pub const SKIP_CODE: Self = Self {
+        skip_code: true,
+        ..Self::NONE
+    };
+    pub const SKIP_QUOTES: Self = Self {
+        skip_quotes: true,
+        ..Self::NONE
+    };
+    pub const FILTER_CODE: Self = Self {
+        filter_code: true,
+        ..Self::NONE
+    };
+    pub const NUMERALS: Self = Self {
+        numerals: true,
+        ..Self::NONE
+    };
+    pub const FUNCTIONS: Self = Self {
+        functions: true,
+        ..Self::NONE
+    };
+    pub const DETECT_LANGUAGE: Self = Self {
+        detect_language: true,
+        ..Self::NONE
+    };
+}
+
+/// A command in a comment, written as `[name argument]`, `\[name argument\]` or `!name argument`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandToken<'a> {
+    /// The name in lower case, without markdown escapes (can have a space, like `no note`)
+    pub name: String,
+    /// The rest of the brackets, or for `!name` the next word (only used, if it is a valid argument)
+    pub argument: Option<&'a str>,
+    /// If it was written as `!name`
+    pub bang: bool,
+    /// The command as written
+    pub text: &'a str,
+}
+
+/// A command, that is not known, but looks like a misspelled one.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(any(feature = "serde", test), derive(Serialize, Deserialize))]
+pub struct UnknownCommand {
+    /// The command as written
+    pub text: String,
+    /// The closest known command
+    pub suggestion: String,
+}
+
+/// A known command, that is not used, as its argument is not valid (like `[base 99]` or `[digits 50]`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(any(feature = "serde", test), derive(Serialize, Deserialize))]
+pub struct InvalidCommand {
+    /// The command as written
+    pub text: String,
 }
 
+/// Changes the commands with the argument. Returns None, if the argument is not valid (or given, but not taken).
+type CommandAction = fn(&mut Commands, Option<&str>) -> Option<()>;
+
+macro_rules! flag_command {
+    ($field:ident = $value:expr) => {
+        |commands: &mut Commands, argument: Option<&str>| {
+            commands.$field = $value;
+            argument.is_none().then_some(())
+        }
+    };
+}
+
+/// The commands with all their spellings (lower case, without escapes).
+const COMMANDS: &[(&[&str], CommandAction)] = &[
+    (&["short", "shorten"], flag_command!(shorten = true)),
+    (&["steps", "all"], flag_command!(steps = true)),
+    (
+        &["nest", "nested", "no multi", "no_multi"],
+        flag_command!(nested = true),
+    ),
+    (&["termial", "triangle"], flag_command!(termial = true)),
+    (&["no note", "no_note"], flag_command!(no_note = true)),
+    (&["write_out", "write_num"], |commands, argument| {
+        commands.write_out = true;
+        if let Some(naming) = argument {
+            commands.naming = Some(naming.parse().ok()?);
+        }
+        Some(())
+    }),
+    (&["factorion", "digit_sum"], flag_command!(factorion = true)),
+    (&["digits"], flag_command!(digits = true)),
+    (&["why"], flag_command!(why = true)),
+    (&["skip_code"], flag_command!(skip_code = true)),
+    (&["skip_quotes"], flag_command!(skip_quotes = true)),
+    (&["filter_code"], flag_command!(filter_code = true)),
+    (&["numerals", "roman"], flag_command!(numerals = true)),
+    (&["functions"], flag_command!(functions = true)),
+    (&["detect_language"], flag_command!(detect_language = true)),
+    (&["precision"], |commands, argument| {
+        commands.precision = Some(argument?.parse().ok()?);
+        Some(())
+    }),
+    (&["scientific", "sci"], |commands, argument| {
+        commands.scientific = Some(argument?.parse().ok()?);
+        Some(())
+    }),
+    (&["base", "radix"], |commands, argument| {
+        let base = argument?.parse().ok()?;
+        (MIN_BASE..=MAX_BASE).contains(&base).then_some(())?;
+        commands.base = Some(base);
+        Some(())
+    }),
+];
+/// The commands turning something off, even if it is on by default (for the subreddit or channel).
+const OVERRIDES: &[(&[&str], CommandAction)] = &[
+    (&["long"], flag_command!(shorten = false)),
+    (&["no steps", "no_steps"], flag_command!(steps = false)),
+    (&["no_nest", "multi"], flag_command!(nested = false)),
+    (
+        &["no termial", "no_termial"],
+        flag_command!(termial = false),
+    ),
+    (&["note"], flag_command!(no_note = false)),
+    (
+        &["dont_write_out", "normal num", "normal_num"],
+        flag_command!(write_out = false),
+    ),
+    (&["no_skip_code"], flag_command!(skip_code = false)),
+    (&["no_skip_quotes"], flag_command!(skip_quotes = false)),
+    (&["no_filter_code"], flag_command!(filter_code = false)),
+    (&["no_numerals"], flag_command!(numerals = false)),
+    (&["no_functions"], flag_command!(functions = false)),
+    (
+        &["no_detect_language"],
+        flag_command!(detect_language = false),
+    ),
+];
+/// The commands switching the locale of the reply, see [locale_from_comment_text](Commands::locale_from_comment_text).
+const LOCALE_COMMANDS: &[&str] = &["lang", "locale", "language"];
+/// The bases results can be written in, see [to_string_radix](Integer::to_string_radix)
+pub const MIN_BASE: u32 = 2;
+pub const MAX_BASE: u32 = 36;
+
 impl Commands {
-    fn contains_command_format(text: &str, command: &str) -> bool {
-        let pattern1 = format!("\\[{command}\\]");
-        let pattern2 = format!("[{command}]");
-        let pattern3 = format!("!{command}");
-        text.contains(&pattern1) || text.contains(&pattern2) || text.contains(&pattern3)
+    /// Finds all commands (known or not) in the text, in order.
+    pub fn tokens(text: &str) -> Vec<CommandToken<'_>> {
+        let mut tokens = Vec::new();
+        let mut i = 0;
+        while i < text.len() {
+            let rest = &text[i..];
+            let token = if let Some(content) = rest.strip_prefix("\\[") {
+                Self::bracket_token(text, i, content, "\\[".len())
+            } else if let Some(content) = rest.strip_prefix('[') {
+                Self::bracket_token(text, i, content, "[".len())
+            } else if let Some(name) = rest.strip_prefix('!') {
+                Self::bang_token(text, i, name)
+            } else {
+                None
+            };
+            if let Some(token) = token {
+                i += token.text.len();
+                tokens.push(token);
+            } else {
+                i += rest.chars().next().map_or(1, char::len_utf8);
+            }
+        }
+        tokens
     }
 
-    pub fn from_comment_text(text: &str) -> Self {
-        Self {
-            shorten: Self::contains_command_format(text, "short")
-                || Self::contains_command_format(text, "shorten"),
-            steps: Self::contains_command_format(text, "steps")
-                || Self::contains_command_format(text, "all"),
-            nested: Self::contains_command_format(text, "nest")
-                || Self::contains_command_format(text, "nested")
-                || Self::contains_command_format(text, "no multi")
-                || Self::contains_command_format(text, "no\\_multi")
-                || Self::contains_command_format(text, "no_multi"),
-            termial: Self::contains_command_format(text, "termial")
-                || Self::contains_command_format(text, "triangle"),
-            no_note: Self::contains_command_format(text, "no note")
-                || Self::contains_command_format(text, "no\\_note")
-                || Self::contains_command_format(text, "no_note"),
-            write_out: Self::contains_command_format(text, "write_out")
-                || Self::contains_command_format(text, "write\\_out")
-                || Self::contains_command_format(text, "write_num")
-                || Self::contains_command_format(text, "write\\_num"),
+    /// Gets `[name argument]` (the opening bracket being before content), closed on the same line
+    fn bracket_token<'a>(
+        text: &'a str,
+        start: usize,
+        content: &'a str,
+        open_len: usize,
+    ) -> Option<CommandToken<'a>> {
+        let end = content.find([']', '[', '\n'])?;
+        if !content[end..].starts_with(']') {
+            return None;
+        }
+        let token_end = start + open_len + end + 1;
+        // A markdown link, not a command
+        if text[token_end..].starts_with('(') {
+            return None;
+        }
+        let content = content[..end].trim_end_matches('\\').trim();
+        let name = Self::normalize_name(content);
+        let (name, argument) = if Self::is_known(&name) {
+            (name, None)
+        } else {
+            match content.split_once(char::is_whitespace) {
+                Some((name, argument)) => (Self::normalize_name(name), Some(argument.trim())),
+                None => (name, None),
+            }
+        };
+        Some(CommandToken {
+            name,
+            argument,
+            bang: false,
+            text: &text[start..token_end],
+        })
+    }
+
+    /// Gets `!name argument` (the `!` being before rest), the argument being the next word
+    fn bang_token<'a>(text: &'a str, start: usize, rest: &'a str) -> Option<CommandToken<'a>> {
+        let word_len = |text: &str| {
+            let mut chars = text.char_indices().peekable();
+            while let Some((i, c)) = chars.next() {
+                let escape = c == '\\' && chars.peek().is_some_and(|(_, c)| *c == '_');
+                if !(c.is_ascii_alphabetic() || c == '_' || escape) {
+                    return i;
+                }
+            }
+            text.len()
+        };
+        let mut len = word_len(rest);
+        if len == 0 {
+            return None;
+        }
+        // Names with a space (like `no note`)
+        if let Some(second) = rest[len..].strip_prefix(' ') {
+            let second_len = word_len(second);
+            if second_len > 0
+                && Self::is_known(&Self::normalize_name(&rest[..len + 1 + second_len]))
+            {
+                len += 1 + second_len;
+            }
+        }
+        let after = &rest[len..];
+        let argument = after
+            .starts_with(char::is_whitespace)
+            .then(|| after.split_whitespace().next())
+            .flatten();
+        Some(CommandToken {
+            name: Self::normalize_name(&rest[..len]),
+            argument,
+            bang: true,
+            text: &text[start..start + 1 + len],
+        })
+    }
+
+    fn normalize_name(name: &str) -> String {
+        name.replace("\\_", "_").to_lowercase()
+    }
+
+    fn known_names() -> impl Iterator<Item = &'static str> {
+        COMMANDS
+            .iter()
+            .chain(OVERRIDES)
+            .flat_map(|(names, _)| names.iter())
+            .chain(LOCALE_COMMANDS)
+            .copied()
+    }
+
+    fn is_known(name: &str) -> bool {
+        Self::known_names().any(|known| known == name)
+    }
+
+    /// Applies the commands of the table found in the text to start. \
+    /// With `!name` the argument is optional, so it is only used if valid.
+    fn from_tokens(text: &str, table: &[(&[&str], CommandAction)], start: Self) -> Self {
+        let mut commands = start;
+        for token in Self::tokens(text) {
+            if let Some(changed) = Self::apply_token(&token, table, commands) {
+                commands = changed;
+            }
+        }
+        commands
+    }
+
+    /// The commands changed by the token, if it is in the table and its argument is valid
+    fn apply_token(
+        token: &CommandToken,
+        table: &[(&[&str], CommandAction)],
+        commands: Self,
+    ) -> Option<Self> {
+        let (_, action) = table
+            .iter()
+            .find(|(names, _)| names.contains(&token.name.as_str()))?;
+        let mut changed = commands;
+        if action(&mut changed, token.argument).is_some() {
+            return Some(changed);
         }
+        let mut changed = commands;
+        (token.bang && action(&mut changed, None).is_some()).then_some(changed)
     }
+
+    pub fn from_comment_text(text: &str) -> Self {
+        Self::from_tokens(text, COMMANDS, Self::NONE)
+    }
+    /// Gets the commands turning things off, everything else is on (so it can be used with `&`).
     pub fn overrides_from_comment_text(text: &str) -> Self {
-        Self {
-            shorten: !Self::contains_command_format(text, "long"),
-            steps: !(Self::contains_command_format(text, "no steps")
-                || Self::contains_command_format(text, "no_steps")
-                || Self::contains_command_format(text, "no\\_steps")),
-            nested: !(Self::contains_command_format(text, "no_nest")
-                || Self::contains_command_format(text, "no\\_nest")
-                || Self::contains_command_format(text, "multi")),
-            termial: !(Self::contains_command_format(text, "no termial")
-                || Self::contains_command_format(text, "no_termial")
-                || Self::contains_command_format(text, "no\\_termial")),
-            no_note: !Self::contains_command_format(text, "note"),
-            write_out: !(Self::contains_command_format(text, "dont_write_out")
-                || Self::contains_command_format(text, "dont\\_write\\_out")
-                || Self::contains_command_format(text, "normal num")
-                || Self::contains_command_format(text, "normal\\_num")),
+        Self::from_tokens(text, OVERRIDES, !Self::NONE)
+    }
+    /// Gets the commands, that are not known, but close to a known one (so probably misspelled).
+    pub fn unknown_from_comment_text(text: &str) -> Vec<UnknownCommand> {
+        let mut unknown: Vec<UnknownCommand> = Vec::new();
+        for token in Self::tokens(text) {
+            if Self::is_known(&token.name) || unknown.iter().any(|cmd| cmd.text == token.text) {
+                continue;
+            }
+            // Allow less typos for short names, as they are more likely just words
+            let max_distance = if token.name.chars().count() <= 4 {
+                1
+            } else {
+                2
+            };
+            let closest = Self::known_names()
+                .map(|name| (edit_distance(&token.name, name), name))
+                .filter(|(distance, _)| *distance <= max_distance)
+                .min_by_key(|(distance, _)| *distance);
+            if let Some((_, suggestion)) = closest {
+                unknown.push(UnknownCommand {
+                    text: token.text.to_owned(),
+                    suggestion: suggestion.to_owned(),
+                });
+            }
         }
+        unknown
+    }
+    /// Whether the text uses any known command (even with an invalid argument). \
+    /// Only then the unknown ones are probably misspelled commands, and not just words in brackets (like `[best]`).
+    pub fn any_known_in_comment_text(text: &str) -> bool {
+        Self::tokens(text)
+            .iter()
+            .any(|token| Self::is_known(&token.name))
+    }
+    /// Gets the known commands, that can't be used with their argument (or without one, like `[precision]`).
+    pub fn invalid_from_comment_text(text: &str) -> Vec<InvalidCommand> {
+        let mut invalid: Vec<InvalidCommand> = Vec::new();
+        for token in Self::tokens(text) {
+            let known = COMMANDS
+                .iter()
+                .chain(OVERRIDES)
+                .any(|(names, _)| names.contains(&token.name.as_str()));
+            if !known
+                || Self::apply_token(&token, COMMANDS, Self::NONE).is_some()
+                || Self::apply_token(&token, OVERRIDES, !Self::NONE).is_some()
+                || invalid.iter().any(|cmd| cmd.text == token.text)
+            {
+                continue;
+            }
+            invalid.push(InvalidCommand {
+                text: token.text.to_owned(),
+            });
+        }
+        invalid
+    }
+    /// Gets the locale asked for with `[lang de]` or `!locale de` (the last one, if there are multiple). \
+    /// It is not checked, if the locale exists.
+    pub fn locale_from_comment_text(text: &str) -> Option<String> {
+        Self::tokens(text)
+            .into_iter()
+            .filter(|token| LOCALE_COMMANDS.contains(&token.name.as_str()))
+            .filter_map(|token| token.argument)
+            .map(|locale| locale.replace("\\_", "_"))
+            .rfind(|locale| {
+                !locale.is_empty()
+                    && locale
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+            })
+    }
+    /// If the (deprecated) nested command, or its override, is used
+    fn mentions_nested(text: &str) -> bool {
+        Self::tokens(text)
+            .iter()
+            .any(|token| matches!(token.name.as_str(), "nest" | "nested" | "no_nest"))
     }
 }
 
+/// Levenshtein distance between a and b (in chars)
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != *b);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
 macro_rules! contains_comb {
     // top level (advance both separately)
     ($var:ident, [$start:tt,$($start_rest:tt),* $(,)?], [$end:tt,$($end_rest:tt),* $(,)?]) => {
@@ -309,18 +742,22 @@ impl<Meta> CommentConstructed<Meta> {
             (Commands::from_comment_text(comment_text) | pre_commands) & command_overrides;
 
         let mut status: Status = Default::default();
-        if Commands::contains_command_format(comment_text, "nest")
-            || Commands::contains_command_format(comment_text, "nested")
-            || Commands::contains_command_format(comment_text, "no_nest")
-            || Commands::contains_command_format(comment_text, "no\\_nest")
-        {
+        if Commands::mentions_nested(comment_text) {
             status.nested_used = true;
         }
 
-        let text = if Self::might_have_factorial(comment_text) {
+        // Any number can be a factorion
+        let normalized_text = normalize_digits(comment_text);
+        let might_have_factorial = commands.factorion
+            || Self::might_have_factorial(&normalized_text)
+            || (commands.numerals && might_have_numeral(&normalized_text));
+        if !might_have_factorial {
+            status.no_factorial = true;
+        }
+        // Keep the text anyway to explain why, as almost calculations might not look like them
+        let text = if might_have_factorial || commands.why {
             comment_text.to_owned()
         } else {
-            status.no_factorial = true;
             String::new()
         };
 
@@ -332,6 +769,14 @@ impl<Meta> CommentConstructed<Meta> {
             commands,
             max_length,
             locale: locale.to_owned(),
+            diagnostics: Vec::new(),
+            unknown_commands: if Commands::any_known_in_comment_text(comment_text) {
+                Commands::unknown_from_comment_text(comment_text)
+            } else {
+                Vec::new()
+            },
+            invalid_commands: Commands::invalid_from_comment_text(comment_text),
+            requested_locale: Commands::locale_from_comment_text(comment_text),
         }
     }
 
@@ -362,7 +807,7 @@ impl<Meta> CommentConstructed<Meta> {
                 "∞\u{303}",
                 "∞"
             ],
-            ["!", "?"]
+            ["!", "?", "$", "^(", "\\^(", "↑"]
         ) || contains_comb!(
             text,
             ["!"],
@@ -390,7 +835,8 @@ impl<Meta> CommentConstructed<Meta> {
                 "∞\u{303}",
                 "∞"
             ]
-        )
+        ) || contains_comb!(text, [")"], ["_", "\\_"])
+            || contains_comb!(text, ["C", "B", "S", "s", "D"], ["("])
     }
 
     /// Extracts the calculations using [parse](mod@crate::parse).
@@ -400,20 +846,70 @@ impl<Meta> CommentConstructed<Meta> {
             calculation_list: comment_text,
             notify,
             mut status,
-            commands,
+            mut commands,
             max_length,
-            locale,
+            mut locale,
+            diagnostics: _,
+            unknown_commands,
+            mut invalid_commands,
+            mut requested_locale,
         } = self;
-        let mut pending_list: Vec<CalculationJob> = parse(
+        // Switch to the requested locale, or the one it is based on (ignoring case, as commands do)
+        if let Some(requested) = &requested_locale
+            && let Some(known) =
+                locale::fallback_chain(&requested.replace('-', "_")).find_map(|requested| {
+                    consts
+                        .locales
+                        .keys()
+                        .find(|known| known.eq_ignore_ascii_case(requested))
+                })
+        {
+            locale = known.clone();
+            requested_locale = None;
+        } else if requested_locale.is_none()
+            && commands.detect_language
+            && let Some(detected) = language::detect(
+                &comment_text,
+                consts.get_language_profiles(),
+                &consts.locales,
+                &locale,
+            )
+        {
+            locale = detected.to_owned();
+        }
+        // Lower precisions can't give the shown decimals
+        let min_precision = min_float_precision(consts);
+        if commands
+            .precision
+            .is_some_and(|precision| precision < min_precision)
+        {
+            commands.precision = None;
+        }
+        for token in Commands::tokens(&comment_text) {
+            let too_low = token
+                .argument
+                .and_then(|argument| argument.parse::<u32>().ok())
+                .is_some_and(|precision| precision < min_precision);
+            if token.name == "precision"
+                && too_low
+                && !invalid_commands.iter().any(|cmd| cmd.text == token.text)
+            {
+                invalid_commands.push(InvalidCommand {
+                    text: token.text.to_owned(),
+                });
+            }
+        }
+        let comment_text =
+            markdown::mask_skipped(&comment_text, commands.skip_code, commands.skip_quotes);
+        let (mut pending_list, diagnostics) = parse_with_diagnostics(
             &comment_text,
             commands.termial,
+            commands.factorion.then_some(Operation::DigitFactorialSum),
+            commands.filter_code,
+            commands.numerals,
+            commands.functions,
             consts,
-            &consts
-                .locales
-                .get(&locale)
-                .unwrap_or(consts.locales.get(&consts.default_locale).unwrap())
-                .format
-                .number_format,
+            &consts.get_locale(&locale).0.format.number_format,
         );
 
         if commands.nested {
@@ -422,9 +918,7 @@ impl<Meta> CommentConstructed<Meta> {
             }
         }
 
-        if pending_list.is_empty() {
-            status.no_factorial = true;
-        }
+        status.no_factorial = pending_list.is_empty();
 
         Comment {
             meta,
@@ -434,14 +928,23 @@ impl<Meta> CommentConstructed<Meta> {
             commands,
             max_length,
             locale,
+            diagnostics,
+            unknown_commands,
+            invalid_commands,
+            requested_locale,
         }
     }
 
     fn multi_to_nested(mut calc: &mut CalculationJob) {
         loop {
-            let level = calc.level.clamp(-1, 1);
-            let depth = calc.level.abs();
-            calc.level = level;
+            let depth = match &mut calc.operation {
+                Operation::Factorial(level) => {
+                    let depth = level.abs();
+                    *level = (*level).clamp(-1, 1);
+                    depth
+                }
+                _ => 1,
+            };
             for _ in 1..depth {
                 let base = std::mem::replace(
                     &mut calc.base,
@@ -451,8 +954,9 @@ impl<Meta> CommentConstructed<Meta> {
                 );
                 let new_base = CalculationBase::Calc(Box::new(CalculationJob {
                     base,
-                    level,
+                    operation: calc.operation.clone(),
                     negative: 0,
+                    span: calc.span,
                 }));
                 let _ = std::mem::replace(&mut calc.base, new_base);
             }
@@ -480,6 +984,10 @@ impl<Meta> CommentConstructed<Meta> {
             commands,
             max_length,
             locale: locale.to_owned(),
+            diagnostics: Vec::new(),
+            unknown_commands: Vec::new(),
+            invalid_commands: Vec::new(),
+            requested_locale: None,
         }
     }
 }
@@ -499,10 +1007,16 @@ impl<Meta> CommentExtracted<Meta> {
             commands,
             max_length,
             locale,
+            diagnostics,
+            unknown_commands,
+            invalid_commands,
+            requested_locale,
         } = self;
         let mut calculation_list: Vec<Calculation> = pending_list
             .into_iter()
-            .flat_map(|calc| calc.execute(commands.steps, consts))
+            .flat_map(|calc| {
+                calc.execute_with_precision(commands.steps, commands.precision, consts)
+            })
             .filter_map(|x| {
                 if x.is_none() {
                     status.number_too_big_to_calculate = true;
@@ -511,8 +1025,8 @@ impl<Meta> CommentExtracted<Meta> {
             })
             .collect();
 
-        calculation_list.sort();
-        calculation_list.dedup();
+        calculation_list.sort_by(Calculation::cmp_calculation);
+        calculation_list.dedup_by(|a, b| a.cmp_calculation(b).is_eq());
         calculation_list.sort_by_key(|x| x.steps.len());
 
         if calculation_list.is_empty() {
@@ -528,34 +1042,58 @@ impl<Meta> CommentExtracted<Meta> {
             commands,
             max_length,
             locale,
+            diagnostics,
+            unknown_commands,
+            invalid_commands,
+            requested_locale,
         }
     }
 }
 impl<Meta> CommentCalculated<Meta> {
     /// Does the formatting for the reply using [calculation_result](crate::calculation_results).
     pub fn get_reply(&self, consts: &Consts) -> String {
-        let mut fell_back = false;
-        let locale = consts.locales.get(&self.locale).unwrap_or_else(|| {
-            fell_back = true;
-            consts.locales.get(&consts.default_locale).unwrap()
-        });
+        let (locale, fell_back) = consts.get_locale(&self.locale);
         let mut note = self
             .notify
             .as_ref()
             .map(|user| locale.notes.mention.replace("{mention}", user) + "\n\n")
             .unwrap_or_default();
 
-        if fell_back {
+        // A requested locale is only kept, if it doesn't exist
+        let not_spoken = match &self.requested_locale {
+            Some(requested) => Some(requested),
+            None => fell_back.then_some(&self.locale),
+        };
+        if let Some(not_spoken) = not_spoken {
             let _ = note.write_str("Sorry, I currently don't speak ");
-            let _ = note.write_str(&self.locale);
+            let _ = note.write_str(not_spoken);
             let _ = note.write_str(". Maybe you could [teach me](https://github.com/tolik518/factorion-bot/blob/master/CONTRIBUTING.md#translation)? \n\n");
         }
 
+        if self.commands.why && self.calculation_list.is_empty() {
+            let mut reply = note;
+            self.add_why(locale, &mut reply);
+            if !locale.bot_disclaimer.is_empty() {
+                reply.push_str("\n*^(");
+                reply.push_str(&locale.bot_disclaimer);
+                reply.push_str(")*");
+            }
+            return reply;
+        }
+
         let too_big_number = Integer::u64_pow_u64(10, self.max_length as u64).complete();
         let too_big_number = &too_big_number;
 
         let multiple = self.add_note(consts, locale, &mut note, too_big_number);
 
+        // SI prefixes only exist for shortened numbers, so with them everything is shortened from the start
+        let shorten = self.commands.shorten
+            || self
+                .commands
+                .scientific
+                .or(locale.format.number_format.scientific)
+                == Some(ScientificStyle::Si);
+
         // Add Factorials
         let mut reply = self.add_factorials(
             consts,
@@ -563,15 +1101,27 @@ impl<Meta> CommentCalculated<Meta> {
             &note,
             too_big_number,
             FormatOptions {
-                force_shorten: self.commands.shorten,
+                force_shorten: shorten,
                 write_out: self.commands.write_out,
+                naming: self.commands.naming,
+                scientific: self.commands.scientific,
+                base: self.commands.base,
                 ..FormatOptions::NONE
             },
         );
 
+        // Add the digit statistics, only if they fit without shortening anything
+        if self.commands.digits {
+            let digit_stats = self.add_digit_stats(consts, locale);
+            if reply.len() + digit_stats.len() + locale.bot_disclaimer.len() + 16 <= self.max_length
+            {
+                reply.push_str(&digit_stats);
+            }
+        }
+
         // If the reply was too long try force shortening all factorials
         if reply.len() + locale.bot_disclaimer.len() + 16 > self.max_length
-            && !self.commands.shorten
+            && !shorten
             && !self
                 .calculation_list
                 .iter()
@@ -592,6 +1142,9 @@ impl<Meta> CommentCalculated<Meta> {
                 too_big_number,
                 FormatOptions {
                     write_out: self.commands.write_out,
+                    naming: self.commands.naming,
+                    scientific: self.commands.scientific,
+                    base: self.commands.base,
                     ..FormatOptions::FORCE_SHORTEN
                 },
             );
@@ -612,6 +1165,9 @@ impl<Meta> CommentCalculated<Meta> {
                 too_big_number,
                 FormatOptions {
                     write_out: self.commands.write_out,
+                    naming: self.commands.naming,
+                    scientific: self.commands.scientific,
+                    base: self.commands.base,
                     ..{ FormatOptions::FORCE_SHORTEN | FormatOptions::AGRESSIVE_SHORTEN }
                 },
             );
@@ -632,6 +1188,75 @@ impl<Meta> CommentCalculated<Meta> {
         reply
     }
 
+    /// Explains why nothing was calculated, using the diagnostics (as many as fit).
+    fn add_why(&self, locale: &crate::locale::Locale<'_>, reply: &mut String) {
+        let notes = &locale.notes;
+        let mut reasons: Vec<String> = self
+            .diagnostics
+            .iter()
+            .map(|diagnostic| {
+                let (reason, default) = match diagnostic.kind {
+                    DiagnosticKind::InUri => (
+                        &notes.why_in_uri,
+                        "`{calculation}` is part of a link, so I left it alone.",
+                    ),
+                    DiagnosticKind::Escaped => (
+                        &notes.why_escaped,
+                        "The op in `{calculation}` is escaped with a backslash.",
+                    ),
+                    DiagnosticKind::IntegerOnlyOp => (
+                        &notes.why_integer_only,
+                        "`{calculation}` is only defined for whole numbers.",
+                    ),
+                    DiagnosticKind::TooBig => (
+                        &notes.why_too_big,
+                        "`{calculation}` needs the exact number, but it is too big for me to know it.",
+                    ),
+                    DiagnosticKind::UnbalancedParen => (
+                        &notes.why_unbalanced_paren,
+                        "The parenthesis in `{calculation}` is not matched.",
+                    ),
+                    DiagnosticKind::LooksLikeCode => (
+                        &notes.why_code,
+                        "`{calculation}` looks like code, so I left it alone.",
+                    ),
+                };
+                reason
+                    .as_ref()
+                    .map(AsRef::as_ref)
+                    .unwrap_or(default)
+                    .replace("{calculation}", &diagnostic.text)
+            })
+            .collect();
+        reasons.dedup();
+        if reasons.is_empty() {
+            let _ = reply.write_str(
+                notes
+                    .why_nothing
+                    .as_ref()
+                    .map(AsRef::as_ref)
+                    .unwrap_or("I didn't find anything that looks like a calculation."),
+            );
+            let _ = reply.write_str("\n\n");
+            return;
+        }
+        let _ = reply.write_str(
+            notes
+                .why
+                .as_ref()
+                .map(AsRef::as_ref)
+                .unwrap_or("I didn't calculate anything, because:"),
+        );
+        let _ = reply.write_str("\n\n");
+        for reason in reasons {
+            if reply.len() + reason.len() + locale.bot_disclaimer.len() + 20 > self.max_length {
+                break;
+            }
+            let _ = writeln!(reply, "- {reason}");
+        }
+        reply.push('\n');
+    }
+
     fn add_factorials_to_fit(
         &self,
         consts: &Consts<'_>,
@@ -651,6 +1276,9 @@ impl<Meta> CommentCalculated<Meta> {
                         FormatOptions {
                             agressive_shorten: !self.commands.steps,
                             write_out: self.commands.write_out,
+                            naming: self.commands.naming,
+                            scientific: self.commands.scientific,
+                            base: self.commands.base,
                             ..FormatOptions::FORCE_SHORTEN
                         },
                         too_big_number,
@@ -704,6 +1332,15 @@ impl<Meta> CommentCalculated<Meta> {
             })
     }
 
+    fn add_digit_stats(&self, consts: &Consts<'_>, locale: &crate::locale::Locale<'_>) -> String {
+        self.calculation_list
+            .iter()
+            .fold(String::new(), |mut acc, factorial| {
+                let _ = factorial.format_digit_stats(&mut acc, consts, &locale.format);
+                acc
+            })
+    }
+
     fn add_note(
         &self,
         consts: &Consts<'_>,
@@ -720,6 +1357,62 @@ impl<Meta> CommentCalculated<Meta> {
             );
             let _ = note.write_str("\n\n");
         }
+        for unknown in &self.unknown_commands {
+            let _ = note.write_str(
+                &locale
+                    .notes
+                    .unknown_command
+                    .as_ref()
+                    .map(AsRef::as_ref)
+                    .unwrap_or("I don't know the command `{command}`, did you mean `{suggestion}`?")
+                    .replace("{command}", &unknown.text)
+                    .replace("{suggestion}", &unknown.suggestion),
+            );
+            let _ = note.write_str("\n\n");
+        }
+        for invalid in &self.invalid_commands {
+            let _ = note.write_str(
+                &locale
+                    .notes
+                    .invalid_command
+                    .as_ref()
+                    .map(AsRef::as_ref)
+                    .unwrap_or("I can't use the command `{command}` like that, so I ignored it.")
+                    .replace("{command}", &invalid.text),
+            );
+            let _ = note.write_str("\n\n");
+        }
+        if !self.commands.no_note {
+            // 1 and 2 are posted all the time, so they are only pointed out when asked for
+            let mut factorions: Vec<&Integer> = self
+                .calculation_list
+                .iter()
+                .filter_map(|calc| match &calc.value {
+                    Number::Exact(n)
+                        if math::is_factorion(n) && (self.commands.factorion || *n > 2) =>
+                    {
+                        Some(n)
+                    }
+                    _ => None,
+                })
+                .collect();
+            factorions.sort();
+            factorions.dedup();
+            for factorion in factorions {
+                let _ = note.write_str(
+                    &locale
+                        .notes
+                        .factorion
+                        .as_ref()
+                        .map(AsRef::as_ref)
+                        .unwrap_or(
+                            "{number} is a factorion, it is the sum of the factorials of its digits!",
+                        )
+                        .replace("{number}", &factorion.to_string()),
+                );
+                let _ = note.write_str("\n\n");
+            }
+        }
         if !self.commands.no_note {
             if self.status.limit_hit {
                 let _ = note.write_str(
@@ -733,6 +1426,21 @@ impl<Meta> CommentCalculated<Meta> {
                         ),
                 );
                 let _ = note.write_str("\n\n");
+            } else if self
+                .calculation_list
+                .iter()
+                .any(Calculation::is_digit_hyper)
+            {
+                if multiple {
+                    let _ = note.write_str(locale.notes.hyper_mult.as_ref().map(AsRef::as_ref).unwrap_or(
+                        "Some of these are so large, that not even a power of ten tower fits, so I can only give bounds with up-arrows.",
+                    ));
+                } else {
+                    let _ = note.write_str(locale.notes.hyper.as_ref().map(AsRef::as_ref).unwrap_or(
+                        "That is so large, that not even a power of ten tower fits, so I can only give bounds with up-arrows.",
+                    ));
+                }
+                let _ = note.write_str("\n\n");
             } else if self
                 .calculation_list
                 .iter()
@@ -794,9 +1502,11 @@ impl<Meta> CommentCalculated<Meta> {
                     let _ = note.write_str(&locale.notes.too_big);
                     let _ = note.write_str("\n\n");
                 }
-            } else if self.commands.write_out && self.locale != "en" {
-                let _ =
-                    note.write_str("I can only write out numbers in english, so I will do that.");
+            } else if self.commands.write_out && locale.format.write_out.is_none() {
+                let _ = note.write_str(locale.notes.write_out_unsupported.as_ref().map_or(
+                    "I can only write out numbers in english, so I will do that.",
+                    AsRef::as_ref,
+                ));
                 let _ = note.write_str("\n\n");
             }
         }
@@ -808,8 +1518,9 @@ impl<Meta> CommentCalculated<Meta> {
 mod tests {
     use crate::{
         calculation_results::Number,
-        calculation_tasks::{CalculationBase, CalculationJob},
+        calculation_tasks::{CalculationBase, CalculationJob, Span},
         locale::NumFormat,
+        parse::parse,
     };
 
     const MAX_LENGTH: usize = 10_000;
@@ -821,46 +1532,48 @@ mod tests {
     #[test]
     fn test_extraction_dedup() {
         let consts = Consts::default();
-        let jobs = parse(
-            "24! -24! 2!? (2!?)!",
-            true,
-            &consts,
-            &NumFormat { decimal: '.' },
-        );
+        let jobs = parse("24! -24! 2!? (2!?)!", true, &consts, &NumFormat::default());
         assert_eq!(
             jobs,
             [
                 CalculationJob {
                     base: CalculationBase::Num(Number::Exact(24.into())),
-                    level: 1,
-                    negative: 0
+                    operation: Operation::Factorial(1),
+                    negative: 0,
+                    span: Span::new(0, 3),
                 },
                 CalculationJob {
                     base: CalculationBase::Num(Number::Exact(24.into())),
-                    level: 1,
-                    negative: 1
+                    operation: Operation::Factorial(1),
+                    negative: 1,
+                    span: Span::new(5, 8),
                 },
                 CalculationJob {
                     base: CalculationBase::Calc(Box::new(CalculationJob {
                         base: CalculationBase::Num(Number::Exact(2.into())),
-                        level: 1,
-                        negative: 0
+                        operation: Operation::Factorial(1),
+                        negative: 0,
+                        span: Span::new(9, 11),
                     })),
-                    level: -1,
-                    negative: 0
+                    operation: Operation::Factorial(-1),
+                    negative: 0,
+                    span: Span::new(9, 12),
                 },
                 CalculationJob {
                     base: CalculationBase::Calc(Box::new(CalculationJob {
                         base: CalculationBase::Calc(Box::new(CalculationJob {
                             base: CalculationBase::Num(Number::Exact(2.into())),
-                            level: 1,
-                            negative: 0
+                            operation: Operation::Factorial(1),
+                            negative: 0,
+                            span: Span::new(14, 16),
                         })),
-                        level: -1,
-                        negative: 0
+                        operation: Operation::Factorial(-1),
+                        negative: 0,
+                        span: Span::new(14, 17),
                     })),
-                    level: 1,
-                    negative: 0
+                    operation: Operation::Factorial(1),
+                    negative: 0,
+                    span: Span::new(13, 19),
                 }
             ]
         );
@@ -895,6 +1608,239 @@ mod tests {
         assert!(!cmd4.nested);
     }
 
+    #[test]
+    fn test_commands_naming_from_comment_text() {
+        let cmd1 = Commands::from_comment_text("13! [write_out long]");
+        assert_eq!(cmd1.naming, Some(NamingSystem::Long));
+        assert!(cmd1.write_out);
+        let cmd2 = Commands::from_comment_text(r"13! \[write\_out conway\_wechsler\]");
+        assert_eq!(cmd2.naming, Some(NamingSystem::ConwayWechsler));
+        assert!(cmd2.write_out);
+        let cmd3 = Commands::from_comment_text("13! !write_out knuth");
+        assert_eq!(cmd3.naming, Some(NamingSystem::Knuth));
+        assert!(cmd3.write_out);
+        let cmd4 = Commands::from_comment_text("13! [write_out]");
+        assert_eq!(cmd4.naming, None);
+        assert!(cmd4.write_out);
+        let cmd5 = Commands::from_comment_text("13! [write_out metric]");
+        assert_eq!(cmd5.naming, None);
+        assert!(!cmd5.write_out);
+    }
+    #[test]
+    fn test_commands_scientific_from_comment_text() {
+        let cmd1 = Commands::from_comment_text("3000! [scientific e]");
+        assert_eq!(cmd1.scientific, Some(ScientificStyle::ENotation));
+        let cmd2 = Commands::from_comment_text(r"3000! \[sci engineering\]");
+        assert_eq!(cmd2.scientific, Some(ScientificStyle::Engineering));
+        let cmd3 = Commands::from_comment_text("3000! !scientific SI");
+        assert_eq!(cmd3.scientific, Some(ScientificStyle::Si));
+        let cmd4 = Commands::from_comment_text("3000! [scientific fancy]");
+        assert_eq!(cmd4.scientific, None);
+    }
+
+    #[test]
+    fn test_commands_why_from_comment_text() {
+        assert!(Commands::from_comment_text("5\\! [why]").why);
+        assert!(Commands::from_comment_text(r"5\! \[why\]").why);
+        assert!(Commands::from_comment_text("5\\! !why").why);
+        assert!(!Commands::from_comment_text("but why 5\\!").why);
+    }
+
+    #[test]
+    fn test_commands_skip_from_comment_text() {
+        assert!(Commands::from_comment_text("[skip_code]").skip_code);
+        assert!(Commands::from_comment_text(r"\[skip\_quotes\]").skip_quotes);
+        assert!(!Commands::overrides_from_comment_text("!no_skip_code").skip_code);
+        assert!(Commands::overrides_from_comment_text("!no_skip_code").skip_quotes);
+        assert!(Commands::from_comment_text("[filter_code]").filter_code);
+        assert!(!Commands::overrides_from_comment_text(r"\[no\_filter\_code\]").filter_code);
+    }
+
+    #[test]
+    fn test_commands_numerals_from_comment_text() {
+        assert!(Commands::from_comment_text("XII! [numerals]").numerals);
+        assert!(Commands::from_comment_text(r"XII! \[roman\]").numerals);
+        assert!(!Commands::from_comment_text("XII! numerals").numerals);
+        assert!(!Commands::overrides_from_comment_text("!no_numerals").numerals);
+        assert!(Commands::overrides_from_comment_text("!numerals").numerals);
+    }
+
+    #[test]
+    fn test_commands_functions_from_comment_text() {
+        assert!(Commands::from_comment_text("C(5) [functions]").functions);
+        assert!(!Commands::from_comment_text("C(5) functions").functions);
+        assert!(!Commands::overrides_from_comment_text("!no_functions").functions);
+    }
+
+    #[test]
+    fn test_commands_precision_from_comment_text() {
+        let cmd1 = Commands::from_comment_text("5.5! [precision 2048]");
+        assert_eq!(cmd1.precision, Some(2048));
+        let cmd2 = Commands::from_comment_text(r"5.5! \[precision 64\]");
+        assert_eq!(cmd2.precision, Some(64));
+        let cmd3 = Commands::from_comment_text("5.5! !precision 100 !shorten");
+        assert_eq!(cmd3.precision, Some(100));
+        assert!(cmd3.shorten);
+        let cmd4 = Commands::from_comment_text("5.5! [precision high] precision 100");
+        assert_eq!(cmd4.precision, None);
+        let cmd5 = cmd1 | Commands::from_comment_text("[precision 64]");
+        assert_eq!(cmd5.precision, Some(2048));
+        assert_eq!((!cmd1).precision, Some(2048));
+    }
+
+    #[test]
+    fn test_commands_factorion_from_comment_text() {
+        assert!(Commands::from_comment_text("[factorion] 145").factorion);
+        assert!(Commands::from_comment_text(r"\[digit\_sum\] 145").factorion);
+        assert!(Commands::from_comment_text("!digit_sum 145").factorion);
+        assert!(!Commands::from_comment_text("factorion 145").factorion);
+    }
+
+    #[test]
+    fn test_commands_digits_from_comment_text() {
+        assert!(Commands::from_comment_text("[digits] 10!").digits);
+        assert!(Commands::from_comment_text(r"\[digits\] 10!").digits);
+        assert!(Commands::from_comment_text("!digits 10!").digits);
+        assert!(!Commands::from_comment_text("digits 10!").digits);
+    }
+
+    #[test]
+    fn test_commands_tokens() {
+        let tokens = Commands::tokens(
+            r"5! [Precision 200] !STEPS \[write\_out long\] [link](x) !no note 3!",
+        );
+        let tokens = tokens
+            .iter()
+            .map(|token| (token.name.as_str(), token.argument, token.bang, token.text))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tokens,
+            [
+                ("precision", Some("200"), false, "[Precision 200]"),
+                ("steps", Some(r"\[write\_out"), true, "!STEPS"),
+                ("write_out", Some("long"), false, r"\[write\_out long\]"),
+                ("no note", Some("3!"), true, "!no note"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_commands_case_insensitive() {
+        let cmd = Commands::from_comment_text("[SHORTEN] !Steps [No_Note] [Write_Out Long]");
+        assert!(cmd.shorten);
+        assert!(cmd.steps);
+        assert!(cmd.no_note);
+        assert_eq!(cmd.naming, Some(NamingSystem::Long));
+        assert!(!Commands::overrides_from_comment_text("[LONG]").shorten);
+    }
+
+    #[test]
+    fn test_commands_base_from_comment_text() {
+        assert_eq!(Commands::from_comment_text("[base 16]").base, Some(16));
+        assert_eq!(Commands::from_comment_text(r"\[radix 2\]").base, Some(2));
+        assert_eq!(Commands::from_comment_text("!base 36 5!").base, Some(36));
+        assert_eq!(Commands::from_comment_text("[base 1]").base, None);
+        assert_eq!(Commands::from_comment_text("[base 37]").base, None);
+        assert_eq!(Commands::from_comment_text("!base 5!").base, None);
+    }
+
+    #[test]
+    fn test_commands_unknown_from_comment_text() {
+        let unknown = Commands::unknown_from_comment_text(
+            "[shortn] 5! [stesp] [deleted] [sic] [link](x) !stpes [shortn] [precison 200] !important",
+        );
+        let unknown = unknown
+            .iter()
+            .map(|cmd| (cmd.text.as_str(), cmd.suggestion.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            unknown,
+            [
+                ("[shortn]", "short"),
+                ("[stesp]", "steps"),
+                ("!stpes", "steps"),
+                ("[precison 200]", "precision"),
+            ]
+        );
+        assert!(Commands::unknown_from_comment_text("[short] [no note] !no_note").is_empty());
+    }
+
+    #[test]
+    fn test_commands_any_known_in_comment_text() {
+        assert!(Commands::any_known_in_comment_text("[sort] [short] 5!"));
+        assert!(Commands::any_known_in_comment_text(
+            "[best] [precision abc]"
+        ));
+        assert!(Commands::any_known_in_comment_text("!lang de"));
+        assert!(!Commands::any_known_in_comment_text(
+            "[best] [song] [case] 5!"
+        ));
+    }
+
+    #[test]
+    fn test_commands_invalid_from_comment_text() {
+        let invalid = Commands::invalid_from_comment_text(
+            "[digits 50] [precision abc] [base 99] [precision] !base x [long 5] [digits 50] [shortn 5]",
+        );
+        let invalid = invalid
+            .iter()
+            .map(|cmd| cmd.text.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            invalid,
+            [
+                "[digits 50]",
+                "[precision abc]",
+                "[base 99]",
+                "[precision]",
+                "!base",
+                "[long 5]",
+            ]
+        );
+        assert!(
+            Commands::invalid_from_comment_text(
+                "[digits] [precision 200] [base 16] !steps 5! [long] !base 2 [lang de]"
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_commands_locale_from_comment_text() {
+        assert_eq!(
+            Commands::locale_from_comment_text("5! [lang de]"),
+            Some("de".to_owned())
+        );
+        assert_eq!(
+            Commands::locale_from_comment_text("!locale fr 5!"),
+            Some("fr".to_owned())
+        );
+        assert_eq!(
+            Commands::locale_from_comment_text(r"\[Language en\_fuck\] [lang it]"),
+            Some("it".to_owned())
+        );
+        assert_eq!(Commands::locale_from_comment_text("[lang] 5!"), None);
+        assert_eq!(Commands::locale_from_comment_text("!lang 5!"), None);
+        assert_eq!(Commands::locale_from_comment_text("lang de 5!"), None);
+        assert!(Commands::unknown_from_comment_text("[lang de] !locale fr").is_empty());
+    }
+
+    #[test]
+    fn test_commands_detect_language_from_comment_text() {
+        assert!(Commands::from_comment_text("[detect_language]").detect_language);
+        assert!(Commands::from_comment_text(r"\[detect\_language\]").detect_language);
+        assert!(!Commands::overrides_from_comment_text("!no_detect_language").detect_language);
+        assert!(Commands::overrides_from_comment_text("[no_numerals]").detect_language);
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("steps", "steps"), 0);
+        assert_eq!(edit_distance("shortn", "shorten"), 1);
+        assert_eq!(edit_distance("stpes", "steps"), 2);
+        assert_eq!(edit_distance("", "why"), 3);
+    }
+
     #[test]
     fn test_commands_overrides_from_comment_text() {
         let cmd1 = Commands::overrides_from_comment_text("long no_steps no_termial note multi");
@@ -948,14 +1894,17 @@ mod tests {
 
     #[test]
     fn test_write_out_unsupported_note() {
-        let consts = Consts::default();
+        let mut consts = Consts::default();
+        let mut de = crate::locale::get_de();
+        de.format.write_out = None;
+        consts.locales.insert("de".to_owned(), de);
         let comment = Comment::new("1!", (), Commands::WRITE_OUT, MAX_LENGTH, "de")
             .extract(&consts)
             .calc(&consts);
         let reply = comment.get_reply(&consts);
         assert_eq!(
             reply,
-            "I can only write out numbers in english, so I will do that.\n\nFakultät von one ist one \n\n\n*^(Dieser Kommentar wurde automatisch geschrieben | [Quelltext](http://f.r0.fyi))*"
+            "Ich kann nur in Englisch Zahlen ausschreiben, also werde ich das tun.\n\nFakultät von one ist one \n\n\n*^(Dieser Kommentar wurde automatisch geschrieben | [Quelltext](http://f.r0.fyi))*"
         );
     }
 
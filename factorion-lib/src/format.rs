@@ -1,11 +1,13 @@
 //! This module holds the underlying formatting functions used in [`calculation_result`]
+use crate::calculation_tasks::Operation;
 use crate::{Consts, calculation_results::FormatOptions, locale};
 use core::fmt;
 use factorion_math::{
     length,
     rug::{Complete, Float, Integer, float::OrdFloat, integer::IntegerExt64, ops::Pow},
+    tetration_height,
 };
-use std::{borrow::Cow, fmt::Write};
+use std::{borrow::Cow, collections::HashMap, fmt::Write};
 
 const SINGLES: [&str; 10] = [
     "", "un", "duo", "tre", "quattuor", "quin", "sex", "septen", "octo", "novem",
@@ -88,6 +90,72 @@ const BINDING_T: [[bool; 10]; 6] = [
         false, false, false, false, false, false, false, false, false, false,
     ],
 ];
+pub fn get_operation_string<'a>(
+    operation: &Operation,
+    locale: &'a locale::Format<'a>,
+) -> Cow<'a, str> {
+    match operation {
+        Operation::Factorial(level) => get_factorial_level_string(level.abs(), locale),
+        Operation::ExponentialFactorial => locale
+            .exponential
+            .as_ref()
+            .map_or("exponential {factorial}", AsRef::as_ref)
+            .into(),
+        Operation::Compositorial => locale
+            .compositorial
+            .as_ref()
+            .map_or("compositorial", AsRef::as_ref)
+            .into(),
+        Operation::RisingFactorial(_) => locale
+            .rising
+            .as_ref()
+            .map_or("rising {factorial} of order {operand}", AsRef::as_ref)
+            .into(),
+        Operation::FallingFactorial(_) => locale
+            .falling
+            .as_ref()
+            .map_or("falling {factorial} of order {operand}", AsRef::as_ref)
+            .into(),
+        Operation::Catalan => locale
+            .catalan
+            .as_ref()
+            .map_or("Catalan number", AsRef::as_ref)
+            .into(),
+        Operation::Bell => locale
+            .bell
+            .as_ref()
+            .map_or("Bell number", AsRef::as_ref)
+            .into(),
+        Operation::StirlingSecond(_) => locale
+            .stirling_second
+            .as_ref()
+            .map_or(
+                "Stirling number of the second kind (k = {operand})",
+                AsRef::as_ref,
+            )
+            .into(),
+        Operation::StirlingFirst(_) => locale
+            .stirling_first
+            .as_ref()
+            .map_or(
+                "Stirling number of the first kind (k = {operand})",
+                AsRef::as_ref,
+            )
+            .into(),
+        Operation::Rencontres(_) => locale
+            .rencontres
+            .as_ref()
+            .map_or("rencontres number (k = {operand})", AsRef::as_ref)
+            .into(),
+        Operation::DigitFactorialSum => locale
+            .digit_sum
+            .as_ref()
+            .map_or("sum of the {factorial}s of the digits", AsRef::as_ref)
+            .into(),
+        // The modulus is in the result template
+        Operation::FactorialModulo(_) => "{factorial}".into(),
+    }
+}
 pub fn get_factorial_level_string<'a>(level: i32, locale: &'a locale::Format<'a>) -> Cow<'a, str> {
     if let Some(s) = locale.num_overrides.get(&level) {
         return s.as_ref().into();
@@ -96,39 +164,8 @@ pub fn get_factorial_level_string<'a>(level: i32, locale: &'a locale::Format<'a>
         0 => locale.sub.as_ref().into(),
         1 => "{factorial}".into(),
         ..=999999 if !locale.force_num => {
-            let singles = if level < 10 { SINGLES_LAST } else { SINGLES };
-            let mut acc = String::new();
-            let mut n = level;
-            let s = n % 10;
-            n /= 10;
-            acc.write_str(singles[s as usize]).unwrap();
-            let t = n % 10;
-            n /= 10;
-            acc.write_str(TENS[t as usize]).unwrap();
-            let h = n % 10;
-            n /= 10;
-            acc.write_str(HUNDREDS[h as usize]).unwrap();
-            let th = n % 10;
-            n /= 10;
-            acc.write_str(THOUSANDS[th as usize]).unwrap();
-            let tth = n % 10;
-            n /= 10;
-            acc.write_str(TEN_THOUSANDS[tth as usize]).unwrap();
-            let hth = n % 10;
-            acc.write_str(HUNDRED_THOUSANDS[hth as usize]).unwrap();
-            // Check if we need tuple not uple
-            let last_written = [s, t, h, th, tth, hth]
-                .iter()
-                .cloned()
-                .enumerate()
-                .rev()
-                .find(|(_, n)| *n != 0)
-                .unwrap();
-            if BINDING_T[last_written.0][last_written.1 as usize] {
-                acc.write_str("t").unwrap();
-            }
-            acc.write_str(&locale.uple).unwrap();
-
+            let mut acc = latin_prefix(level as u32, SINGLES_LAST).concat();
+            acc.push_str(&locale.uple);
             acc.into()
         }
         _ => {
@@ -138,112 +175,453 @@ pub fn get_factorial_level_string<'a>(level: i32, locale: &'a locale::Format<'a>
         }
     }
 }
-const EN_SINGLES: [&str; 10] = [
-    "", "one ", "two ", "three ", "four ", "five ", "six ", "seven ", "eight ", "nine ",
-];
-const EN_TENS: [&str; 10] = [
-    "", "ten ", "twenty ", "thirty ", "forty ", "fifty ", "sixty ", "seventy ", "eighty ",
-    "ninety ",
-];
-const EN_TENS_SINGLES: [&str; 10] = [
-    "ten ",
-    "eleven ",
-    "twelve ",
-    "thirteen ",
-    "fourteen ",
-    "fifteen ",
-    "sixteen ",
-    "seventeen ",
-    "eighteen ",
-    "nineteen ",
-];
 const SINGLES_LAST_ILLION: [&str; 10] = [
     "", "m", "b", "tr", "quadr", "quint", "sext", "sept", "oct", "non",
 ];
-// TODO: localize (illion, illiard, type of scale, numbers, digit order, thousand)
-pub fn write_out_number(acc: &mut String, num: &Integer, consts: &Consts) -> std::fmt::Result {
-    if num == &0 {
-        return acc.write_str("zero");
+/// Gets the latin parts making up the name of the `n`th -uple or -illion. \
+/// `singles_last` is used for the single digit, if `n` is below 10.
+fn latin_prefix(n: u32, singles_last: [&'static str; 10]) -> Vec<&'static str> {
+    let singles = if n < 10 { singles_last } else { SINGLES };
+    let digits = [
+        n % 10,
+        n / 10 % 10,
+        n / 100 % 10,
+        n / 1000 % 10,
+        n / 10000 % 10,
+        n / 100000 % 10,
+    ];
+    let mut parts: Vec<_> = [
+        singles,
+        TENS,
+        HUNDREDS,
+        THOUSANDS,
+        TEN_THOUSANDS,
+        HUNDRED_THOUSANDS,
+    ]
+    .iter()
+    .zip(digits)
+    .map(|(names, d)| names[d as usize])
+    .filter(|part| !part.is_empty())
+    .collect();
+    // Check if we need tuple not uple
+    if let Some((place, d)) = digits.iter().enumerate().rev().find(|(_, d)| **d != 0)
+        && BINDING_T[place][*d as usize]
+    {
+        parts.push("t");
     }
-    let negative = num < &0;
-    let num = Float::with_val(consts.float_precision, num).abs();
-    let ten = Float::with_val(consts.float_precision, 10);
-    let digit_blocks = num
-        .clone()
-        .log10()
-        .to_u32_saturating_round(factorion_math::rug::float::Round::Down)
-        .unwrap()
-        / 3;
-    if negative {
-        acc.write_str("minus ")?;
+    parts
+}
+/// Gets the settings to write out numbers in the locale, or if it has none, in the default locale.
+pub(crate) fn get_write_out<'a>(
+    locale: &'a locale::Format<'_>,
+    consts: &'a Consts<'_>,
+) -> Option<&'a locale::WriteOut<'a>> {
+    locale.write_out.as_ref().or_else(|| {
+        consts
+            .locales
+            .get(&consts.default_locale)
+            .and_then(|l| l.format.write_out.as_ref())
+    })
+}
+fn number_word(words: &HashMap<u32, Cow<'_, str>>, n: u32) -> String {
+    words
+        .get(&n)
+        .map_or_else(|| n.to_string(), |w| w.to_string())
+}
+/// Writes out a number below 100. \
+/// `last` selects the forms used at the end of a number, `units` overrides the words for the units.
+fn write_out_below_hundred(
+    acc: &mut String,
+    n: u32,
+    last: bool,
+    units: Option<&HashMap<u32, Cow<'_, str>>>,
+    locale: &locale::WriteOut<'_>,
+) {
+    let unit = |u: u32| {
+        units
+            .and_then(|units| units.get(&u))
+            .map_or_else(|| number_word(&locale.numbers, u), |w| w.to_string())
+    };
+    if let Some(word) = units
+        .filter(|_| n < 10)
+        .and_then(|units| units.get(&n))
+        .or_else(|| {
+            locale
+                .numbers_final
+                .as_ref()
+                .filter(|_| last)
+                .and_then(|numbers| numbers.get(&n))
+        })
+        .or_else(|| locale.numbers.get(&n))
+    {
+        acc.push_str(word);
+    } else {
+        acc.push_str(
+            &locale
+                .tens
+                .replace("{ten}", &number_word(&locale.numbers, n / 10 * 10))
+                .replace("{unit}", &unit(n % 10)),
+        );
     }
-    for digit_blocks_left in (digit_blocks.saturating_sub(5)..=digit_blocks).rev() {
-        let current_digits = Float::to_u32_saturating_round(
-            &((num.clone() / ten.clone().pow(digit_blocks_left * 3)) % 1000),
-            factorion_math::rug::float::Round::Down,
-        )
-        .unwrap();
-        let mut n = current_digits;
-        let s = n % 10;
-        n /= 10;
-        let t = n % 10;
-        n /= 10;
-        let h = n % 10;
-        acc.write_str(EN_SINGLES[h as usize])?;
-        if h != 0 {
-            acc.write_str("hundred ")?;
+}
+/// Writes out a block of three digits.
+fn write_out_block(
+    acc: &mut String,
+    n: u32,
+    last: bool,
+    units: Option<&HashMap<u32, Cow<'_, str>>>,
+    locale: &locale::WriteOut<'_>,
+) {
+    let hundreds = n / 100;
+    let n = n % 100;
+    let mut rest = String::new();
+    if n != 0 {
+        write_out_below_hundred(&mut rest, n, last, units, locale);
+    }
+    if hundreds == 0 {
+        acc.push_str(&rest);
+        return;
+    }
+    let hundred = locale
+        .hundreds_final
+        .as_ref()
+        .filter(|_| last && n == 0)
+        .and_then(|words| words.get(&hundreds))
+        .map_or_else(
+            || number_word(&locale.hundreds, hundreds),
+            |w| w.to_string(),
+        );
+    if n == 0 {
+        acc.push_str(&hundred);
+        return;
+    }
+    let mut hundred = hundred.as_str();
+    // Drop a doubled vowel at the join (cento + otto = centotto)
+    if locale.elide
+        && let Some(end) = hundred.chars().next_back()
+        && rest.starts_with(end)
+    {
+        hundred = &hundred[..hundred.len() - end.len_utf8()];
+    }
+    acc.push_str(
+        &locale
+            .hundred
+            .replace("{hundred}", hundred)
+            .replace("{rest}", &rest),
+    );
+}
+const CONWAY_WECHSLER_UNITS: [&str; 10] = [
+    "", "un", "duo", "tre", "quattuor", "quinqua", "se", "septe", "octo", "nove",
+];
+// The letters are the markers, that change the units in front of them
+const CONWAY_WECHSLER_TENS: [(&str, &str); 10] = [
+    ("", ""),
+    ("deci", "n"),
+    ("viginti", "ms"),
+    ("triginta", "ns"),
+    ("quadraginta", "ns"),
+    ("quinquaginta", "ns"),
+    ("sexaginta", "n"),
+    ("septuaginta", "n"),
+    ("octoginta", "mx"),
+    ("nonaginta", ""),
+];
+const CONWAY_WECHSLER_HUNDREDS: [(&str, &str); 10] = [
+    ("", ""),
+    ("centi", "nx"),
+    ("ducenti", "n"),
+    ("trecenti", "ns"),
+    ("quadringenti", "ns"),
+    ("quingenti", "ns"),
+    ("sescenti", "n"),
+    ("septingenti", "n"),
+    ("octingenti", "mx"),
+    ("nongenti", ""),
+];
+/// The -illion index from which the Conway–Wechsler system is always used, as the tables end there
+const CONWAY_WECHSLER_FROM: u32 = 1_000_000;
+fn localize_prefix<'a>(part: &'a str, locale: &'a locale::WriteOut<'_>) -> &'a str {
+    locale
+        .prefixes
+        .as_ref()
+        .and_then(|prefixes| prefixes.get(part))
+        .map_or(part, AsRef::as_ref)
+}
+/// Writes the prefix of the `n`th -illion in the Conway–Wechsler system (without the stem). \
+/// Every group of three digits gets a prefix, joined by "illi" ("n" for empty groups).
+fn write_conway_wechsler_prefix(acc: &mut String, n: u32, locale: &locale::WriteOut<'_>) {
+    let mut groups = vec![];
+    let mut n = n;
+    loop {
+        groups.push(n % 1000);
+        n /= 1000;
+        if n == 0 {
+            break;
         }
-        if t == 1 {
-            acc.write_str(EN_TENS_SINGLES[s as usize])?;
-        } else {
-            acc.write_str(EN_TENS[t as usize])?;
-            acc.write_str(EN_SINGLES[s as usize])?;
+    }
+    for (i, group) in groups.into_iter().rev().enumerate() {
+        if i != 0 {
+            acc.push_str(localize_prefix("illi", locale));
         }
-
-        if digit_blocks_left > 0 && current_digits != 0 {
-            let singles = if digit_blocks_left < 10 {
-                SINGLES_LAST_ILLION
-            } else {
-                SINGLES
-            };
-            let mut n = digit_blocks_left - 1;
-            if n > 0 {
-                let s = n % 10;
-                n /= 10;
-                acc.write_str(singles[s as usize]).unwrap();
-                let t = n % 10;
-                n /= 10;
-                acc.write_str(TENS[t as usize]).unwrap();
-                let h = n % 10;
-                n /= 10;
-                acc.write_str(HUNDREDS[h as usize]).unwrap();
-                let th = n % 10;
-                n /= 10;
-                acc.write_str(THOUSANDS[th as usize]).unwrap();
-                let tth = n % 10;
-                n /= 10;
-                acc.write_str(TEN_THOUSANDS[tth as usize]).unwrap();
-                let hth = n % 10;
-                acc.write_str(HUNDRED_THOUSANDS[hth as usize]).unwrap();
-                // Check if we need tuple not uple
-                let last_written = [s, t, h, th, tth, hth]
-                    .iter()
-                    .cloned()
-                    .enumerate()
-                    .rev()
-                    .find(|(_, n)| *n != 0)
-                    .unwrap();
-                if BINDING_T[last_written.0][last_written.1 as usize] {
-                    acc.write_str("t").unwrap();
+        match group {
+            0 => acc.push_str(localize_prefix("n", locale)),
+            1..10 => acc.push_str(localize_prefix(SINGLES_LAST_ILLION[group as usize], locale)),
+            _ => {
+                let (tens, tens_markers) = CONWAY_WECHSLER_TENS[(group / 10 % 10) as usize];
+                let (hundreds, hundreds_markers) = CONWAY_WECHSLER_HUNDREDS[(group / 100) as usize];
+                let markers = if tens.is_empty() {
+                    hundreds_markers
+                } else {
+                    tens_markers
+                };
+                let units = match CONWAY_WECHSLER_UNITS[(group % 10) as usize] {
+                    "tre" if markers.contains(['s', 'x']) => "tres",
+                    "se" if markers.contains('s') => "ses",
+                    "se" if markers.contains('x') => "sex",
+                    "septe" if markers.contains('m') => "septem",
+                    "septe" if markers.contains('n') => "septen",
+                    "nove" if markers.contains('m') => "novem",
+                    "nove" if markers.contains('n') => "noven",
+                    units => units,
+                };
+                for part in [units, tens, hundreds] {
+                    if !part.is_empty() {
+                        acc.push_str(localize_prefix(part, locale));
+                    }
                 }
-                acc.write_str("illion ")?;
-            } else {
-                acc.write_str("thousand ")?;
+                // The last vowel is replaced by the following "illi" or stem
+                acc.pop();
             }
         }
     }
-    acc.pop();
-    Ok(())
+}
+/// Writes the name of the `n`th -illion (or -illiard or -yllion) of the scale.
+fn write_out_scale_name(
+    acc: &mut String,
+    n: u32,
+    system: locale::NamingSystem,
+    scale: &locale::Scale<'_>,
+    locale: &locale::WriteOut<'_>,
+) {
+    let start = acc.len();
+    if let Some(name) = scale.names.as_ref().and_then(|names| names.get(&n)) {
+        acc.push_str(name);
+    } else {
+        if system == locale::NamingSystem::ConwayWechsler || n >= CONWAY_WECHSLER_FROM {
+            write_conway_wechsler_prefix(acc, n, locale);
+        } else {
+            for part in latin_prefix(n, SINGLES_LAST_ILLION) {
+                acc.push_str(localize_prefix(part, locale));
+            }
+        }
+        acc.push_str(scale.stem.as_ref().map_or("", AsRef::as_ref));
+    }
+    if scale.capitalize
+        && let Some(first) = acc[start..].chars().next()
+    {
+        let upper = first.to_uppercase().to_string();
+        acc.replace_range(start..start + first.len_utf8(), &upper);
+    }
+}
+/// Writes a written out number followed by a scale word (like "five thousand"). \
+/// `last_two` are the last two digits of the number, used to pick the form.
+fn write_out_scaled(
+    acc: &mut String,
+    number: &str,
+    is_one: bool,
+    last_two: u32,
+    name: &str,
+    scale: &locale::Scale<'_>,
+) {
+    let template = match &scale.one {
+        Some(one) if is_one => one,
+        _ => scale
+            .by_last_digit
+            .as_ref()
+            .filter(|_| !(10..20).contains(&last_two))
+            .and_then(|forms| forms.get(&(last_two % 10)))
+            .unwrap_or(&scale.other),
+    };
+    acc.push_str(&template.replace("{number}", number).replace("{name}", name));
+}
+/// Writes out blocks of three digits (from most significant), given as their index and value. \
+/// `last` selects the final forms for the last block.
+fn write_out_blocks(
+    acc: &mut String,
+    blocks: impl IntoIterator<Item = (u32, u32)>,
+    last: bool,
+    system: locale::NamingSystem,
+    locale: &locale::WriteOut<'_>,
+) {
+    let mut separator = "";
+    for (digit_blocks_left, current_digits) in blocks {
+        if current_digits == 0 {
+            continue;
+        }
+        acc.push_str(separator);
+        if digit_blocks_left == 0 {
+            write_out_block(acc, current_digits, last, None, locale);
+            continue;
+        }
+        // Long scale alternates between -illion and -illiard after the thousands
+        let (scale, n) = match (system, &locale.illiard, digit_blocks_left) {
+            (_, _, 1) => (&locale.thousand, 0),
+            (locale::NamingSystem::Long, Some(illiard), b) if b % 2 == 1 => (illiard, b / 2),
+            (locale::NamingSystem::Long, Some(_), b) => (&locale.illion, b / 2),
+            (_, _, b) => (&locale.illion, b - 1),
+        };
+        let mut number = String::new();
+        write_out_block(
+            &mut number,
+            current_digits,
+            scale.final_forms,
+            scale.numbers.as_ref(),
+            locale,
+        );
+        let mut name = String::new();
+        if n > 0 {
+            write_out_scale_name(&mut name, n, system, scale, locale);
+        }
+        write_out_scaled(
+            acc,
+            &number,
+            current_digits == 1,
+            current_digits % 100,
+            &name,
+            scale,
+        );
+        separator = &scale.separator;
+    }
+}
+/// Writes out a number in Knuth's -yllion system, where every name squares the one before: \
+/// myriad (10^4), myllion (10^8), byllion (10^16), ...
+fn write_out_knuth(
+    acc: &mut String,
+    num: &Integer,
+    last: bool,
+    consts: &Consts,
+    [myriad, yllion]: [&locale::Scale<'_>; 2],
+    locale: &locale::WriteOut<'_>,
+) {
+    let digits = length(num, consts.float_precision)
+        .to_u32()
+        .unwrap_or(u32::MAX);
+    if digits <= 4 {
+        let num = num.to_u32().unwrap_or(0);
+        write_out_blocks(
+            acc,
+            [(1, num / 1000), (0, num % 1000)],
+            last,
+            locale::NamingSystem::Knuth,
+            locale,
+        );
+        return;
+    }
+    // The largest myriad power (4, 8, 16, ... digits), that still fits
+    let mut level = 0;
+    while 8 << level < digits {
+        level += 1;
+    }
+    let (high, low) = num
+        .div_rem_ref(&Integer::u_pow_u(10, 4 << level).complete())
+        .complete();
+    let scale = if level == 0 { myriad } else { yllion };
+    let mut number = String::new();
+    write_out_knuth(
+        &mut number,
+        &high,
+        scale.final_forms,
+        consts,
+        [myriad, yllion],
+        locale,
+    );
+    let mut name = String::new();
+    if level > 0 {
+        write_out_scale_name(
+            &mut name,
+            level,
+            locale::NamingSystem::Knuth,
+            yllion,
+            locale,
+        );
+    }
+    write_out_scaled(acc, &number, high == 1, high.mod_u(100), &name, scale);
+    if low != 0 {
+        acc.push_str(&scale.separator);
+        write_out_knuth(acc, &low, last, consts, [myriad, yllion], locale);
+    }
+}
+/// Gets the naming system to use, the requested one, if the locale supports it, otherwise the locale's.
+fn get_naming_system(
+    locale: &locale::WriteOut<'_>,
+    requested: Option<locale::NamingSystem>,
+) -> locale::NamingSystem {
+    [requested, locale.system]
+        .into_iter()
+        .flatten()
+        .find(|system| match system {
+            locale::NamingSystem::Long => locale.illiard.is_some(),
+            locale::NamingSystem::Knuth => locale.myriad.is_some() && locale.yllion.is_some(),
+            _ => true,
+        })
+        .unwrap_or_default()
+}
+/// Writes out a number in words, as set in the locale, naming large numbers with the requested system if supported. \
+/// Only the six most significant blocks of three digits are written, the rest is left out.
+pub fn write_out_number(
+    acc: &mut String,
+    num: &Integer,
+    consts: &Consts,
+    locale: &locale::WriteOut<'_>,
+    system: Option<locale::NamingSystem>,
+) -> std::fmt::Result {
+    if num == &0 {
+        return acc.write_str(&locale.zero);
+    }
+    let negative = num < &0;
+    let mut words = String::new();
+    let system = get_naming_system(locale, system);
+    if system == locale::NamingSystem::Knuth
+        && let (Some(myriad), Some(yllion)) = (&locale.myriad, &locale.yllion)
+    {
+        let mut num = num.clone().abs();
+        let digits = length(&num, consts.float_precision)
+            .to_u32()
+            .unwrap_or(u32::MAX);
+        // Keep the same 18 significant digits as for the other systems
+        if digits > 18 {
+            let cut = Integer::u_pow_u(10, digits - 18).complete();
+            num /= &cut;
+            num *= cut;
+        }
+        write_out_knuth(&mut words, &num, true, consts, [myriad, yllion], locale);
+    } else {
+        let num = Float::with_val(consts.float_precision, num).abs();
+        let ten = Float::with_val(consts.float_precision, 10);
+        let digit_blocks = num
+            .clone()
+            .log10()
+            .to_u32_saturating_round(factorion_math::rug::float::Round::Down)
+            .unwrap()
+            / 3;
+        let blocks =
+            (digit_blocks.saturating_sub(5)..=digit_blocks)
+                .rev()
+                .map(|digit_blocks_left| {
+                    let current_digits = Float::to_u32_saturating_round(
+                        &((num.clone() / ten.clone().pow(digit_blocks_left * 3)) % 1000),
+                        factorion_math::rug::float::Round::Down,
+                    )
+                    .unwrap();
+                    (digit_blocks_left, current_digits)
+                });
+        write_out_blocks(&mut words, blocks, true, system, locale);
+    }
+    if negative {
+        acc.write_str(&locale.negative.replace("{number}", &words))
+    } else {
+        acc.write_str(&words)
+    }
 }
 /// Rounds a base 10 number string. \
 /// Uses the last digit to decide the rounding direction. \
@@ -285,7 +663,14 @@ pub fn round(number: &mut String) -> bool {
     }
     false
 }
-pub fn truncate(number: &Integer, consts: &Consts) -> (String, bool) {
+/// Shortens a number to scientific notation in the given style, if it is longer than number_decimals_scientific
+/// (or has an SI prefix, when that is the style). \
+/// Also returns, if that lost precision.
+pub fn truncate(
+    number: &Integer,
+    consts: &Consts,
+    style: locale::ScientificStyle,
+) -> (String, bool) {
     let prec = consts.float_precision;
     if number == &0 {
         return (number.to_string(), false);
@@ -327,17 +712,22 @@ pub fn truncate(number: &Integer, consts: &Consts) -> (String, bool) {
     if negative {
         truncated_number.insert(0, '-');
     }
-    if length > consts.number_decimals_scientific + 1 {
-        if truncated_number == "1" {
-            (format!("10^{length}"), rough)
-        } else {
-            (format!("{truncated_number} × 10^{length}"), rough)
-        }
+    if length > consts.number_decimals_scientific + 1 || uses_si_prefix(style, &length) {
+        let mut acc = String::new();
+        let mantissa = (truncated_number != "1").then_some(truncated_number.as_str());
+        let _ = write_scientific(&mut acc, mantissa, &length, style, None);
+        (acc, rough)
     } else {
         (orig_number.to_string(), false)
     }
 }
-pub fn format_float(acc: &mut String, number: &Float, consts: &Consts) -> std::fmt::Result {
+pub fn format_float(
+    acc: &mut String,
+    number: &Float,
+    consts: &Consts,
+    number_format: &locale::NumFormat,
+    style: locale::ScientificStyle,
+) -> std::fmt::Result {
     // -a.b x 10^c
     // -
     // a
@@ -355,9 +745,10 @@ pub fn format_float(acc: &mut String, number: &Float, consts: &Consts) -> std::f
         .to_integer_round(factorion_math::rug::float::Round::Down)
         .expect("Could not round exponent")
         .0;
-    if exponent > consts.number_decimals_scientific
+    let scientific = exponent > consts.number_decimals_scientific
         || exponent < -(consts.number_decimals_scientific as isize)
-    {
+        || uses_si_prefix(style, &exponent);
+    if scientific {
         number /= Float::with_val(consts.float_precision, &exponent).exp10();
     }
     let mut whole_number = number
@@ -383,22 +774,164 @@ pub fn format_float(acc: &mut String, number: &Float, consts: &Consts) -> std::f
         }
         decimal_part.push(digit);
     }
+    let mut mantissa = String::new();
     if negative {
-        acc.write_str("-")?;
+        mantissa.push('-');
     }
-    write!(acc, "{whole_number}")?;
+    mantissa.push_str(&group_digits(&whole_number.to_string(), number_format));
     if !decimal_part.is_empty() && decimal_part != "0" {
-        acc.write_str(".")?;
-        acc.write_str(&decimal_part)?;
+        mantissa.push('.');
+        mantissa.push_str(&decimal_part);
     }
-    if exponent > consts.number_decimals_scientific
-        || exponent < -(consts.number_decimals_scientific as isize)
+    if scientific {
+        write_scientific(acc, Some(&mantissa), &exponent, style, None)
+    } else {
+        acc.write_str(&mantissa)
+    }
+}
+
+/// SI prefixes from quecto (10^-30) to quetta (10^30)
+const SI_PREFIXES: [&str; 21] = [
+    "q", "r", "y", "z", "a", "f", "p", "n", "µ", "m", "", "k", "M", "G", "T", "P", "E", "Z", "Y",
+    "R", "Q",
+];
+const SUPERSCRIPT_DIGITS: [char; 10] = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+const SUBSCRIPT_DIGITS: [char; 10] = ['₀', '₁', '₂', '₃', '₄', '₅', '₆', '₇', '₈', '₉'];
+
+/// Whether a number of that size is written with an SI prefix (from kilo to quetta), as it would be shown in full otherwise
+fn uses_si_prefix(style: locale::ScientificStyle, exponent: &Integer) -> bool {
+    style == locale::ScientificStyle::Si && *exponent >= 3 && *exponent <= 30
+}
+
+/// Writes mantissa × 10^exponent in the given style, a missing mantissa being 1 (and omitted if possible). \
+/// The exponent is shortened (and put in parentheses), if consts are given.
+pub(crate) fn write_scientific(
+    acc: &mut String,
+    mantissa: Option<&str>,
+    exponent: &Integer,
+    style: locale::ScientificStyle,
+    shorten: Option<&Consts>,
+) -> std::fmt::Result {
+    use locale::ScientificStyle;
+    let (mantissa, exponent) = match style {
+        ScientificStyle::Engineering | ScientificStyle::Si => {
+            let shift = exponent.mod_u(3);
+            let mantissa = if shift == 0 {
+                mantissa.map(str::to_owned)
+            } else {
+                Some(shift_decimal(mantissa.unwrap_or("1"), shift as usize))
+            };
+            (mantissa, exponent.clone() - shift)
+        }
+        _ => (mantissa.map(str::to_owned), exponent.clone()),
+    };
+    if style == ScientificStyle::Si
+        && let Some(e) = exponent.to_i32()
+        && (-30..=30).contains(&e)
+    {
+        acc.write_str(mantissa.as_deref().unwrap_or("1"))?;
+        if e != 0 {
+            acc.write_char(' ')?;
+            acc.write_str(SI_PREFIXES[(e / 3 + 10) as usize])?;
+        }
+        return Ok(());
+    }
+    let exponent = match shorten {
+        Some(consts) => {
+            let nested = if style == ScientificStyle::Si {
+                ScientificStyle::Engineering
+            } else {
+                style
+            };
+            format!("({})", truncate(&exponent, consts, nested).0)
+        }
+        None => exponent.to_string(),
+    };
+    if style == ScientificStyle::ENotation {
+        acc.write_str(mantissa.as_deref().unwrap_or("1"))?;
+        acc.write_char('e')?;
+        return acc.write_str(&exponent);
+    }
+    if let Some(mantissa) = mantissa {
+        acc.write_str(&mantissa)?;
+        acc.write_str(" × ")?;
+    }
+    acc.write_str("10")?;
+    if style == ScientificStyle::Superscript
+        && exponent.chars().all(|c| c.is_ascii_digit() || c == '-')
+    {
+        for c in exponent.chars() {
+            acc.write_char(match c.to_digit(10) {
+                Some(digit) => SUPERSCRIPT_DIGITS[digit as usize],
+                None => '⁻',
+            })?;
+        }
+        Ok(())
+    } else {
+        acc.write_char('^')?;
+        acc.write_str(&exponent)
+    }
+}
+
+/// Moves the decimal point of a number shift digits to the right, padding with zeros
+fn shift_decimal(number: &str, shift: usize) -> String {
+    let (sign, number) = number
+        .strip_prefix('-')
+        .map_or(("", number), |number| ("-", number));
+    let point = number.find('.').unwrap_or(number.len()) + shift;
+    let mut digits = number.replace('.', "");
+    while digits.len() < point {
+        digits.push('0');
+    }
+    let (whole, decimal) = digits.split_at(point);
+    if decimal.is_empty() {
+        format!("{sign}{whole}")
+    } else {
+        format!("{sign}{whole}.{decimal}")
+    }
+}
+
+/// Continues the chain of digit factorial sums after n (which is already written) until it repeats, like " → 1454 → 169 → 363601"
+pub fn format_digit_factorial_chain(acc: &mut String, n: &Integer) -> std::fmt::Result {
+    for next in factorion_math::digit_factorial_chain(n.clone())
+        .iter()
+        .skip(1)
     {
-        write!(acc, " × 10^{exponent}")?;
+        write!(acc, " → {next}")?;
     }
     Ok(())
 }
 
+/// Groups the leading digits of a number with "," (localized later), if the number format wants it. \
+/// Sizes are read from the right and the last one repeats, so `[3, 2]` turns 12345678 into 1,23,45,678.
+pub fn group_digits(number: &str, number_format: &locale::NumFormat) -> String {
+    if number_format.group_separator().is_none() {
+        return number.to_owned();
+    }
+    let (sign, number) = number
+        .strip_prefix('-')
+        .map_or(("", number), |number| ("-", number));
+    let end = number
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(number.len());
+    let (digits, rest) = number.split_at(end);
+    let sizes = number_format.group_sizes.as_deref().unwrap_or(&[3]);
+    let mut groups = Vec::new();
+    let mut digits = digits;
+    for i in 0.. {
+        let size = sizes.get(i).or(sizes.last()).copied().unwrap_or(3).max(1);
+        if digits.len() <= size {
+            groups.push(digits);
+            break;
+        }
+        let (head, group) = digits.split_at(digits.len() - size);
+        groups.push(group);
+        digits = head;
+    }
+    groups.reverse();
+    format!("{sign}{}{rest}", groups.join(","))
+}
+
 pub fn replace(s: &mut String, search_start: usize, from: &str, to: &str) -> usize {
     if let Some(start) = s[search_start..].find(from) {
         let start = start + search_start;
@@ -411,10 +944,10 @@ pub fn replace(s: &mut String, search_start: usize, from: &str, to: &str) -> usi
 
 pub(crate) fn format_complex_infinity(
     acc: &mut String,
-    opts: FormatOptions,
+    write_out: Option<&locale::WriteOut<'_>>,
 ) -> Result<(), fmt::Error> {
-    if opts.write_out {
-        acc.write_str("complex infinity")?;
+    if let Some(write_out) = write_out {
+        acc.write_str(&write_out.complex_infinity)?;
     } else {
         acc.write_str("∞\u{0303}")?;
     }
@@ -446,7 +979,7 @@ pub(crate) fn format_approximate_digits_tower(
             acc.write_str("(")?;
         }
         if opts.force_shorten {
-            acc.write_str(&truncate(exponent, consts).0)?;
+            acc.write_str(&truncate(exponent, consts, opts.scientific.unwrap_or_default()).0)?;
         } else {
             write!(acc, "{exponent}")?;
         }
@@ -457,34 +990,67 @@ pub(crate) fn format_approximate_digits_tower(
             acc.write_str(")")?;
         }
     } else {
-        let mut extra = 0;
-        let mut exponent = Float::with_val(consts.float_precision, exponent);
-        while exponent >= 10 {
-            extra += 1;
-            exponent = exponent.log10();
-        }
+        let height = tetration_height(depth, Float::with_val(consts.float_precision, exponent));
         acc.write_str("^(")?;
-        write!(acc, "{}", depth + extra)?;
+        write!(acc, "{height}")?;
         acc.write_str(")10")?;
     }
     Ok(())
 }
 
+/// Most arrows written out, more use Conway chained arrows
+const MAX_KNUTH_ARROWS: u32 = 4;
+
+pub(crate) fn format_approximate_digits_hyper(
+    acc: &mut String,
+    opts: &FormatOptions,
+    is_value: bool,
+    consts: &Consts<'_>,
+    negative: &bool,
+    arrows: &u32,
+    height: &Integer,
+) -> Result<(), fmt::Error> {
+    // With more arrows, the number has the same bounds as its digits
+    let height = if is_value && *arrows == 2 {
+        height.clone() + 1
+    } else {
+        height.clone()
+    };
+    let height = if opts.force_shorten {
+        truncate(&height, consts, opts.scientific.unwrap_or_default()).0
+    } else {
+        height.to_string()
+    };
+    acc.write_str(if *negative { "-" } else { "" })?;
+    if *arrows <= MAX_KNUTH_ARROWS {
+        acc.write_str("10")?;
+        acc.write_str(&"↑".repeat(*arrows as usize))?;
+        acc.write_str(&height)?;
+    } else {
+        write!(acc, "10→{height}→{arrows}")?;
+    }
+    Ok(())
+}
+
 pub(crate) fn format_approximate_digits(
     acc: &mut String,
     opts: &FormatOptions,
     is_value: bool,
     consts: &Consts<'_>,
+    write_out: Option<&locale::WriteOut<'_>>,
     digits: &Integer,
 ) -> Result<(), fmt::Error> {
-    if opts.write_out && !is_value && length(digits, consts.float_precision) < 3000000 {
-        write_out_number(acc, digits, consts)?;
+    if let Some(write_out) = write_out
+        && !is_value
+        && length(digits, consts.float_precision) < 3000000
+    {
+        write_out_number(acc, digits, consts, write_out, opts.naming)?;
     } else {
         if is_value {
             acc.write_str("10^(")?;
         }
         if opts.force_shorten {
-            acc.write_str(&truncate(digits, consts).0)?;
+            acc.write_str(&truncate(digits, consts, opts.scientific.unwrap_or_default()).0)?;
         } else {
             write!(acc, "{digits}")?;
         }
@@ -499,20 +1065,34 @@ pub(crate) fn format_approximate(
     acc: &mut String,
     opts: &FormatOptions,
     consts: &Consts<'_>,
+    number_format: &locale::NumFormat,
     base: &OrdFloat,
     exponent: &Integer,
 ) -> Result<(), fmt::Error> {
-    let base = base.as_float();
-    format_float(acc, base, consts)?;
-    acc.write_str(" × 10^")?;
-    if opts.force_shorten {
-        acc.write_str("(")?;
-        acc.write_str(&truncate(exponent, consts).0)?;
-        acc.write_str(")")?;
-    } else {
-        write!(acc, "{exponent}")?;
+    let mut mantissa = String::new();
+    format_float(
+        &mut mantissa,
+        base.as_float(),
+        consts,
+        number_format,
+        locale::ScientificStyle::default(),
+    )?;
+    write_scientific(
+        acc,
+        Some(&mantissa),
+        exponent,
+        opts.scientific.unwrap_or_default(),
+        opts.force_shorten.then_some(consts),
+    )
+}
+
+/// The digits in upper case, and the base as a subscript (like 78₁₆)
+pub(crate) fn radix_string(n: &Integer, base: u32) -> String {
+    let mut radix = n.to_string_radix(base as i32).to_uppercase();
+    for digit in base.to_string().bytes() {
+        radix.push(SUBSCRIPT_DIGITS[(digit - b'0') as usize]);
     }
-    Ok(())
+    radix
 }
 
 pub(crate) fn format_exact(
@@ -520,16 +1100,24 @@ pub(crate) fn format_exact(
     rough: &mut bool,
     opts: &FormatOptions,
     consts: &Consts<'_>,
+    number_format: &locale::NumFormat,
+    write_out: Option<&locale::WriteOut<'_>>,
     factorial: &Integer,
 ) -> Result<(), fmt::Error> {
-    if opts.write_out && length(factorial, consts.float_precision) < 3000000 {
-        write_out_number(acc, factorial, consts)?;
+    if let Some(write_out) = write_out
+        && length(factorial, consts.float_precision) < 3000000
+    {
+        write_out_number(acc, factorial, consts, write_out, opts.naming)?;
+    } else if let Some(base) = opts.base.filter(|base| *base != 10)
+        && !opts.force_shorten
+    {
+        acc.write_str(&radix_string(factorial, base))?;
     } else if opts.force_shorten {
-        let (s, r) = truncate(factorial, consts);
+        let (s, r) = truncate(factorial, consts, opts.scientific.unwrap_or_default());
         *rough = r;
-        acc.write_str(&s)?;
+        acc.write_str(&group_digits(&s, number_format))?;
     } else {
-        write!(acc, "{factorial}")?;
+        acc.write_str(&group_digits(&factorial.to_string(), number_format))?;
     }
     Ok(())
 }
@@ -632,6 +1220,8 @@ mod tests {
     #[test]
     fn test_write_out_number() {
         let consts = Consts::default();
+        let en = locale::get_en();
+        let en = en.format.write_out.as_ref().unwrap();
         let mut acc = String::new();
         write_out_number(
             &mut acc,
@@ -639,6 +1229,8 @@ mod tests {
                 .parse()
                 .unwrap(),
             &consts,
+            en,
+            None,
         )
         .unwrap();
         assert_eq!(
@@ -646,25 +1238,189 @@ mod tests {
             "one tredeccentillion two hundred thirty four duodeccentillion five hundred sixty seven undeccentillion eight hundred ninety deccentillion one hundred twenty three novemcentillion four hundred fifty six octocentillion"
         );
         let mut acc = String::new();
-        write_out_number(&mut acc, &"123456789".parse().unwrap(), &consts).unwrap();
+        write_out_number(&mut acc, &"123456789".parse().unwrap(), &consts, en, None).unwrap();
         assert_eq!(
             acc,
             "one hundred twenty three million four hundred fifty six thousand seven hundred eighty nine"
         );
     }
 
+    #[test]
+    fn test_write_out_number_localized() {
+        let consts = Consts::default();
+        let write_out = |locale: &locale::Locale, n: &str| {
+            let mut acc = String::new();
+            write_out_number(
+                &mut acc,
+                &n.parse().unwrap(),
+                &consts,
+                locale.format.write_out.as_ref().unwrap(),
+                None,
+            )
+            .unwrap();
+            acc
+        };
+        let de = locale::get_de();
+        assert_eq!(
+            write_out(&de, "123456789"),
+            "einhundertdreiundzwanzig Millionen vierhundertsechsundfünfzigtausendsiebenhundertneunundachtzig"
+        );
+        assert_eq!(write_out(&de, "1001"), "eintausendeins");
+        assert_eq!(write_out(&de, "1000000000"), "eine Milliarde");
+        assert_eq!(write_out(&de, "2000000000000"), "zwei Billionen");
+        assert_eq!(write_out(&de, "-21"), "minus einundzwanzig");
+        let fr = locale::get_fr();
+        assert_eq!(
+            write_out(&fr, "123456789"),
+            "cent vingt-trois millions quatre cent cinquante-six mille sept cent quatre-vingt-neuf"
+        );
+        assert_eq!(write_out(&fr, "80"), "quatre-vingts");
+        assert_eq!(write_out(&fr, "80000"), "quatre-vingt mille");
+        assert_eq!(write_out(&fr, "200000000"), "deux cents millions");
+        assert_eq!(write_out(&fr, "71"), "soixante et onze");
+        let it = locale::get_it();
+        assert_eq!(
+            write_out(&it, "123456789"),
+            "centoventitré milioni quattrocentocinquantaseimilasettecentottantanove"
+        );
+        assert_eq!(write_out(&it, "1000"), "mille");
+        assert_eq!(write_out(&it, "1000000000"), "un miliardo");
+        let nl = locale::get_nl();
+        assert_eq!(
+            write_out(&nl, "123456789"),
+            "honderddrieëntwintig miljoen vierhonderdzesenvijftigduizend zevenhonderdnegenentachtig"
+        );
+        assert_eq!(write_out(&nl, "2000000000"), "twee miljard");
+        let ru = locale::get_ru();
+        assert_eq!(
+            write_out(&ru, "123456789"),
+            "сто двадцать три миллиона четыреста пятьдесят шесть тысяч семьсот восемьдесят девять"
+        );
+        assert_eq!(write_out(&ru, "21000"), "двадцать одна тысяча");
+        assert_eq!(write_out(&ru, "12000000000"), "двенадцать миллиардов");
+        assert_eq!(
+            write_out(&ru, "1000000000000000000000000000000000"),
+            "один дециллион"
+        );
+        assert_eq!(write_out(&ru, "0"), "ноль");
+    }
+
+    #[test]
+    fn test_write_out_naming_systems() {
+        use locale::NamingSystem;
+        let consts = Consts::default();
+        let write_out = |locale: &locale::Locale, n: &str, system| {
+            let mut acc = String::new();
+            write_out_number(
+                &mut acc,
+                &n.parse().unwrap(),
+                &consts,
+                locale.format.write_out.as_ref().unwrap(),
+                Some(system),
+            )
+            .unwrap();
+            acc
+        };
+        let en = locale::get_en();
+        assert_eq!(
+            write_out(&en, "1000000000", NamingSystem::Short),
+            "one billion"
+        );
+        assert_eq!(
+            write_out(&en, "2500000000000", NamingSystem::Long),
+            "two billion five hundred milliard"
+        );
+        assert_eq!(
+            write_out(&en, "123456789", NamingSystem::Knuth),
+            "one myllion two thousand three hundred forty five myriad six thousand seven hundred eighty nine"
+        );
+        assert_eq!(
+            write_out(&en, "100000000000000000", NamingSystem::Knuth),
+            "ten byllion"
+        );
+        assert_eq!(
+            write_out(&en, &format!("1{}", "0".repeat(312)), NamingSystem::Short),
+            "one trecentillion"
+        );
+        assert_eq!(
+            write_out(
+                &en,
+                &format!("1{}", "0".repeat(312)),
+                NamingSystem::ConwayWechsler
+            ),
+            "one trescentillion"
+        );
+        let de = locale::get_de();
+        assert_eq!(
+            write_out(&de, "1000000000", NamingSystem::Short),
+            "eine Billion"
+        );
+        // Falls back to the locale's system
+        assert_eq!(
+            write_out(&de, "1000000000", NamingSystem::Knuth),
+            "eine Milliarde"
+        );
+        let ru = locale::get_ru();
+        assert_eq!(
+            write_out(&ru, "1000000000", NamingSystem::Long),
+            "один миллиард"
+        );
+        assert_eq!(
+            write_out(
+                &ru,
+                &format!("1{}", "0".repeat(303)),
+                NamingSystem::ConwayWechsler
+            ),
+            "один центиллион"
+        );
+    }
+
+    #[test]
+    fn test_conway_wechsler_prefix() {
+        let en = locale::get_en();
+        let en = en.format.write_out.as_ref().unwrap();
+        for (n, name) in [
+            (3, "tr"),
+            (16, "sedec"),
+            (17, "septendec"),
+            (86, "sexoctogint"),
+            (103, "trescent"),
+            (1000, "millin"),
+            (1203, "millitreducent"),
+            (1000000, "millinillin"),
+        ] {
+            let mut acc = String::new();
+            write_conway_wechsler_prefix(&mut acc, n, en);
+            assert_eq!(acc, name);
+        }
+    }
+
     #[test]
     fn test_truncate() {
         let consts = Consts::default();
-        assert_eq!(truncate(&Integer::from_str("0").unwrap(), &consts,).0, "0");
         assert_eq!(
-            truncate(&Integer::from_str("-1").unwrap(), &consts,).0,
+            truncate(
+                &Integer::from_str("0").unwrap(),
+                &consts,
+                Default::default()
+            )
+            .0,
+            "0"
+        );
+        assert_eq!(
+            truncate(
+                &Integer::from_str("-1").unwrap(),
+                &consts,
+                Default::default()
+            )
+            .0,
             "-1"
         );
         assert_eq!(
             truncate(
                 &Integer::from_str(&format!("1{}", "0".repeat(300))).unwrap(),
-                &consts
+                &consts,
+                Default::default()
             )
             .0,
             "10^300"
@@ -672,14 +1428,16 @@ mod tests {
         assert!(
             !truncate(
                 &Integer::from_str(&format!("1{}", "0".repeat(300))).unwrap(),
-                &consts
+                &consts,
+                Default::default()
             )
             .1
         );
         assert_eq!(
             truncate(
                 &Integer::from_str(&format!("2{}", "0".repeat(300))).unwrap(),
-                &consts
+                &consts,
+                Default::default()
             )
             .0,
             "2 × 10^300"
@@ -687,7 +1445,8 @@ mod tests {
         assert_eq!(
             truncate(
                 &-Integer::from_str(&format!("2{}", "0".repeat(300))).unwrap(),
-                &consts
+                &consts,
+                Default::default()
             )
             .0,
             "-2 × 10^300"
@@ -695,32 +1454,204 @@ mod tests {
         assert_eq!(
             truncate(
                 &Integer::from_str(&format!("2{}", "0".repeat(2000000))).unwrap(),
-                &consts
+                &consts,
+                Default::default()
             )
             .0,
             "2 × 10^2000000"
         );
+        let si = locale::ScientificStyle::Si;
+        assert_eq!(
+            truncate(&Integer::from(3628800), &consts, si),
+            ("3.6288 M".to_owned(), false)
+        );
+        assert_eq!(truncate(&Integer::from(720), &consts, si).0, "720");
+        assert_eq!(
+            truncate(
+                &Integer::from_str(&format!("15{}", "0".repeat(33))).unwrap(),
+                &consts,
+                si
+            )
+            .0,
+            "15 × 10^33"
+        );
     }
 
+    #[test]
+    fn test_format_digit_factorial_chain() {
+        let mut acc = String::new();
+        format_digit_factorial_chain(&mut acc, &Integer::from(363601)).unwrap();
+        assert_eq!(acc, " → 1454 → 169 → 363601");
+        let mut acc = String::new();
+        format_digit_factorial_chain(&mut acc, &Integer::from(40585)).unwrap();
+        assert_eq!(acc, " → 40585");
+    }
+    #[test]
+    fn test_group_digits() {
+        let nf = locale::NumFormat::default();
+        assert_eq!(group_digits("1234567", &nf), "1234567");
+        let nf = locale::NumFormat {
+            group: Some(','),
+            ..Default::default()
+        };
+        assert_eq!(group_digits("1234567", &nf), "1,234,567");
+        assert_eq!(group_digits("-123456.789", &nf), "-123,456.789");
+        assert_eq!(group_digits("123", &nf), "123");
+        assert_eq!(group_digits("1.2345 × 10^67890", &nf), "1.2345 × 10^67890");
+        let nf = locale::NumFormat {
+            group: Some(','),
+            group_sizes: Some(vec![3, 2]),
+            ..Default::default()
+        };
+        assert_eq!(group_digits("12345678", &nf), "1,23,45,678");
+        let nf = locale::NumFormat {
+            narrow_no_break_space: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(group_digits("12345", &nf), "12,345");
+    }
+    #[test]
+    fn test_write_scientific() {
+        use locale::ScientificStyle;
+        let consts = Consts::default();
+        let styled = |mantissa: Option<&str>, exponent: &str, style, shorten: bool| {
+            let mut acc = String::new();
+            write_scientific(
+                &mut acc,
+                mantissa,
+                &Integer::from_str(exponent).unwrap(),
+                style,
+                shorten.then_some(&consts),
+            )
+            .unwrap();
+            acc
+        };
+        let standard = ScientificStyle::Standard;
+        assert_eq!(
+            styled(Some("1.23"), "456", standard, false),
+            "1.23 × 10^456"
+        );
+        assert_eq!(styled(None, "456", standard, false), "10^456");
+        assert_eq!(
+            styled(Some("1.23"), "456", ScientificStyle::ENotation, false),
+            "1.23e456"
+        );
+        assert_eq!(
+            styled(None, "-7", ScientificStyle::ENotation, false),
+            "1e-7"
+        );
+        assert_eq!(
+            styled(Some("1.23"), "456", ScientificStyle::Superscript, false),
+            "1.23 × 10⁴⁵⁶"
+        );
+        assert_eq!(
+            styled(Some("-1.23"), "-45", ScientificStyle::Superscript, false),
+            "-1.23 × 10⁻⁴⁵"
+        );
+        assert_eq!(
+            styled(Some("1.23"), "457", ScientificStyle::Engineering, false),
+            "12.3 × 10^456"
+        );
+        assert_eq!(
+            styled(Some("1.2"), "458", ScientificStyle::Engineering, false),
+            "120 × 10^456"
+        );
+        assert_eq!(
+            styled(Some("1.23"), "-7", ScientificStyle::Engineering, false),
+            "123 × 10^-9"
+        );
+        assert_eq!(
+            styled(None, "456", ScientificStyle::Engineering, false),
+            "10^456"
+        );
+        assert_eq!(
+            styled(Some("3.6288"), "6", ScientificStyle::Si, false),
+            "3.6288 M"
+        );
+        assert_eq!(styled(None, "28", ScientificStyle::Si, false), "10 R");
+        assert_eq!(
+            styled(Some("1.5"), "-5", ScientificStyle::Si, false),
+            "15 µ"
+        );
+        assert_eq!(
+            styled(Some("1.5"), "34", ScientificStyle::Si, false),
+            "15 × 10^33"
+        );
+        assert_eq!(
+            styled(
+                Some("1.5"),
+                "1234567890000000000000000000000000000000000000",
+                standard,
+                true
+            ),
+            "1.5 × 10^(1.23456789 × 10^45)"
+        );
+        assert_eq!(
+            styled(
+                Some("1.5"),
+                "1234567890000000000000000000000000000000000000",
+                ScientificStyle::ENotation,
+                true
+            ),
+            "1.5e(1.23456789e45)"
+        );
+        assert_eq!(
+            styled(
+                Some("1.5"),
+                "1234567890000000000000000000000000000000000000",
+                ScientificStyle::Superscript,
+                true
+            ),
+            "1.5 × 10^(1.23456789 × 10⁴⁵)"
+        );
+    }
     #[test]
     fn test_format_float() {
         let consts = Consts::default();
         let x = Float::with_val(consts.float_precision, 1.5);
         let mut acc = String::new();
-        format_float(&mut acc, &x, &consts).unwrap();
+        format_float(
+            &mut acc,
+            &x,
+            &consts,
+            &Default::default(),
+            Default::default(),
+        )
+        .unwrap();
         assert_eq!(acc, "1.5");
         let x = Float::with_val(consts.float_precision, -1.5);
         let mut acc = String::new();
-        format_float(&mut acc, &x, &consts).unwrap();
+        format_float(
+            &mut acc,
+            &x,
+            &consts,
+            &Default::default(),
+            Default::default(),
+        )
+        .unwrap();
         assert_eq!(acc, "-1.5");
         let x = Float::with_val(consts.float_precision, 1);
         let mut acc = String::new();
-        format_float(&mut acc, &x, &consts).unwrap();
+        format_float(
+            &mut acc,
+            &x,
+            &consts,
+            &Default::default(),
+            Default::default(),
+        )
+        .unwrap();
         assert_eq!(acc, "1");
         let x = Float::with_val(consts.float_precision, 1.5)
             * Float::with_val(consts.float_precision, 50000).exp10();
         let mut acc = String::new();
-        format_float(&mut acc, &x, &consts).unwrap();
+        format_float(
+            &mut acc,
+            &x,
+            &consts,
+            &Default::default(),
+            Default::default(),
+        )
+        .unwrap();
         assert_eq!(acc, "1.5 × 10^50000");
     }
 }
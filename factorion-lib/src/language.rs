@@ -0,0 +1,297 @@
+//! Guesses the language of a text among the locales, by comparing character trigrams.
+//!
+//! The trigrams of each locale are taken from its own texts (see [Profile::from_locale]), so any locale can be detected.
+//! The profiles are only built once (see [Consts::get_language_profiles](crate::Consts::get_language_profiles)).
+
+use std::collections::{HashMap, HashSet};
+use std::iter::once;
+
+use crate::locale::{Locale, Notes, VARIANT_SEPARATOR};
+
+/// Texts with less trigrams (about three words) are too short to tell
+pub const MIN_TRIGRAMS: usize = 12;
+/// How much more likely the best language has to be than the next one (log per trigram)
+pub const MIN_CONFIDENCE: f64 = 0.15;
+
+type Trigram = [char; 3];
+
+/// How often each trigram occurs in a language.
+#[derive(Debug, Clone, Default)]
+pub struct Profile {
+    counts: HashMap<Trigram, u32>,
+    total: u32,
+}
+
+impl Profile {
+    pub fn from_text(text: &str) -> Self {
+        let mut profile = Self::default();
+        profile.add_text(text);
+        profile
+    }
+    /// Builds the profile from the texts of the locale (notes, names of calculations and the language sample). \
+    /// Placeholders (like `{number}`) are left out, as they are the same in every locale.
+    pub fn from_locale(locale: &Locale<'_>) -> Self {
+        let mut profile = Self::default();
+        let format = &locale.format;
+        for text in notes_texts(&locale.notes)
+            .chain([
+                locale.bot_disclaimer.as_ref(),
+                &format.termial,
+                &format.factorial,
+                &format.exact,
+                &format.rough,
+                &format.approx,
+                &format.digits,
+                &format.order,
+                &format.all_that,
+            ])
+            .chain(locale.language_sample.as_deref())
+        {
+            profile.add_text(&remove_placeholders(text));
+        }
+        profile
+    }
+
+    fn add_text(&mut self, text: &str) {
+        for trigram in trigrams(text) {
+            *self.counts.entry(trigram).or_default() += 1;
+            self.total += 1;
+        }
+    }
+
+    /// Log likelihood of the trigrams, with add-one smoothing over a shared vocabulary size
+    fn log_likelihood(&self, trigrams: &[Trigram], vocabulary: usize) -> f64 {
+        let total = (self.total as usize + vocabulary) as f64;
+        trigrams
+            .iter()
+            .map(|trigram| {
+                let count = self.counts.get(trigram).copied().unwrap_or(0);
+                ((count + 1) as f64 / total).ln()
+            })
+            .sum()
+    }
+}
+
+fn notes_texts<'a>(notes: &'a Notes<'_>) -> impl Iterator<Item = &'a str> {
+    [
+        &notes.tower,
+        &notes.tower_mult,
+        &notes.digits,
+        &notes.digits_mult,
+        &notes.approx,
+        &notes.approx_mult,
+        &notes.round,
+        &notes.round_mult,
+        &notes.too_big,
+        &notes.too_big_mult,
+        &notes.remove,
+        &notes.tetration,
+        &notes.no_post,
+    ]
+    .into_iter()
+    .map(AsRef::as_ref)
+    .chain(
+        [
+            &notes.limit_hit,
+            &notes.factorion,
+            &notes.hyper,
+            &notes.hyper_mult,
+            &notes.why,
+            &notes.why_nothing,
+            &notes.why_in_uri,
+            &notes.why_escaped,
+            &notes.why_integer_only,
+            &notes.why_too_big,
+            &notes.why_unbalanced_paren,
+            &notes.why_code,
+        ]
+        .into_iter()
+        .filter_map(|note| note.as_deref()),
+    )
+}
+
+fn remove_placeholders(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut in_placeholder = false;
+    for c in text.chars() {
+        match c {
+            '{' => in_placeholder = true,
+            '}' if in_placeholder => {
+                in_placeholder = false;
+                result.push(' ');
+            }
+            _ if !in_placeholder => result.push(c),
+            _ => {}
+        }
+    }
+    result
+}
+
+/// The trigrams of all words (only letters, in lower case), each padded with a space on both ends
+pub fn trigrams(text: &str) -> Vec<Trigram> {
+    text.split(|c: char| !c.is_alphabetic())
+        .filter(|word| !word.is_empty())
+        .flat_map(|word| {
+            let chars = once(' ')
+                .chain(word.chars().flat_map(char::to_lowercase))
+                .chain(once(' '))
+                .collect::<Vec<_>>();
+            chars
+                .windows(3)
+                .map(|window| [window[0], window[1], window[2]])
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// The profiles of all locales, with how many different trigrams they have together.
+#[derive(Debug, Clone, Default)]
+pub struct Profiles {
+    profiles: Vec<(String, Profile)>,
+    vocabulary: usize,
+}
+
+impl Profiles {
+    pub fn from_locales(locales: &HashMap<String, Locale<'_>>) -> Self {
+        let profiles = locales
+            .iter()
+            .map(|(key, locale)| (key.clone(), Profile::from_locale(locale)))
+            .collect::<Vec<_>>();
+        let vocabulary = profiles
+            .iter()
+            .flat_map(|(_, profile)| profile.counts.keys())
+            .collect::<HashSet<_>>()
+            .len();
+        Self {
+            profiles,
+            vocabulary,
+        }
+    }
+}
+
+/// Guesses which of the locales the text is written in (profiles being built from them). \
+/// Returns None if the text is too short, or no language is clearly more likely than the others. \
+/// Locales of the same language (like `en` and `en_fuck`) can't be told apart well, so preferred is chosen if it is one of them, otherwise the one without a variant.
+pub fn detect<'a>(
+    text: &str,
+    profiles: &Profiles,
+    locales: &'a HashMap<String, Locale<'_>>,
+    preferred: &str,
+) -> Option<&'a str> {
+    let trigrams = trigrams(text);
+    if trigrams.len() < MIN_TRIGRAMS {
+        return None;
+    }
+    // Best score of each language
+    let mut languages: HashMap<&str, f64> = HashMap::new();
+    for (key, profile) in &profiles.profiles {
+        let score = profile.log_likelihood(&trigrams, profiles.vocabulary);
+        let best = languages.entry(language(key)).or_insert(f64::NEG_INFINITY);
+        *best = best.max(score);
+    }
+    let mut scores = languages.into_iter().collect::<Vec<_>>();
+    scores.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    let (detected, best) = *scores.first()?;
+    if let Some((_, second)) = scores.get(1)
+        && (best - second) / (trigrams.len() as f64) < MIN_CONFIDENCE
+    {
+        return None;
+    }
+    if language(preferred) == detected
+        && let Some((key, _)) = locales.get_key_value(preferred)
+    {
+        return Some(key);
+    }
+    locales
+        .get_key_value(detected)
+        .map(|(key, _)| key.as_str())
+        .or_else(|| {
+            locales
+                .keys()
+                .map(String::as_str)
+                .filter(|key| language(key) == detected)
+                .min()
+        })
+}
+
+/// The language of a locale key, without the variant (`en` for `en_fuck`)
+fn language(key: &str) -> &str {
+    key.split(VARIANT_SEPARATOR).next().unwrap_or(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale::get_all;
+
+    fn locales() -> HashMap<String, Locale<'static>> {
+        get_all()
+            .map(|(key, locale)| (key.to_owned(), locale))
+            .collect()
+    }
+
+    #[test]
+    fn test_trigrams() {
+        assert_eq!(
+            trigrams("Hi, ÄB!"),
+            [
+                [' ', 'h', 'i'],
+                ['h', 'i', ' '],
+                [' ', 'ä', 'b'],
+                ['ä', 'b', ' ']
+            ]
+        );
+        assert!(trigrams("5! 3?").is_empty());
+        assert_eq!(remove_placeholders("{factorial} of {number}"), "  of  ");
+    }
+
+    #[test]
+    fn test_detect() {
+        let locales = locales();
+        let profiles = Profiles::from_locales(&locales);
+        let detect = |text| detect(text, &profiles, &locales, "en");
+        assert_eq!(
+            detect("What is the factorial of 5!, I always forget how big it gets?"),
+            Some("en")
+        );
+        assert_eq!(
+            detect("Was ist eigentlich die Fakultät von 5!, ich vergesse das immer wieder?"),
+            Some("de")
+        );
+        assert_eq!(
+            detect("Quelle est la factorielle de 5! ? Je ne me souviens jamais du résultat."),
+            Some("fr")
+        );
+        assert_eq!(
+            detect("Qual è il fattoriale di 5!? Non mi ricordo mai quanto fa, scusate."),
+            Some("it")
+        );
+        assert_eq!(
+            detect("Wat is de faculteit van 5!? Ik vergeet het altijd weer, heel raar."),
+            Some("nl")
+        );
+        assert_eq!(
+            detect("Сколько будет факториал 5!? Я всегда забываю, какой там ответ."),
+            Some("ru")
+        );
+    }
+
+    #[test]
+    fn test_detect_unsure() {
+        let locales = locales();
+        let profiles = Profiles::from_locales(&locales);
+        // Too short
+        assert_eq!(detect("5! lol", &profiles, &locales, "de"), None);
+        assert_eq!(detect("", &profiles, &locales, "de"), None);
+        // The same language as preferred
+        assert_eq!(
+            detect(
+                "What is the factorial of 5!, I always forget how big it gets?",
+                &profiles,
+                &locales,
+                "en_fuck"
+            ),
+            Some("en_fuck")
+        );
+    }
+}
@@ -4,6 +4,7 @@
 use serde::{Deserialize, Serialize};
 
 use std::collections::HashMap;
+use std::sync::OnceLock;
 
 use factorion_math as math;
 use rug::Integer;
@@ -11,7 +12,9 @@ pub mod calculation_results;
 pub mod calculation_tasks;
 pub mod comment;
 pub(crate) mod format;
+pub mod language;
 pub mod locale;
+pub mod markdown;
 pub mod parse;
 
 #[cfg(feature = "influxdb")]
@@ -41,26 +44,35 @@ pub mod recommended {
 #[cfg_attr(any(feature = "serde", test), derive(Serialize, Deserialize))]
 pub struct Consts<'a> {
     pub float_precision: u32,
+    pub max_float_precision: u32,
     pub upper_calculation_limit: Integer,
     pub upper_approximation_limit: Integer,
     pub upper_subfactorial_limit: Integer,
+    pub upper_combinatorial_limit: Integer,
     pub upper_termial_limit: Integer,
     pub upper_termial_approximation_limit: u32,
+    pub upper_tower_depth_limit: Integer,
     pub integer_construction_limit: Integer,
     pub number_decimals_scientific: usize,
     pub locales: HashMap<String, Locale<'a>>,
     pub default_locale: String,
+    /// The language profiles of the locales, built on first use (see [get_language_profiles](Consts::get_language_profiles)).
+    #[cfg_attr(any(feature = "serde", test), serde(skip))]
+    pub language_profiles: OnceLock<language::Profiles>,
 }
 impl Default for Consts<'_> {
     fn default() -> Self {
         Consts {
             float_precision: math::recommended::FLOAT_PRECISION,
+            max_float_precision: calculation_tasks::recommended::MAX_FLOAT_PRECISION,
             upper_calculation_limit: calculation_tasks::recommended::UPPER_CALCULATION_LIMIT(),
             upper_approximation_limit: calculation_tasks::recommended::UPPER_APPROXIMATION_LIMIT(),
             upper_subfactorial_limit: calculation_tasks::recommended::UPPER_SUBFACTORIAL_LIMIT(),
+            upper_combinatorial_limit: calculation_tasks::recommended::UPPER_COMBINATORIAL_LIMIT(),
             upper_termial_limit: calculation_tasks::recommended::UPPER_TERMIAL_LIMIT(),
             upper_termial_approximation_limit:
                 calculation_tasks::recommended::UPPER_TERMIAL_APPROXIMATION_LIMIT,
+            upper_tower_depth_limit: calculation_tasks::recommended::UPPER_TOWER_DEPTH_LIMIT(),
             integer_construction_limit: parse::recommended::INTEGER_CONSTRUCTION_LIMIT(),
             number_decimals_scientific:
                 calculation_results::recommended::NUMBER_DECIMALS_SCIENTIFIC,
@@ -71,6 +83,23 @@ impl Default for Consts<'_> {
             #[cfg(not(any(feature = "serde", test)))]
             locales: HashMap::new(),
             default_locale: "en".to_owned(),
+            language_profiles: OnceLock::new(),
         }
     }
 }
+impl<'a> Consts<'a> {
+    /// Gets the locale, or the closest one it is based on (`de` for `de_AT`), if it is missing. \
+    /// Falls back to the default locale, if none of them are there, which is returned as true.
+    pub fn get_locale(&self, key: &str) -> (&locale::Locale<'a>, bool) {
+        locale::fallback_chain(key)
+            .find_map(|key| self.locales.get(key))
+            .map(|locale| (locale, false))
+            .unwrap_or_else(|| (self.locales.get(&self.default_locale).unwrap(), true))
+    }
+    /// Gets the language profiles of the locales, building them on first use. \
+    /// So changes to the locales after that are not seen by [language::detect].
+    pub fn get_language_profiles(&self) -> &language::Profiles {
+        self.language_profiles
+            .get_or_init(|| language::Profiles::from_locales(&self.locales))
+    }
+}
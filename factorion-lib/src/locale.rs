@@ -32,12 +32,23 @@ pub fn get_fr() -> Locale<'static> {
 pub fn get_nl() -> Locale<'static> {
     serde_json::de::from_str(include_str!("nl.json")).unwrap()
 }
+/// Swiss German, a variant of [get_de]
+#[cfg(any(feature = "serde", test))]
+pub fn get_de_ch() -> Locale<'static> {
+    let mut de = serde_json::de::from_str(include_str!("de.json")).unwrap();
+    merge_json(
+        &mut de,
+        serde_json::de::from_str(include_str!("de_CH.json")).unwrap(),
+    );
+    serde_json::from_value(de).unwrap()
+}
 #[cfg(any(feature = "serde", test))]
 pub fn get_all() -> impl Iterator<Item = (&'static str, Locale<'static>)> {
     [
         ("en", get_en()),
         ("en_fuck", get_en_fuck()),
         ("de", get_de()),
+        ("de_CH", get_de_ch()),
         ("ru", get_ru()),
         ("it", get_it()),
         ("fr", get_fr()),
@@ -46,6 +57,58 @@ pub fn get_all() -> impl Iterator<Item = (&'static str, Locale<'static>)> {
     .into_iter()
 }
 
+pub(crate) const VARIANT_SEPARATOR: char = '_';
+
+/// The locale a variant is based on (`de` for `de_AT`, `de_AT` for `de_AT_x`), if it is a variant.
+pub fn parent(key: &str) -> Option<&str> {
+    key.rsplit_once(VARIANT_SEPARATOR).map(|(parent, _)| parent)
+}
+
+/// The locale and the locales it is based on, in the order to fall back to (`de_AT`, `de`).
+pub fn fallback_chain(key: &str) -> impl Iterator<Item = &str> {
+    std::iter::successors(Some(key), |key| parent(key))
+}
+
+/// Merges a variant into the locale it is based on, field by field (also in maps, like `num_overrides`). \
+/// So a variant only needs to have the fields that are different.
+#[cfg(any(feature = "serde", test))]
+pub fn merge_json(base: &mut serde_json::Value, variant: serde_json::Value) {
+    match (base, variant) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(variant)) => {
+            for (key, value) in variant {
+                match base.get_mut(&key) {
+                    Some(base_value) => merge_json(base_value, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, variant) => *base = variant,
+    }
+}
+
+/// Parses locales from json, merging each variant (like `de_AT`) into the locales it is based on (`de`), if they are there. \
+/// Variants of missing locales have to be complete.
+#[cfg(any(feature = "serde", test))]
+pub fn from_jsons(
+    jsons: &HashMap<String, serde_json::Value>,
+) -> Result<HashMap<String, Locale<'static>>, serde_json::Error> {
+    jsons
+        .keys()
+        .map(|key| {
+            let mut chain = fallback_chain(key)
+                .filter_map(|key| jsons.get(key))
+                .collect::<Vec<_>>();
+            let mut merged = chain.pop().cloned().unwrap_or_default();
+            for variant in chain.into_iter().rev() {
+                merge_json(&mut merged, variant.clone());
+            }
+            Ok((key.clone(), serde_json::from_value(merged)?))
+        })
+        .collect()
+}
+
 /// Represents the whole locale.
 /// Create by deserializing or intializing as a Struct, sperading with default.
 ///
@@ -60,6 +123,7 @@ pub fn get_all() -> impl Iterator<Item = (&'static str, Locale<'static>)> {
 #[non_exhaustive]
 pub struct Locale<'a> {
     pub bot_disclaimer: Cow<'a, str>,
+    pub language_sample: Option<Cow<'a, str>>,
     pub notes: Notes<'a>,
     pub format: Format<'a>,
 }
@@ -85,6 +149,19 @@ pub struct Notes<'a> {
     pub limit_hit: Option<Cow<'a, str>>,
     pub write_out_unsupported: Option<Cow<'a, str>>,
     pub nested_used: Option<Cow<'a, str>>,
+    pub factorion: Option<Cow<'a, str>>,
+    pub hyper: Option<Cow<'a, str>>,
+    pub hyper_mult: Option<Cow<'a, str>>,
+    pub why: Option<Cow<'a, str>>,
+    pub why_nothing: Option<Cow<'a, str>>,
+    pub why_in_uri: Option<Cow<'a, str>>,
+    pub why_escaped: Option<Cow<'a, str>>,
+    pub why_integer_only: Option<Cow<'a, str>>,
+    pub why_too_big: Option<Cow<'a, str>>,
+    pub why_unbalanced_paren: Option<Cow<'a, str>>,
+    pub why_code: Option<Cow<'a, str>>,
+    pub unknown_command: Option<Cow<'a, str>>,
+    pub invalid_command: Option<Cow<'a, str>>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -108,6 +185,126 @@ pub struct Format<'a> {
     pub order: Cow<'a, str>,
     pub all_that: Cow<'a, str>,
     pub number_format: NumFormat,
+    pub exponential: Option<Cow<'a, str>>,
+    pub compositorial: Option<Cow<'a, str>>,
+    pub rising: Option<Cow<'a, str>>,
+    pub falling: Option<Cow<'a, str>>,
+    pub catalan: Option<Cow<'a, str>>,
+    pub bell: Option<Cow<'a, str>>,
+    pub stirling_second: Option<Cow<'a, str>>,
+    pub stirling_first: Option<Cow<'a, str>>,
+    pub rencontres: Option<Cow<'a, str>>,
+    pub digit_sum: Option<Cow<'a, str>>,
+    pub digit_stats: Option<Cow<'a, str>>,
+    pub digit_palindrome: Option<Cow<'a, str>>,
+    pub digit_zeros: Option<Cow<'a, str>>,
+    pub modulo: Option<Cow<'a, str>>,
+    pub between: Option<Cow<'a, str>>,
+    pub write_out: Option<WriteOut<'a>>,
+}
+
+/// How to write out numbers in words.
+/// Numbers are written in blocks of three digits, each followed by their [Scale].
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(any(feature = "serde", test), derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct WriteOut<'a> {
+    pub zero: Cow<'a, str>,
+    pub negative: Cow<'a, str>,
+    pub complex_infinity: Cow<'a, str>,
+    pub numbers: HashMap<u32, Cow<'a, str>>,
+    pub numbers_final: Option<HashMap<u32, Cow<'a, str>>>,
+    pub tens: Cow<'a, str>,
+    pub hundreds: HashMap<u32, Cow<'a, str>>,
+    pub hundreds_final: Option<HashMap<u32, Cow<'a, str>>>,
+    pub hundred: Cow<'a, str>,
+    pub elide: bool,
+    pub system: Option<NamingSystem>,
+    pub thousand: Scale<'a>,
+    pub illion: Scale<'a>,
+    pub illiard: Option<Scale<'a>>,
+    pub myriad: Option<Scale<'a>>,
+    pub yllion: Option<Scale<'a>>,
+    pub prefixes: Option<HashMap<Cow<'a, str>, Cow<'a, str>>>,
+}
+
+/// How to name large numbers when writing them out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+#[cfg_attr(any(feature = "serde", test), derive(Serialize, Deserialize))]
+#[cfg_attr(any(feature = "serde", test), serde(rename_all = "snake_case"))]
+#[non_exhaustive]
+pub enum NamingSystem {
+    /// Million, billion, trillion, ...
+    #[default]
+    Short,
+    /// Million, milliard, billion, billiard, ... (needs `illiard`)
+    Long,
+    /// Knuth's system, where every name squares the previous: myriad, myllion, byllion, ... (needs `myriad` and `yllion`)
+    Knuth,
+    /// The Conway–Wechsler system, which can name any -illion. Also used by the others for very large -illions.
+    ConwayWechsler,
+}
+
+impl std::str::FromStr for NamingSystem {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_ascii_lowercase().replace("\\_", "_").as_str() {
+            "short" | "short_scale" => Self::Short,
+            "long" | "long_scale" => Self::Long,
+            "knuth" | "yllion" => Self::Knuth,
+            "conway_wechsler" | "conway" | "cw" => Self::ConwayWechsler,
+            _ => return Err(()),
+        })
+    }
+}
+
+/// How to write numbers in scientific notation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+#[cfg_attr(any(feature = "serde", test), derive(Serialize, Deserialize))]
+#[cfg_attr(any(feature = "serde", test), serde(rename_all = "snake_case"))]
+#[non_exhaustive]
+pub enum ScientificStyle {
+    /// 1.23 × 10^456
+    #[default]
+    Standard,
+    /// 12.3 × 10^456, the exponent being a multiple of 3
+    Engineering,
+    /// 1.23e456
+    ENotation,
+    /// 1.23 × 10⁴⁵⁶
+    Superscript,
+    /// 12.3 M, with SI prefixes from quecto to quetta, engineering notation beyond
+    Si,
+}
+
+impl std::str::FromStr for ScientificStyle {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_ascii_lowercase().replace("\\_", "_").as_str() {
+            "standard" | "default" | "times" => Self::Standard,
+            "engineering" | "eng" => Self::Engineering,
+            "e" | "e_notation" | "e-notation" => Self::ENotation,
+            "superscript" | "sup" | "unicode" => Self::Superscript,
+            "si" | "si_prefix" | "prefix" => Self::Si,
+            _ => return Err(()),
+        })
+    }
+}
+
+/// How to write a block of three digits followed by a scale word (thousand, million, ...).
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(any(feature = "serde", test), derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct Scale<'a> {
+    pub one: Option<Cow<'a, str>>,
+    pub other: Cow<'a, str>,
+    pub by_last_digit: Option<HashMap<u32, Cow<'a, str>>>,
+    pub numbers: Option<HashMap<u32, Cow<'a, str>>>,
+    pub final_forms: bool,
+    pub separator: Cow<'a, str>,
+    pub stem: Option<Cow<'a, str>>,
+    pub names: Option<HashMap<u32, Cow<'a, str>>>,
+    pub capitalize: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -115,10 +312,113 @@ pub struct Format<'a> {
 #[non_exhaustive]
 pub struct NumFormat {
     pub decimal: char,
+    /// Separator between groups of digits, none if not set
+    pub group: Option<char>,
+    /// Sizes of the digit groups from the right, the last one repeating (`[3, 2]` for Indian grouping), default `[3]`
+    pub group_sizes: Option<Vec<usize>>,
+    /// Use a narrow no-break space as the group separator (instead of group)
+    pub narrow_no_break_space: Option<bool>,
+    /// Style of scientific notation, default standard
+    pub scientific: Option<ScientificStyle>,
+}
+
+impl NumFormat {
+    /// Gets the separator between groups of digits, if grouping is enabled.
+    pub fn group_separator(&self) -> Option<char> {
+        if self.narrow_no_break_space == Some(true) {
+            Some('\u{202F}')
+        } else {
+            self.group
+        }
+        .filter(|group| *group != self.decimal)
+    }
 }
 
 impl Default for NumFormat {
     fn default() -> Self {
-        NumFormat { decimal: '.' }
+        NumFormat {
+            decimal: '.',
+            group: None,
+            group_sizes: None,
+            narrow_no_break_space: None,
+            scientific: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_fallback_chain() {
+        assert_eq!(parent("de_AT"), Some("de"));
+        assert_eq!(parent("de"), None);
+        assert_eq!(
+            fallback_chain("de_CH_x").collect::<Vec<_>>(),
+            ["de_CH_x", "de_CH", "de"]
+        );
+    }
+
+    #[test]
+    fn test_merge_json() {
+        let mut base = json!({
+            "bot_disclaimer": "base",
+            "format": {"num_overrides": {"1": "one", "2": "two"}, "factorial": "factorial"},
+        });
+        merge_json(
+            &mut base,
+            json!({"format": {"num_overrides": {"2": "deux", "3": "trois"}}}),
+        );
+        assert_eq!(
+            base,
+            json!({
+                "bot_disclaimer": "base",
+                "format": {
+                    "num_overrides": {"1": "one", "2": "deux", "3": "trois"},
+                    "factorial": "factorial",
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn test_from_jsons() {
+        let en = serde_json::from_str::<serde_json::Value>(include_str!("en.json")).unwrap();
+        let jsons = HashMap::from([
+            ("en".to_owned(), en.clone()),
+            (
+                "en_GB".to_owned(),
+                json!({"format": {"number_format": {"group": " "}}}),
+            ),
+            ("en_GB_x".to_owned(), json!({"bot_disclaimer": "x"})),
+            // Not a variant of a known locale, so it has to be complete
+            ("xx_YY".to_owned(), en),
+        ]);
+        let locales = from_jsons(&jsons).unwrap();
+        assert_eq!(locales.len(), 4);
+        let en_gb_x = &locales["en_GB_x"];
+        assert_eq!(en_gb_x.bot_disclaimer, "x");
+        assert_eq!(en_gb_x.format.number_format.group, Some(' '));
+        assert_eq!(en_gb_x.format.number_format.decimal, '.');
+        assert_eq!(en_gb_x.format.factorial, locales["en"].format.factorial);
+        assert!(from_jsons(&HashMap::from([("zz".to_owned(), json!({}))])).is_err());
+    }
+
+    #[test]
+    fn test_de_ch() {
+        let (de, de_ch) = (get_de(), get_de_ch());
+        assert_eq!(de_ch.format.number_format.decimal, '.');
+        assert_eq!(de_ch.format.number_format.group, Some('\''));
+        assert!(de_ch.notes.tower.contains("gross"));
+        assert_eq!(de_ch.notes.remove, de.notes.remove);
+        let (numbers, de_numbers) = (
+            &de_ch.format.write_out.as_ref().unwrap().numbers,
+            &de.format.write_out.as_ref().unwrap().numbers,
+        );
+        assert_eq!(numbers[&30], "dreissig");
+        assert_eq!(numbers[&20], de_numbers[&20]);
+        assert_eq!(numbers.len(), de_numbers.len());
     }
 }
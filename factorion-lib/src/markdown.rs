@@ -0,0 +1,272 @@
+//! Finds markdown structure, that should not be parsed (code and quotes)
+
+use std::borrow::Cow;
+use std::ops::Range;
+
+const FENCES: [char; 2] = ['`', '~'];
+const MIN_FENCE: usize = 3;
+const CODE_INDENT: usize = 4;
+const QUOTE_STARTS: [&str; 2] = [">", "&gt;"];
+// Spoilers also start with a quote, see parse
+const SPOILER_STARTS: [&str; 2] = [">!", "&gt;!"];
+const INLINE_CODE: u8 = b'`';
+const ESCAPE: u8 = b'\\';
+
+/// Blanks out code (fenced and indented code blocks, and inline code) if skip_code, and blockquotes if skip_quotes. \
+/// Everything but line breaks is replaced by spaces, so positions in the result are the same as in text.
+pub fn mask_skipped(text: &str, skip_code: bool, skip_quotes: bool) -> Cow<'_, str> {
+    if !skip_code && !skip_quotes {
+        return Cow::Borrowed(text);
+    }
+    let (code_blocks, quotes) = find_blocks(text);
+    let mut skipped = Vec::new();
+    if skip_code {
+        skipped.extend(find_inline_code(text, &code_blocks));
+        skipped.extend(code_blocks);
+    }
+    if skip_quotes {
+        skipped.extend(quotes);
+    }
+    if skipped.is_empty() {
+        return Cow::Borrowed(text);
+    }
+    let mut bytes = text.as_bytes().to_vec();
+    for range in skipped {
+        // Ranges are on char boundaries, so every byte of a char is replaced
+        for byte in &mut bytes[range] {
+            if *byte != b'\n' {
+                *byte = b' ';
+            }
+        }
+    }
+    Cow::Owned(String::from_utf8(bytes).expect("Only whole chars are replaced"))
+}
+
+/// Finds the code blocks and blockquotes, line by line.
+fn find_blocks(text: &str) -> (Vec<Range<usize>>, Vec<Range<usize>>) {
+    let mut code_blocks = Vec::new();
+    let mut quotes = Vec::new();
+    // (fence char, fence length)
+    let mut fence: Option<(char, usize)> = None;
+    let mut in_indented = false;
+    let mut in_quote = false;
+    let mut previous_blank = true;
+    let mut end = 0;
+    for line in text.split_inclusive('\n') {
+        let start = end;
+        end += line.len();
+        let content = line.trim_end_matches(['\n', '\r']);
+        let blank = content.trim().is_empty();
+        let indent = indent_width(content);
+        let trimmed = content.trim_start_matches([' ', '\t']);
+
+        if let Some((fence_char, fence_len)) = fence {
+            code_blocks.push(start..end);
+            if indent < CODE_INDENT
+                && trimmed.starts_with(fence_char)
+                && trimmed.len() - trimmed.trim_start_matches(fence_char).len() >= fence_len
+                && trimmed.trim_start_matches(fence_char).trim().is_empty()
+            {
+                fence = None;
+            }
+            previous_blank = blank;
+            continue;
+        }
+        if indent < CODE_INDENT
+            && let Some(fence_start) = parse_fence(trimmed)
+        {
+            fence = Some(fence_start);
+            code_blocks.push(start..end);
+            in_quote = false;
+            in_indented = false;
+            previous_blank = false;
+            continue;
+        }
+        // Indented code continues over blank lines, but can't interrupt a paragraph
+        if in_indented && (indent >= CODE_INDENT || blank) {
+            code_blocks.push(start..end);
+            previous_blank = blank;
+            continue;
+        }
+        in_indented = false;
+        if indent >= CODE_INDENT && previous_blank && !in_quote && !blank {
+            in_indented = true;
+            code_blocks.push(start..end);
+            previous_blank = false;
+            continue;
+        }
+        // Quotes continue until a blank line (lazy continuation)
+        if indent < CODE_INDENT
+            && QUOTE_STARTS.iter().any(|quote| trimmed.starts_with(quote))
+            && !SPOILER_STARTS
+                .iter()
+                .any(|spoiler| trimmed.starts_with(spoiler))
+        {
+            in_quote = true;
+        } else if blank {
+            in_quote = false;
+        }
+        if in_quote {
+            quotes.push(start..end);
+        }
+        previous_blank = blank;
+    }
+    (code_blocks, quotes)
+}
+
+/// Width of the leading whitespace, with tabs to the next multiple of four
+fn indent_width(line: &str) -> usize {
+    let mut width = 0;
+    for c in line.chars() {
+        match c {
+            ' ' => width += 1,
+            '\t' => width += CODE_INDENT - width % CODE_INDENT,
+            _ => break,
+        }
+    }
+    width
+}
+
+/// The fence char and length, if the line opens a fenced code block
+fn parse_fence(line: &str) -> Option<(char, usize)> {
+    let fence_char = line.chars().next().filter(|c| FENCES.contains(c))?;
+    let info = line.trim_start_matches(fence_char);
+    let len = line.len() - info.len();
+    // The info string of a backtick fence can't contain backticks
+    (len >= MIN_FENCE && !(fence_char == '`' && info.contains('`'))).then_some((fence_char, len))
+}
+
+/// Finds inline code, which is closed by the next backtick run of the same length in the same paragraph.
+fn find_inline_code(text: &str, code_blocks: &[Range<usize>]) -> Vec<Range<usize>> {
+    let bytes = text.as_bytes();
+    let mut inline_code = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if let Some(block) = code_blocks.iter().find(|block| block.contains(&i)) {
+            i = block.end;
+            continue;
+        }
+        match bytes[i] {
+            ESCAPE => i += 2,
+            INLINE_CODE => {
+                let len = backtick_run(&bytes[i..]);
+                let mut j = i + len;
+                let mut close = None;
+                while let Some(offset) = bytes[j..].iter().position(|b| *b == INLINE_CODE) {
+                    let k = j + offset;
+                    if has_blank_line(&text[i + len..k])
+                        || code_blocks
+                            .iter()
+                            .any(|block| block.start < k && block.end > i)
+                    {
+                        break;
+                    }
+                    let run = backtick_run(&bytes[k..]);
+                    if run == len {
+                        close = Some(k + run);
+                        break;
+                    }
+                    j = k + run;
+                }
+                if let Some(end) = close {
+                    inline_code.push(i..end);
+                    i = end;
+                } else {
+                    // Unmatched backticks are literal
+                    i += len;
+                }
+            }
+            _ => i += 1,
+        }
+    }
+    inline_code
+}
+
+fn backtick_run(bytes: &[u8]) -> usize {
+    bytes.iter().take_while(|b| **b == INLINE_CODE).count()
+}
+
+/// If there is a blank line (so a new paragraph) in text
+fn has_blank_line(text: &str) -> bool {
+    let lines = text.split('\n').collect::<Vec<_>>();
+    lines.len() > 2
+        && lines[1..lines.len() - 1]
+            .iter()
+            .any(|line| line.trim().is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_skip() {
+        let text = "`x != 5`\n> 5!";
+        assert!(matches!(mask_skipped(text, false, false), Cow::Borrowed(_)));
+        assert!(matches!(
+            mask_skipped("just 5!", true, true),
+            Cow::Borrowed("just 5!")
+        ));
+    }
+
+    #[test]
+    fn test_inline_code() {
+        assert_eq!(
+            mask_skipped("if `x != 5` then 3!", true, false),
+            "if          then 3!"
+        );
+        assert_eq!(
+            mask_skipped("``a ` 5!`` but 4!", true, false),
+            "           but 4!"
+        );
+        // Unmatched or escaped backticks are literal
+        assert_eq!(mask_skipped("a ` 5!", true, false), "a ` 5!");
+        assert_eq!(mask_skipped("\\`5!\\` ok", true, false), "\\`5!\\` ok");
+        // Inline code ends with the paragraph
+        assert_eq!(mask_skipped("a `5!\n\n3!`", true, false), "a `5!\n\n3!`");
+        // Positions stay the same, even with multibyte chars
+        assert_eq!(mask_skipped("`ö!` 4!", true, false), "      4!");
+    }
+
+    #[test]
+    fn test_fenced_code() {
+        assert_eq!(
+            mask_skipped("look\n```rust\nx != 5\n```\n4!", true, false),
+            "look\n       \n      \n   \n4!"
+        );
+        assert_eq!(
+            mask_skipped("~~~~\n5!\n~~~\n~~~~\n4!", true, false),
+            "    \n  \n   \n    \n4!"
+        );
+        // Unclosed fences go to the end
+        assert_eq!(mask_skipped("```\n5!", true, false), "   \n  ");
+        // Not code, if only quotes are skipped
+        assert_eq!(mask_skipped("```\n5!\n```", false, true), "```\n5!\n```");
+    }
+
+    #[test]
+    fn test_indented_code() {
+        assert_eq!(
+            mask_skipped("code:\n\n    x != 5\n\n\tfact(5)!\n4!", true, false),
+            "code:\n\n          \n\n         \n4!"
+        );
+        // Can't interrupt a paragraph
+        assert_eq!(
+            mask_skipped("text\n    5! more", true, false),
+            "text\n    5! more"
+        );
+    }
+
+    #[test]
+    fn test_quotes() {
+        assert_eq!(
+            mask_skipped("> 5! is big\nstill quoted\n\n4!", false, true),
+            "           \n            \n\n4!"
+        );
+        assert_eq!(mask_skipped("&gt; 5!\n\n4!", false, true), "       \n\n4!");
+        // Spoilers are not quotes
+        assert_eq!(mask_skipped(">!5!!< 4!", false, true), ">!5!!< 4!");
+        // Quotes are kept, if only code is skipped
+        assert_eq!(mask_skipped("> 5!", true, false), "> 5!");
+    }
+}
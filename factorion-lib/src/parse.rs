@@ -1,14 +1,18 @@
 //! Parses text and extracts calculations
 
+use std::borrow::Cow;
 use std::ops::ControlFlow;
 
+#[cfg(any(feature = "serde", test))]
+use serde::{Deserialize, Serialize};
+
 use crate::locale::NumFormat;
-use crate::rug::{Complete, Float, Integer, integer::IntegerExt64};
+use crate::rug::{Complete, Float, Integer, integer::IntegerExt64, ops::Pow};
 
 use crate::Consts;
 use crate::{
     calculation_results::Number,
-    calculation_tasks::{CalculationBase, CalculationJob},
+    calculation_tasks::{CalculationBase, CalculationJob, Operation, Span},
 };
 
 pub mod recommended {
@@ -17,7 +21,68 @@ pub mod recommended {
     pub static INTEGER_CONSTRUCTION_LIMIT: fn() -> Integer = || 200_000_000u128.into();
 }
 
-// NOTE: Most of these rely on being ascii (byte indxed)
+// NOTE: Most of these rely on being ascii (byte indxed), other digits and fullwidth ops are normalized first
+
+// Zeros of the digit systems we normalize, each followed by the other digits
+const DIGIT_ZEROS: [char; 4] = ['０', '٠', '۰', '०'];
+const FULLWIDTH_OPS: [(char, char); 4] = [('！', '!'), ('？', '?'), ('（', '('), ('）', ')')];
+// Arabic separators, replaced by the locale's decimal and an always allowed separator
+const ARABIC_DECIMAL: char = '٫';
+const ARABIC_GROUP: char = '٬';
+
+// Numerals (only parsed if asked for)
+const ROMAN_NUMERALS: [(char, u32); 7] = [
+    ('I', 1),
+    ('V', 5),
+    ('X', 10),
+    ('L', 50),
+    ('C', 100),
+    ('D', 500),
+    ('M', 1000),
+];
+// Longest canonical Roman numeral (MMMDCCCLXXXVIII)
+const ROMAN_NUMERAL_MAX_LEN: usize = 15;
+// Words (and common abbreviations), that happen to be canonical Roman numerals
+const ROMAN_NUMERAL_WORDS: [&str; 10] =
+    ["CD", "CV", "DC", "DI", "LI", "MD", "MI", "MIX", "MM", "XL"];
+const ROMAN_NUMERAL_PARTS: [(&str, u32); 13] = [
+    ("M", 1000),
+    ("CM", 900),
+    ("D", 500),
+    ("CD", 400),
+    ("C", 100),
+    ("XC", 90),
+    ("L", 50),
+    ("XL", 40),
+    ("X", 10),
+    ("IX", 9),
+    ("V", 5),
+    ("IV", 4),
+    ("I", 1),
+];
+const CJK_DIGITS: [(char, u64); 13] = [
+    ('〇', 0),
+    ('零', 0),
+    ('一', 1),
+    ('二', 2),
+    ('两', 2),
+    ('兩', 2),
+    ('三', 3),
+    ('四', 4),
+    ('五', 5),
+    ('六', 6),
+    ('七', 7),
+    ('八', 8),
+    ('九', 9),
+];
+const CJK_UNITS: [(char, u64); 3] = [('十', 10), ('百', 100), ('千', 1000)];
+const CJK_LARGE_UNITS: [(char, u64); 5] = [
+    ('万', 10_000),
+    ('萬', 10_000),
+    ('億', 100_000_000),
+    ('亿', 100_000_000),
+    ('兆', 1_000_000_000_000),
+];
 
 const POI_STARTS: &[char] = &[
     NEGATION,
@@ -43,6 +108,11 @@ const POI_STARTS: &[char] = &[
     'τ',
     '∞',
     '^', // Tetration
+    'C', // Functions
+    'B',
+    'S',
+    's',
+    'D',
     URI_POI,
     SPOILER_POI,
     SPOILER_HTML_POI,
@@ -82,19 +152,241 @@ const SEPARATORS: [char; 4] = ['.', ',', '_', '\''];
 
 const PREFIX_OPS: [char; 1] = ['!'];
 #[allow(dead_code)]
-const POSTFIX_OPS: [char; 2] = ['!', '?'];
+const POSTFIX_OPS: [char; 3] = ['!', '?', '$'];
+// Written as n!/n#
+const COMPOSITORIAL_END: char = '#';
+// Written as x^(n) or x↑n
+const RISING_FACTORIAL_START: &str = "^(";
+const RISING_FACTORIAL_ARROW: char = '↑';
+// Written as (x)_n
+const FALLING_FACTORIAL_START: char = '_';
+// Written as a↑↑b, a^^b, a↑↑↑b, ... or as a tower a^b^c
+const HYPER_ARROWS: [&str; 3] = ["↑", "^", "\\^"];
+// To limit recursion (and a↑↑...↑b is too large for anything else anyway)
+const MAX_HYPER_ARROWS: usize = 32;
+// Bits to keep on top of the shown digits, when turning towers into numbers
+const GUARD_BITS: i32 = 64;
+// Written as n! mod m or n! % m
+const MODULO_OPS: [&str; 3] = ["mod", "%", "\\%"];
+// Constructor of an operation from its second operand
+type WithOperand = fn(Integer) -> Operation;
+// Written as C(n), B(n), S(n, k), s(n, k), D(n) or D(n, k)
+// (name, operation with one argument, operation with two arguments)
+const FUNCTIONS: [(char, Option<Operation>, Option<WithOperand>); 5] = [
+    ('C', Some(Operation::Catalan), None),
+    ('B', Some(Operation::Bell), None),
+    ('S', None, Some(Operation::StirlingSecond)),
+    ('s', None, Some(Operation::StirlingFirst)),
+    (
+        'D',
+        Some(Operation::Factorial(0)),
+        Some(Operation::Rencontres),
+    ),
+];
 
-const INTEGER_ONLY_OPS: [i32; 1] = [0];
+const INTEGER_ONLY_OPS: [Operation; 5] = [
+    Operation::Factorial(0),
+    Operation::ExponentialFactorial,
+    Operation::Compositorial,
+    Operation::Catalan,
+    Operation::Bell,
+];
+
+// Code filter (see is_code)
+const CODE_NEGATION_BEFORE: &[char] = &['=', '&', '|', '?', ':'];
+const CODE_NEGATION_AFTER: &[char] = &[';', '&', '|', '?'];
+const CODE_BEFORE: &[char] = &[
+    '=', '(', '[', '{', ',', '&', '|', '<', '>', '+', '*', '/', '$',
+];
+const CODE_AFTER: &[char] = &[';', ')', ']', '}', ',', '&', '|', '<', '>', '+', '*', '/'];
+const CODE_MARKERS: [&str; 16] = [
+    "==", "!=", "&&", "||", "=>", "->", "::", "{", "}", ");", "//", "/*", "++", "$((", "#define",
+    "#include",
+];
+// Negated booleans in C-like code
+const CODE_BOOLEANS: [&str; 2] = ["!0", "!1"];
+const CODE_KEYWORDS: [&str; 14] = [
+    "if (",
+    "while (",
+    "for (",
+    "return ",
+    "let ",
+    "var ",
+    "val ",
+    "const ",
+    "fn ",
+    "def ",
+    "function ",
+    "int ",
+    "bool ",
+    "echo ",
+];
 
 pub fn parse(
-    mut text: &str,
+    text: &str,
+    do_termial: bool,
+    consts: &Consts,
+    locale: &NumFormat,
+) -> Vec<CalculationJob> {
+    parse_with_bare_numbers(text, do_termial, None, consts, locale)
+}
+
+/// Like [parse], but if bare_number_operation is given, integers without any operation are also extracted, with that operation. \
+/// The spans of the jobs (and their nested steps) are byte ranges in text.
+pub fn parse_with_bare_numbers(
+    text: &str,
     do_termial: bool,
+    bare_number_operation: Option<Operation>,
     consts: &Consts,
     locale: &NumFormat,
 ) -> Vec<CalculationJob> {
+    parse_with_diagnostics(
+        text,
+        do_termial,
+        bare_number_operation,
+        false,
+        false,
+        false,
+        consts,
+        locale,
+    )
+    .0
+}
+
+/// Like [parse_with_bare_numbers], but also returns [Diagnostic]s for text that almost was a calculation. \
+/// If filter_code is set, calculations that are more likely code (like `if (5!=x)` or `return !0;`) are left out. \
+/// If numerals is set, Roman (like `XII!`) and CJK numerals (like `十二!`) directly followed by an op are also parsed. \
+/// If functions is set, combinatorial functions (like `C(5)` or `S(5, 2)`) are also parsed.
+#[allow(clippy::too_many_arguments)]
+pub fn parse_with_diagnostics(
+    text: &str,
+    do_termial: bool,
+    bare_number_operation: Option<Operation>,
+    filter_code: bool,
+    numerals: bool,
+    functions: bool,
+    consts: &Consts,
+    locale: &NumFormat,
+) -> (Vec<CalculationJob>, Vec<Diagnostic>) {
+    let normalized = Normalized::new(text, locale.decimal);
+    let (mut jobs, mut diagnostics) = parse_ascii(
+        &normalized.text,
+        do_termial,
+        bare_number_operation,
+        filter_code,
+        numerals,
+        functions,
+        consts,
+        locale,
+    );
+    if !normalized.shifts.is_empty() {
+        for job in &mut jobs {
+            normalized.restore_spans(job);
+        }
+        for diagnostic in &mut diagnostics {
+            diagnostic.span = normalized.original_span(diagnostic.span);
+            diagnostic.text = diagnostic_text(text, diagnostic.span);
+        }
+    }
+    (jobs, diagnostics)
+}
+
+/// Replaces other digit systems, Arabic separators and fullwidth ops with their ascii versions (using `.` as decimal)
+pub fn normalize_digits(text: &str) -> Cow<'_, str> {
+    Normalized::new(text, '.').text
+}
+
+/// The ascii version of digits of other systems (fullwidth, Arabic-Indic, Persian and Devanagari), Arabic separators and fullwidth ops.
+fn normalize_char(c: char, decimal: char) -> Option<char> {
+    let digit = DIGIT_ZEROS
+        .iter()
+        .find_map(|zero| (c as u32).checked_sub(*zero as u32).filter(|d| *d < 10));
+    if let Some(digit) = digit {
+        return char::from_digit(digit, 10);
+    }
+    match c {
+        ARABIC_DECIMAL => return Some(decimal),
+        ARABIC_GROUP => return Some('_'),
+        _ => {}
+    }
+    FULLWIDTH_OPS
+        .iter()
+        .find_map(|(fullwidth, ascii)| (c == *fullwidth).then_some(*ascii))
+}
+
+/// Text with [normalize_char] applied, which remembers where the text got shorter, to restore spans.
+struct Normalized<'a> {
+    text: Cow<'a, str>,
+    /// (position after a replaced char, bytes removed up to there)
+    shifts: Vec<(usize, usize)>,
+}
+
+impl<'a> Normalized<'a> {
+    fn new(text: &'a str, decimal: char) -> Self {
+        if !text.contains(|c| normalize_char(c, decimal).is_some()) {
+            return Self {
+                text: Cow::Borrowed(text),
+                shifts: Vec::new(),
+            };
+        }
+        let mut normalized = String::with_capacity(text.len());
+        let mut shifts = Vec::new();
+        let mut removed = 0;
+        for c in text.chars() {
+            match normalize_char(c, decimal) {
+                Some(ascii) => {
+                    normalized.push(ascii);
+                    removed += c.len_utf8() - 1;
+                    shifts.push((normalized.len(), removed));
+                }
+                None => normalized.push(c),
+            }
+        }
+        Self {
+            text: Cow::Owned(normalized),
+            shifts,
+        }
+    }
+
+    /// Position in the original text
+    fn original(&self, position: usize) -> usize {
+        let i = self.shifts.partition_point(|(after, _)| *after <= position);
+        position + i.checked_sub(1).map_or(0, |i| self.shifts[i].1)
+    }
+
+    fn original_span(&self, span: Span) -> Span {
+        Span::new(self.original(span.start), self.original(span.end))
+    }
+
+    /// Turns the spans of the job and its steps back into spans of the original text
+    fn restore_spans(&self, mut job: &mut CalculationJob) {
+        loop {
+            job.span = self.original_span(job.span);
+            let CalculationBase::Calc(inner) = &mut job.base else {
+                return;
+            };
+            job = inner;
+        }
+    }
+}
+
+/// Parses text, which uses only ascii digits and ops (see [Normalized])
+#[allow(clippy::too_many_arguments)]
+fn parse_ascii(
+    mut text: &str,
+    do_termial: bool,
+    bare_number_operation: Option<Operation>,
+    filter_code: bool,
+    numerals: bool,
+    functions: bool,
+    consts: &Consts,
+    locale: &NumFormat,
+) -> (Vec<CalculationJob>, Vec<Diagnostic>) {
+    let full_text = text;
+    let mut diagnostics = Vec::new();
     let mut jobs = Vec::new();
     let mut base: Option<CalculationBase> = None;
-    let mut paren_steps: Vec<(u32, Option<i32>, bool)> = Vec::new();
+    let mut paren_steps: Vec<(u32, Option<Operation>, bool, usize)> = Vec::new();
     let mut current_negative: u32 = 0;
     let mut last_len = usize::MAX;
     let mut had_text_before = false;
@@ -109,7 +401,12 @@ pub fn parse(
             current_negative = 0;
             had_text_before = false;
         }
-        let Some(position_of_interest) = text.find(POI_STARTS) else {
+        let position_of_interest = if numerals {
+            text.find(|c| POI_STARTS.contains(&c) || is_numeral_char(c))
+        } else {
+            text.find(POI_STARTS)
+        };
+        let Some(position_of_interest) = position_of_interest else {
             break;
         };
         if position_of_interest != 0 {
@@ -130,7 +427,20 @@ pub fn parse(
             parse_escape(&mut text);
             continue;
         } else if text.starts_with(URI_START) {
+            let uri_end = full_text.len() - text.len();
             parse_uri(&mut text);
+            let uri_start = full_text[..uri_end]
+                .rfind(char::is_whitespace)
+                .map_or(0, |start| start + 1);
+            let uri = &full_text[uri_start..full_text.len() - text.len()];
+            if looks_like_calculation(uri) {
+                diagnose(
+                    &mut diagnostics,
+                    full_text,
+                    DiagnosticKind::InUri,
+                    Span::new(uri_start, full_text.len() - text.len()),
+                );
+            }
             continue;
         } else if text.starts_with(SPOILER_START) {
             parse_spoiler(&mut text, SPOILER_END, &mut current_negative);
@@ -142,24 +452,39 @@ pub fn parse(
             parse_negation(&mut text, &mut current_negative);
             continue;
         } else if text.starts_with(PAREN_START) {
+            let start = full_text.len() - text.len();
             text = &text[PAREN_START.len_utf8()..];
-            parse_paren_start(ParseContext {
-                jobs: &mut jobs,
-                base: &mut base,
-                paren_steps: &mut paren_steps,
-                current_negative: &mut current_negative,
-            });
+            parse_paren_start(
+                start,
+                ParseContext {
+                    jobs: &mut jobs,
+                    base: &mut base,
+                    paren_steps: &mut paren_steps,
+                    current_negative: &mut current_negative,
+                    bare_number_operation: bare_number_operation.as_ref(),
+                    numerals,
+                    functions,
+                    full_text,
+                    diagnostics: &mut diagnostics,
+                },
+            );
             continue;
         } else if text.starts_with(PAREN_END) {
             text = &text[PAREN_END.len_utf8()..];
             if let ControlFlow::Break(_) = parse_paren_end(
                 &mut text,
                 do_termial,
+                consts,
                 ParseContext {
                     jobs: &mut jobs,
                     base: &mut base,
                     paren_steps: &mut paren_steps,
                     current_negative: &mut current_negative,
+                    bare_number_operation: bare_number_operation.as_ref(),
+                    numerals,
+                    functions,
+                    full_text,
+                    diagnostics: &mut diagnostics,
                 },
             ) {
                 continue;
@@ -175,6 +500,11 @@ pub fn parse(
                     base: &mut base,
                     paren_steps: &mut paren_steps,
                     current_negative: &mut current_negative,
+                    bare_number_operation: bare_number_operation.as_ref(),
+                    numerals,
+                    functions,
+                    full_text,
+                    diagnostics: &mut diagnostics,
                 },
             ) {
                 continue;
@@ -189,6 +519,11 @@ pub fn parse(
                 base: &mut base,
                 paren_steps: &mut paren_steps,
                 current_negative: &mut current_negative,
+                bare_number_operation: bare_number_operation.as_ref(),
+                numerals,
+                functions,
+                full_text,
+                diagnostics: &mut diagnostics,
             },
             had_text,
             &mut had_text_before,
@@ -204,16 +539,197 @@ pub fn parse(
     if let Some(CalculationBase::Calc(job)) = base.take() {
         jobs.push(*job);
     }
-    jobs.sort();
-    jobs.dedup();
-    jobs
+    // Prefix ops with an opening paren, that was never closed
+    for (_, operation, poisoned, start) in paren_steps {
+        if operation.is_some() && !poisoned {
+            let end = full_text[start..]
+                .find(char::is_whitespace)
+                .map_or(full_text.len(), |end| start + end);
+            diagnose(
+                &mut diagnostics,
+                full_text,
+                DiagnosticKind::UnbalancedParen,
+                Span::new(start, end),
+            );
+        }
+    }
+    if filter_code {
+        let code_like = looks_like_code(full_text);
+        jobs.retain(|job| {
+            let is_code = is_code(full_text, job.span, code_like);
+            if is_code {
+                diagnose(
+                    &mut diagnostics,
+                    full_text,
+                    DiagnosticKind::LooksLikeCode,
+                    job.span,
+                );
+            }
+            !is_code
+        });
+    }
+    jobs.sort_by(CalculationJob::cmp_calculation);
+    jobs.dedup_by(|a, b| a.cmp_calculation(b).is_eq());
+    (jobs, diagnostics)
+}
+
+/// Heuristic for a calculation being code instead (like `if (5!=x)`, `return !0;` or `fact(5)!`).
+/// If the whole text is code_like, calculations directly next to code punctuation (like `x = !1 && y`) are also code.
+fn is_code(full_text: &str, span: Span, code_like: bool) -> bool {
+    let (Some(before), Some(calc), Some(after)) = (
+        full_text.get(..span.start),
+        span.slice(full_text),
+        full_text.get(span.end..),
+    ) else {
+        return false;
+    };
+    // Comparisons (5!=x, 5!==x), but not equations (5!=120)
+    if calc.ends_with(POSTFIX_OPS)
+        && after.starts_with('=')
+        && (code_like
+            || !after
+                .trim_start_matches('=')
+                .trim_start()
+                .starts_with(char::is_numeric))
+    {
+        return true;
+    }
+    // Function calls (fact(5)!)
+    if calc.starts_with(PAREN_START) && before.ends_with(is_word_char) {
+        return true;
+    }
+    let before = before.trim_end();
+    let after = after.trim_start();
+    // Boolean negation (return !0;, x = !1, a && !1)
+    if calc.starts_with(PREFIX_OPS)
+        && (before.ends_with(CODE_NEGATION_BEFORE)
+            || ends_with_word(before, "return")
+            || after.starts_with(CODE_NEGATION_AFTER))
+    {
+        return true;
+    }
+    code_like
+        && (CODE_BOOLEANS.contains(&calc)
+            || before.ends_with(CODE_BEFORE)
+            || after.starts_with(CODE_AFTER))
+}
+
+/// If the text looks like code, by using at least two different kinds of code syntax
+fn looks_like_code(text: &str) -> bool {
+    let markers = CODE_MARKERS
+        .iter()
+        .filter(|marker| text.contains(*marker))
+        .count();
+    // Only whole words (not "dif (")
+    let keywords = CODE_KEYWORDS
+        .iter()
+        .filter(|keyword| {
+            text.match_indices(*keyword)
+                .any(|(i, _)| !text[..i].ends_with(is_word_char))
+        })
+        .count();
+    markers + keywords >= 2
+}
+
+/// If text ends with word, that is not just the end of a longer word
+fn ends_with_word(text: &str, word: &str) -> bool {
+    text.strip_suffix(word)
+        .is_some_and(|rest| !rest.ends_with(is_word_char))
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Why something, that looks like a calculation, was not calculated (or could not be).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(any(feature = "serde", test), derive(Serialize, Deserialize))]
+#[cfg_attr(any(feature = "serde", test), serde(rename_all = "snake_case"))]
+#[non_exhaustive]
+pub enum DiagnosticKind {
+    /// It is part of a link (like `https://example.com/5!`).
+    InUri,
+    /// The op is escaped (like `5\!`).
+    Escaped,
+    /// The op is only defined for integers, but the number is a decimal (like `!1.5`).
+    IntegerOnlyOp,
+    /// The op needs the exact integer, but the number is too big to know it.
+    TooBig,
+    /// A paren is not matched (like `5)!` or `!(5`).
+    UnbalancedParen,
+    /// It is more likely code (like `if (5!=x)`), only with the code filter.
+    LooksLikeCode,
+}
+
+/// A [DiagnosticKind] along with where it happened.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(any(feature = "serde", test), derive(Serialize, Deserialize))]
+pub struct Diagnostic {
+    pub kind: DiagnosticKind,
+    pub span: Span,
+    /// The text of the span
+    pub text: String,
+}
+
+fn diagnose(diagnostics: &mut Vec<Diagnostic>, full_text: &str, kind: DiagnosticKind, span: Span) {
+    diagnostics.push(Diagnostic {
+        kind,
+        span,
+        text: diagnostic_text(full_text, span),
+    });
+}
+
+fn diagnostic_text(full_text: &str, span: Span) -> String {
+    // Numbers take trailing separators (like in "!1.5, and"), which are just punctuation here
+    span.slice(full_text)
+        .unwrap_or_default()
+        .trim_end_matches(SEPARATORS)
+        .to_owned()
+}
+
+/// If a number or paren is directly followed by an op (used for text we skip)
+fn looks_like_calculation(text: &str) -> bool {
+    text.match_indices(POSTFIX_OPS).any(|(i, _)| {
+        text[..i]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_ascii_digit() || c == PAREN_END)
+    })
+}
+
+/// If the op can't be done, as it needs the exact integer, and the number is too big to know it
+fn too_big_for(num: &Number, operation: &Operation, consts: &Consts<'_>) -> bool {
+    *operation != Operation::Factorial(0)
+        && INTEGER_ONLY_OPS.contains(operation)
+        && matches!(num, Number::Approximate(_, exponent) if *exponent > consts.integer_construction_limit)
 }
 
 struct ParseContext<'a> {
     jobs: &'a mut Vec<CalculationJob>,
     base: &'a mut Option<CalculationBase>,
-    paren_steps: &'a mut Vec<(u32, Option<i32>, bool)>,
+    /// (negations, prefix op, poisoned, start)
+    paren_steps: &'a mut Vec<(u32, Option<Operation>, bool, usize)>,
     current_negative: &'a mut u32,
+    bare_number_operation: Option<&'a Operation>,
+    /// Also parse Roman and CJK numerals
+    numerals: bool,
+    /// Also parse combinatorial functions
+    functions: bool,
+    /// The whole text, to get positions from the rest
+    full_text: &'a str,
+    diagnostics: &'a mut Vec<Diagnostic>,
+}
+
+impl ParseContext<'_> {
+    /// Span from start to where the rest of the text begins
+    fn span_to(&self, start: usize, rest: usize) -> Span {
+        Span::new(start, self.full_text.len() - rest)
+    }
+
+    fn diagnose(&mut self, kind: DiagnosticKind, start: usize, rest: usize) {
+        let span = self.span_to(start, rest);
+        diagnose(self.diagnostics, self.full_text, kind, span);
+    }
 }
 
 fn parse_other(
@@ -221,7 +737,7 @@ fn parse_other(
     do_termial: bool,
     consts: &Consts<'_>,
     locale: &NumFormat,
-    parse_context: ParseContext<'_>,
+    mut parse_context: ParseContext<'_>,
     had_text: bool,
     had_text_before: &mut bool,
 ) -> ControlFlow<()> {
@@ -230,21 +746,83 @@ fn parse_other(
         *text = &text[1..];
         return ControlFlow::Break(());
     }
-    let Some(num) = parse_num(text, had_text, false, consts, locale) else {
-        *had_text_before = true;
-        // advance one char to avoid loop
-        let mut end = 1;
-        while !text.is_char_boundary(end) && end < text.len() {
-            end += 1;
-        }
-        *text = &text[end.min(text.len())..];
+    let num_text = *text;
+    let start = parse_context.full_text.len() - text.len();
+    let functions = parse_context.functions && !had_text;
+    let function = if functions {
+        parse_function(text)
+    } else {
+        None
+    };
+    if function.is_none() && functions && parse_function_call(text, start, &mut parse_context) {
         return ControlFlow::Break(());
+    }
+    let numeral = if function.is_none() && parse_context.numerals {
+        let previous = parse_context.full_text[..start].chars().next_back();
+        parse_numeral(text, had_text, previous)
+    } else {
+        None
+    };
+    let (num, first) = match (function, numeral) {
+        (Some((n, operation)), _) => (Number::Exact(n), Some(operation)),
+        (None, Some(n)) => (Number::Exact(n), None),
+        (None, None) => {
+            let Some(num) = parse_num(text, had_text, false, consts, locale) else {
+                *had_text_before = true;
+                // advance one char to avoid loop
+                let mut end = 1;
+                while !text.is_char_boundary(end) && end < text.len() {
+                    end += 1;
+                }
+                *text = &text[end.min(text.len())..];
+                return ControlFlow::Break(());
+            };
+            (num, None)
+        }
     };
-    let Some(levels) = parse_ops(text, false, do_termial) else {
+    let num_text = &num_text[..num_text.len() - text.len()];
+    // The first step, if it is written around the number
+    let num_end = text.len();
+    let first =
+        first.or_else(|| parse_rising_order(text, do_termial).map(Operation::RisingFactorial));
+    let first_end = text.len();
+    let Some(mut operations) = parse_ops(text, false, do_termial) else {
         return ControlFlow::Break(());
     };
-    if !levels.is_empty() {
-        let levels = levels.into_iter();
+    if operations.is_empty()
+        && first.is_none()
+        && let Some(rest) = text.strip_prefix(ESCAPE)
+        && rest.starts_with(POSTFIX_OPS)
+    {
+        let end = rest
+            .find(|c| !POSTFIX_OPS.contains(&c))
+            .unwrap_or(rest.len());
+        parse_context.diagnose(DiagnosticKind::Escaped, start, rest.len() - end);
+    }
+    if operations.len() == 1
+        && operations[0].0 == Operation::Factorial(1)
+        && let Some(rest) = text
+            .strip_prefix('/')
+            .and_then(|rest| rest.strip_prefix(num_text))
+            .and_then(|rest| rest.strip_prefix(COMPOSITORIAL_END))
+    {
+        *text = rest;
+        operations = vec![(Operation::Compositorial, text.len())];
+    }
+    // The modulus belongs to the last step, if it is a factorial (of an integer)
+    if let Some((operation, end)) = operations.last_mut()
+        && *operation == Operation::Factorial(1)
+        && matches!(num, Number::Exact(_))
+    {
+        if skip_calculated_modulus(text) {
+            return ControlFlow::Break(());
+        }
+        if let Some(modulus) = parse_modulus(text) {
+            *operation = Operation::FactorialModulo(modulus);
+            *end = text.len();
+        }
+    }
+    if !operations.is_empty() || first.is_some() {
         if let Some(CalculationBase::Calc(job)) = parse_context.base.take() {
             // multiple number, likely expression => poision paren
             if let Some(step) = parse_context.paren_steps.last_mut() {
@@ -252,23 +830,51 @@ fn parse_other(
             }
             parse_context.jobs.push(*job);
         }
-        *parse_context.base = Some(CalculationBase::Num(num));
-        for level in levels {
+        *parse_context.base = Some(match first {
+            Some(operation) => CalculationBase::Calc(Box::new(CalculationJob {
+                base: CalculationBase::Num(num),
+                operation,
+                negative: 0,
+                span: parse_context.span_to(start, first_end),
+            })),
+            None => CalculationBase::Num(num),
+        });
+        for (operation, end) in operations {
             let previous = parse_context.base.take().unwrap();
             if let (CalculationBase::Num(Number::Float(_)), true) =
-                (&previous, INTEGER_ONLY_OPS.contains(&level))
+                (&previous, INTEGER_ONLY_OPS.contains(&operation))
             {
+                parse_context.diagnose(DiagnosticKind::IntegerOnlyOp, start, end);
                 continue;
             }
+            if let CalculationBase::Num(num) = &previous
+                && too_big_for(num, &operation, consts)
+            {
+                parse_context.diagnose(DiagnosticKind::TooBig, start, end);
+            }
             *parse_context.base = Some(CalculationBase::Calc(Box::new(CalculationJob {
                 base: previous,
-                level,
+                operation,
                 negative: 0,
+                span: parse_context.span_to(start, end),
             })))
         }
         if let Some(CalculationBase::Calc(job)) = parse_context.base {
             job.negative = *parse_context.current_negative;
         }
+    } else if let Some(operation) = parse_context.bare_number_operation.cloned()
+        && parse_context.paren_steps.is_empty()
+        && matches!(num, Number::Exact(_))
+    {
+        if let Some(CalculationBase::Calc(job)) = parse_context.base.take() {
+            parse_context.jobs.push(*job);
+        }
+        *parse_context.base = Some(CalculationBase::Calc(Box::new(CalculationJob {
+            base: CalculationBase::Num(num),
+            operation,
+            negative: *parse_context.current_negative,
+            span: parse_context.span_to(start, num_end),
+        })));
     } else if !parse_context.paren_steps.is_empty() {
         let mut num = num;
         if *parse_context.current_negative % 2 == 1 {
@@ -295,9 +901,10 @@ fn parse_prefix_ops(
     do_termial: bool,
     consts: &Consts<'_>,
     locale: &NumFormat,
-    parse_context: ParseContext<'_>,
+    mut parse_context: ParseContext<'_>,
 ) -> ControlFlow<()> {
-    let Ok(level) = parse_op(text, true, do_termial) else {
+    let start = parse_context.full_text.len() - text.len();
+    let Ok(operation) = parse_op(text, true, do_termial) else {
         // also skip number to prevent stuff like "!!!1!" getting through
         parse_num(text, false, true, consts, locale);
         return ControlFlow::Break(());
@@ -310,34 +917,43 @@ fn parse_prefix_ops(
             }
             parse_context.jobs.push(*job);
         }
-        if let (Number::Float(_), true) = (&num, INTEGER_ONLY_OPS.contains(&level)) {
+        if let (Number::Float(_), true) = (&num, INTEGER_ONLY_OPS.contains(&operation)) {
+            parse_context.diagnose(DiagnosticKind::IntegerOnlyOp, start, text.len());
             return ControlFlow::Break(());
         }
+        if too_big_for(&num, &operation, consts) {
+            parse_context.diagnose(DiagnosticKind::TooBig, start, text.len());
+        }
         *parse_context.base = Some(CalculationBase::Calc(Box::new(CalculationJob {
             base: CalculationBase::Num(num),
-            level,
+            operation,
             negative: *parse_context.current_negative,
+            span: parse_context.span_to(start, text.len()),
         })));
         *parse_context.current_negative = 0;
-        let Some(levels) = parse_ops(text, false, do_termial) else {
+        let Some(operations) = parse_ops(text, false, do_termial) else {
             return ControlFlow::Break(());
         };
-        for level in levels {
+        for (operation, end) in operations {
             // base available?
             let Some(inner) = parse_context.base.take() else {
                 continue;
             };
             *parse_context.base = Some(CalculationBase::Calc(Box::new(CalculationJob {
                 base: inner,
-                level,
+                operation,
                 negative: 0,
+                span: parse_context.span_to(start, end),
             })));
         }
     } else {
         if text.starts_with(PAREN_START) {
-            parse_context
-                .paren_steps
-                .push((*parse_context.current_negative, Some(level), false));
+            parse_context.paren_steps.push((
+                *parse_context.current_negative,
+                Some(operation),
+                false,
+                start,
+            ));
             *parse_context.current_negative = 0;
             *text = &text[PAREN_START.len_utf8()..];
         }
@@ -349,11 +965,19 @@ fn parse_prefix_ops(
 fn parse_paren_end(
     text: &mut &str,
     do_termial: bool,
-    parse_context: ParseContext<'_>,
+    consts: &Consts<'_>,
+    mut parse_context: ParseContext<'_>,
 ) -> ControlFlow<()> {
     *parse_context.current_negative = 0;
     // Paren mismatch?
     let Some(step) = parse_context.paren_steps.pop() else {
+        if text.starts_with(POSTFIX_OPS) {
+            let start = parse_context.full_text.len() - text.len() - PAREN_END.len_utf8();
+            let end = text
+                .find(|c| !POSTFIX_OPS.contains(&c))
+                .unwrap_or(text.len());
+            parse_context.diagnose(DiagnosticKind::UnbalancedParen, start, text.len() - end);
+        }
         return ControlFlow::Break(());
     };
     // poisoned paren
@@ -367,8 +991,38 @@ fn parse_paren_end(
         }
         return ControlFlow::Break(());
     }
+    let start = step.3;
     let mut had_op = false;
-    if let Some(level) = step.1 {
+    // A calculation as the order (like (5)_(3!)) can't be done, so all of it is skipped
+    if step.1.is_none()
+        && parse_context.base.is_some()
+        && do_termial
+        && let Some(rest) = text
+            .strip_prefix(FALLING_FACTORIAL_START)
+            .or_else(|| text.strip_prefix("\\_"))
+        && let Some((_, rest)) = split_paren_group(rest)
+    {
+        parse_context.base.take();
+        *text = rest.trim_start_matches(POSTFIX_OPS);
+        if let Some(step) = parse_context.paren_steps.last_mut() {
+            step.2 = true;
+        }
+        return ControlFlow::Break(());
+    }
+    if step.1.is_none()
+        && parse_context.base.is_some()
+        && let Some(order) = parse_falling_order(text, do_termial)
+        && let Some(inner) = parse_context.base.take()
+    {
+        *parse_context.base = Some(CalculationBase::Calc(Box::new(CalculationJob {
+            base: inner,
+            operation: Operation::FallingFactorial(order),
+            negative: 0,
+            span: parse_context.span_to(start, text.len()),
+        })));
+        had_op = true;
+    }
+    if let Some(operation) = step.1 {
         // base available?
         let Some(inner) = parse_context.base.take() else {
             // no number (maybe var) => poison outer paren
@@ -378,18 +1032,31 @@ fn parse_paren_end(
             return ControlFlow::Break(());
         };
         if let (CalculationBase::Num(Number::Float(_)), true) =
-            (&inner, INTEGER_ONLY_OPS.contains(&level))
+            (&inner, INTEGER_ONLY_OPS.contains(&operation))
+        {
+            parse_context.diagnose(DiagnosticKind::IntegerOnlyOp, start, text.len());
+            return ControlFlow::Break(());
+        }
+        // Functions of natural numbers were already parsed directly (see parse_function_call)
+        if let (CalculationBase::Num(_), Operation::Catalan | Operation::Bell) =
+            (&inner, &operation)
         {
             return ControlFlow::Break(());
         }
+        if let CalculationBase::Num(num) = &inner
+            && too_big_for(num, &operation, consts)
+        {
+            parse_context.diagnose(DiagnosticKind::TooBig, start, text.len());
+        }
         *parse_context.base = Some(CalculationBase::Calc(Box::new(CalculationJob {
             base: inner,
-            level,
+            operation,
             negative: 0,
+            span: parse_context.span_to(start, text.len()),
         })));
         had_op = true;
     }
-    let Some(levels) = parse_ops(text, false, do_termial) else {
+    let Some(operations) = parse_ops(text, false, do_termial) else {
         parse_context.base.take();
         // no number (maybe var) => poison outer paren
         if let Some(step) = parse_context.paren_steps.last_mut() {
@@ -397,29 +1064,56 @@ fn parse_paren_end(
         }
         return ControlFlow::Break(());
     };
-    if !levels.is_empty() {
-        for level in levels {
+    if !operations.is_empty() {
+        let ends_with_factorial = operations
+            .last()
+            .is_some_and(|(operation, _)| *operation == Operation::Factorial(1));
+        for (operation, end) in operations {
             // base available?
             let Some(inner) = parse_context.base.take() else {
                 continue;
             };
             *parse_context.base = Some(CalculationBase::Calc(Box::new(CalculationJob {
                 base: inner,
-                level,
+                operation,
                 negative: 0,
+                span: parse_context.span_to(start, end),
             })));
             had_op = true;
         }
+        if ends_with_factorial {
+            if skip_calculated_modulus(text) {
+                parse_context.base.take();
+                if let Some(step) = parse_context.paren_steps.last_mut() {
+                    step.2 = true;
+                }
+                return ControlFlow::Break(());
+            }
+            if let Some(modulus) = parse_modulus(text) {
+                let span = parse_context.span_to(start, text.len());
+                if let Some(CalculationBase::Calc(job)) = parse_context.base {
+                    job.operation = Operation::FactorialModulo(modulus);
+                    job.span = span;
+                }
+            }
+        }
+    }
+    // The modulus has to be the last step (not like (5! mod 7)!), so all of it is skipped
+    if had_op
+        && let Some(CalculationBase::Calc(job)) = parse_context.base
+        && has_modulo_step(&job.base)
+    {
+        parse_context.base.take();
+        if let Some(step) = parse_context.paren_steps.last_mut() {
+            step.2 = true;
+        }
+        return ControlFlow::Break(());
     }
     if !had_op {
         match parse_context.base {
             Some(CalculationBase::Calc(job)) => job.negative += step.0,
-            Some(CalculationBase::Num(n)) => {
-                if step.0 % 2 != 0 {
-                    n.negate();
-                }
-            }
-            None => {}
+            Some(CalculationBase::Num(n)) if step.0 % 2 != 0 => n.negate(),
+            Some(CalculationBase::Num(_)) | None => {}
         }
     } else {
         match parse_context.base {
@@ -441,10 +1135,10 @@ fn parse_paren_end(
     ControlFlow::Continue(())
 }
 
-fn parse_paren_start(parse_context: ParseContext<'_>) {
+fn parse_paren_start(start: usize, parse_context: ParseContext<'_>) {
     parse_context
         .paren_steps
-        .push((*parse_context.current_negative, None, false));
+        .push((*parse_context.current_negative, None, false, start));
     // Submit current base (we won't use it anymore)
     if let Some(CalculationBase::Calc(job)) = parse_context.base.take() {
         parse_context.jobs.push(*job);
@@ -509,7 +1203,7 @@ enum ParseOpErr {
     InvalidOp,
 }
 
-fn parse_op(text: &mut &str, prefix: bool, do_termial: bool) -> Result<i32, ParseOpErr> {
+fn parse_op(text: &mut &str, prefix: bool, do_termial: bool) -> Result<Operation, ParseOpErr> {
     let op = text.chars().next().ok_or(ParseOpErr::NonOp)?;
     let end = text.find(|c| c != op).unwrap_or(text.len());
     let res = match op {
@@ -518,10 +1212,10 @@ fn parse_op(text: &mut &str, prefix: bool, do_termial: bool) -> Result<i32, Pars
                 if end != 1 {
                     Err(ParseOpErr::InvalidOp)
                 } else {
-                    Ok(0)
+                    Ok(Operation::Factorial(0))
                 }
             } else {
-                Ok(end as i32)
+                Ok(Operation::Factorial(end as i32))
             }
         }
         '?' => {
@@ -530,7 +1224,17 @@ fn parse_op(text: &mut &str, prefix: bool, do_termial: bool) -> Result<i32, Pars
             } else if prefix {
                 Err(ParseOpErr::InvalidOp)
             } else {
-                Ok(-(end as i32))
+                Ok(Operation::Factorial(-(end as i32)))
+            }
+        }
+        // Only with termials, as it is common after prices
+        '$' => {
+            if !do_termial {
+                Err(ParseOpErr::NonOp)
+            } else if prefix || end != 1 {
+                Err(ParseOpErr::InvalidOp)
+            } else {
+                Ok(Operation::ExponentialFactorial)
             }
         }
         _ => return Err(ParseOpErr::NonOp),
@@ -539,11 +1243,12 @@ fn parse_op(text: &mut &str, prefix: bool, do_termial: bool) -> Result<i32, Pars
     res
 }
 
-fn parse_ops(text: &mut &str, prefix: bool, do_termial: bool) -> Option<Vec<i32>> {
+/// Parses consecutive ops, along with the length of the rest of the text after each
+fn parse_ops(text: &mut &str, prefix: bool, do_termial: bool) -> Option<Vec<(Operation, usize)>> {
     let mut res = Vec::new();
     loop {
         match parse_op(text, prefix, do_termial) {
-            Ok(op) => res.push(op),
+            Ok(op) => res.push((op, text.len())),
             Err(ParseOpErr::NonOp) => break,
             Err(ParseOpErr::InvalidOp) => return None,
         }
@@ -551,65 +1256,623 @@ fn parse_ops(text: &mut &str, prefix: bool, do_termial: bool) -> Option<Vec<i32>
     Some(res)
 }
 
-fn parse_num(
-    text: &mut &str,
-    had_text: bool,
-    had_op: bool,
-    consts: &Consts,
-    locale: &NumFormat,
-) -> Option<Number> {
-    let prec = consts.float_precision;
-    if text.starts_with(CONSTANT_STARTS) {
-        return parse_const(text, had_text, prec);
+/// Parses the order of a rising factorial (after x), written as ^(n) or ↑n
+fn parse_rising_order(text: &mut &str, do_termial: bool) -> Option<Integer> {
+    let (rest, paren) = if let Some(rest) = text.strip_prefix(RISING_FACTORIAL_ARROW) {
+        (rest, false)
+    } else if let Some(rest) = text
+        .strip_prefix(RISING_FACTORIAL_START)
+        .or_else(|| text.strip_prefix("\\^("))
+        // Only with termials, as it is common for powers
+        && do_termial
+    {
+        (rest, true)
+    } else {
+        return None;
+    };
+    let end = rest
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(rest.len());
+    let (order, mut rest) = rest.split_at(end);
+    if order.is_empty() {
+        return None;
     }
-
-    if text.starts_with("^(") {
-        return parse_tet(text, locale);
+    if paren {
+        rest = rest.strip_prefix(PAREN_END)?;
+        // ^(n)10 is tetration
+        if rest.starts_with(char::is_numeric) {
+            return None;
+        }
     }
+    let order = parse_integer(order)?;
+    *text = rest;
+    Some(order)
+}
 
-    if text.starts_with("10^") || text.starts_with("10\\^") {
-        return parse_tower(text, had_op, consts, locale, prec);
+/// Parses the order of a falling factorial (after (x)), written as _n
+fn parse_falling_order(text: &mut &str, do_termial: bool) -> Option<Integer> {
+    // Only with termials, as it is common for subscripts
+    if !do_termial {
+        return None;
     }
-
-    parse_num_simple(text, had_op, consts, locale, prec)
+    let rest = text
+        .strip_prefix(FALLING_FACTORIAL_START)
+        .or_else(|| text.strip_prefix("\\_"))?;
+    let end = rest
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(rest.len());
+    let (order, rest) = rest.split_at(end);
+    if order.is_empty() {
+        return None;
+    }
+    let order = parse_integer(order)?;
+    *text = rest;
+    Some(order)
 }
 
-fn parse_const(text: &mut &str, had_text: bool, prec: u32) -> Option<Number> {
-    let (n, x) = if text.starts_with("pi") {
-        ("pi".len(), PI(prec))
-    } else if text.starts_with("π") {
-        ("π".len(), PI(prec))
-    } else if text.starts_with("phi") {
-        ("phi".len(), PHI(prec))
-    } else if text.starts_with("ɸ") {
-        ("ɸ".len(), PHI(prec))
-    } else if text.starts_with("tau") {
-        ("tau".len(), TAU(prec))
-    } else if text.starts_with("τ") {
-        ("τ".len(), TAU(prec))
-    } else if text.starts_with("e") {
-        ("e".len(), E(prec))
-    } else if text.starts_with("infinity") {
-        ("infinity".len(), Number::ComplexInfinity)
-    } else if text.starts_with("inf") {
-        ("inf".len(), Number::ComplexInfinity)
-    } else if text.starts_with("∞\u{303}") {
-        ("∞\u{303}".len(), Number::ComplexInfinity)
-    } else if text.starts_with("∞") {
-        ("∞".len(), Number::ComplexInfinity)
-    } else {
+/// Parses the modulus of a factorial (after n!), written as mod m or % m
+fn parse_modulus(text: &mut &str) -> Option<Integer> {
+    let rest = text.trim_start();
+    let rest = MODULO_OPS
+        .iter()
+        .find_map(|op| rest.strip_prefix(op))?
+        .trim_start();
+    let end = rest
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(rest.len());
+    let (modulus, rest) = rest.split_at(end);
+    // Only integers (not the start of a decimal)
+    if modulus.is_empty()
+        || rest.starts_with(['.', ',']) && rest[1..].starts_with(|c: char| c.is_ascii_digit())
+    {
         return None;
-    };
-    if had_text || text[n..].starts_with(char::is_alphabetic) {
+    }
+    let modulus = parse_integer(modulus)?;
+    // mod 0 is undefined
+    if modulus == 0 {
         return None;
     }
-    *text = &text[n..];
-    Some(x)
+    *text = rest;
+    Some(modulus)
 }
 
-fn parse_tower(
-    text: &mut &str,
-    had_op: bool,
+/// Skips a modulus, that is a calculation itself (like mod 7! or mod (3!)), as that can't be done. \
+/// Returns whether there was one.
+fn skip_calculated_modulus(text: &mut &str) -> bool {
+    let rest = text.trim_start();
+    let Some(rest) = MODULO_OPS.iter().find_map(|op| rest.strip_prefix(op)) else {
+        return false;
+    };
+    let rest = rest.trim_start();
+    let rest = if let Some((_, rest)) = split_paren_group(rest) {
+        rest
+    } else {
+        let end = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        if end == 0 || !rest[end..].starts_with(POSTFIX_OPS) {
+            return false;
+        }
+        &rest[end..]
+    };
+    *text = rest.trim_start_matches(POSTFIX_OPS);
+    true
+}
+
+/// Whether a step of the calculation is a factorial modulo
+fn has_modulo_step(mut base: &CalculationBase) -> bool {
+    while let CalculationBase::Calc(job) = base {
+        if matches!(job.operation, Operation::FactorialModulo(_)) {
+            return true;
+        }
+        base = &job.base;
+    }
+    false
+}
+
+/// Parses a combinatorial function of natural numbers, like C(n) or S(n, k).
+/// Returns n and the operation (with k).
+fn parse_function(text: &mut &str) -> Option<(Integer, Operation)> {
+    let mut chars = text.chars();
+    let name = chars.next()?;
+    let (_, unary, binary) = FUNCTIONS.iter().find(|(c, _, _)| *c == name)?;
+    let rest = chars.as_str().strip_prefix(PAREN_START)?;
+    let (n, rest) = parse_natural_argument(rest)?;
+    let (operation, rest) = if let Some(rest) = rest.strip_prefix(',') {
+        let (k, rest) = parse_natural_argument(rest.trim_start_matches(' '))?;
+        ((*binary)?(k), rest)
+    } else {
+        (unary.clone()?, rest)
+    };
+    *text = rest.strip_prefix(PAREN_END)?;
+    Some((n, operation))
+}
+
+/// Handles a function, whose arguments aren't natural numbers (so [parse_function] failed). \
+/// A single argument (like C(3!)) is parsed like a paren, to which the function is applied when closed. \
+/// With more (like S(3!, 2)), the calculation can't be done, so the function and its arguments are skipped. \
+/// Returns whether it was a function.
+fn parse_function_call(
+    text: &mut &str,
+    start: usize,
+    parse_context: &mut ParseContext<'_>,
+) -> bool {
+    let mut chars = text.chars();
+    let Some(name) = chars.next() else {
+        return false;
+    };
+    let Some((_, unary, _)) = FUNCTIONS.iter().find(|(c, _, _)| *c == name) else {
+        return false;
+    };
+    let Some((args, rest)) = split_paren_group(chars.as_str()) else {
+        return false;
+    };
+    if args.contains(',') {
+        *text = rest.trim_start_matches(POSTFIX_OPS);
+        return true;
+    }
+    let Some(operation) = unary else {
+        return false;
+    };
+    parse_context.paren_steps.push((
+        *parse_context.current_negative,
+        Some(operation.clone()),
+        false,
+        start,
+    ));
+    *parse_context.current_negative = 0;
+    *text = &chars.as_str()[PAREN_START.len_utf8()..];
+    true
+}
+
+/// Splits the text into the content of the paren it starts with and the rest after its closing paren
+fn split_paren_group(text: &str) -> Option<(&str, &str)> {
+    let mut depth = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            PAREN_START => depth += 1,
+            PAREN_END => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some((
+                        &text[PAREN_START.len_utf8()..i],
+                        &text[i + PAREN_END.len_utf8()..],
+                    ));
+                }
+            }
+            _ if depth == 0 => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Parses a Roman (like XII) or CJK numeral (like 十二), only if it is directly followed by an op. \
+/// As Roman numerals are letters, they must not be part of a longer word (had_text). \
+/// CJK text has no spaces, so there it must only not start in the middle of another numeral (previous).
+fn parse_numeral(text: &mut &str, had_text: bool, previous: Option<char>) -> Option<Integer> {
+    let cjk_end = text.find(|c| !is_cjk_numeral_char(c)).unwrap_or(text.len());
+    let (n, end) = if cjk_end > 0 {
+        if previous.is_some_and(is_cjk_numeral_char) {
+            return None;
+        }
+        (parse_cjk_numeral(&text[..cjk_end])?, cjk_end)
+    } else if !had_text {
+        let end = text
+            .find(|c: char| !c.is_alphabetic())
+            .unwrap_or(text.len());
+        (parse_roman_numeral(&text[..end])?.into(), end)
+    } else {
+        return None;
+    };
+    if !text[end..].starts_with(POSTFIX_OPS) {
+        return None;
+    }
+    *text = &text[end..];
+    Some(n)
+}
+
+/// If text has something, that might be a Roman or CJK numeral directly followed by an op
+pub fn might_have_numeral(text: &str) -> bool {
+    text.match_indices(POSTFIX_OPS)
+        .any(|(i, _)| text[..i].chars().next_back().is_some_and(is_numeral_char))
+}
+
+fn is_numeral_char(c: char) -> bool {
+    ROMAN_NUMERALS.iter().any(|(numeral, _)| *numeral == c) || is_cjk_numeral_char(c)
+}
+
+fn is_cjk_numeral_char(c: char) -> bool {
+    CJK_DIGITS.iter().any(|(digit, _)| *digit == c)
+        || CJK_UNITS.iter().any(|(unit, _)| *unit == c)
+        || CJK_LARGE_UNITS.iter().any(|(unit, _)| *unit == c)
+}
+
+/// Parses an upper case Roman numeral, only in its canonical form (so not IIII or IC, or above MMMCMXCIX). \
+/// Single letters (like the word I) and words that happen to be numerals (like MIX) are not parsed.
+fn parse_roman_numeral(text: &str) -> Option<u32> {
+    if !(2..=ROMAN_NUMERAL_MAX_LEN).contains(&text.len()) || ROMAN_NUMERAL_WORDS.contains(&text) {
+        return None;
+    }
+    let mut n: u32 = 0;
+    let mut previous = 0;
+    for c in text.chars().rev() {
+        let (_, value) = ROMAN_NUMERALS.iter().find(|(numeral, _)| *numeral == c)?;
+        if *value < previous {
+            n = n.checked_sub(*value)?;
+        } else {
+            n += value;
+            previous = *value;
+        }
+    }
+    (n > 0 && n < 4000 && to_roman_numeral(n) == text).then_some(n)
+}
+
+fn to_roman_numeral(mut n: u32) -> String {
+    let mut roman = String::new();
+    for (numeral, value) in ROMAN_NUMERAL_PARTS {
+        while n >= value {
+            roman.push_str(numeral);
+            n -= value;
+        }
+    }
+    roman
+}
+
+/// Parses a CJK numeral, either with units (like 二千二十五) or only digits (like 二〇二五)
+fn parse_cjk_numeral(text: &str) -> Option<Integer> {
+    let digit_of = |c: char| {
+        CJK_DIGITS
+            .iter()
+            .find_map(|(digit, value)| (*digit == c).then_some(*value))
+    };
+    if text.chars().all(|c| digit_of(c).is_some()) {
+        return text
+            .chars()
+            .filter_map(digit_of)
+            .try_fold(Integer::new(), |n, digit| Some(n * 10 + digit));
+    }
+    let mut n = Integer::new();
+    // Value below the next large unit (like 万)
+    let mut section: u64 = 0;
+    let mut digit: Option<u64> = None;
+    for c in text.chars() {
+        if let Some(value) = digit_of(c) {
+            // Zeros only fill gaps (like 一百零五)
+            if value == 0 {
+                continue;
+            }
+            if digit.is_some() {
+                return None;
+            }
+            digit = Some(value);
+        } else if let Some((_, unit)) = CJK_UNITS.iter().find(|(unit, _)| *unit == c) {
+            section += digit.take().unwrap_or(1) * unit;
+        } else {
+            let (_, unit) = CJK_LARGE_UNITS.iter().find(|(unit, _)| *unit == c)?;
+            section += digit.take().unwrap_or(0);
+            if section == 0 {
+                return None;
+            }
+            n += Integer::from(section) * unit;
+            section = 0;
+        }
+    }
+    n += section + digit.unwrap_or(0);
+    Some(n)
+}
+
+/// Parses the natural number at the start of a function argument (only ascii digits, as separators are ambiguous there)
+fn parse_natural_argument(text: &str) -> Option<(Integer, &str)> {
+    let end = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let (n, rest) = text.split_at(end);
+    if n.is_empty() {
+        return None;
+    }
+    Some((parse_integer(n)?, rest))
+}
+
+fn parse_num(
+    text: &mut &str,
+    had_text: bool,
+    had_op: bool,
+    consts: &Consts,
+    locale: &NumFormat,
+) -> Option<Number> {
+    let prec = consts.float_precision;
+    if text.starts_with(CONSTANT_STARTS) {
+        return parse_const(text, had_text, prec);
+    }
+
+    if text.starts_with("^(") {
+        return parse_tet(text, locale);
+    }
+
+    if !had_op && let Some(num) = parse_hyperoperation(text, consts, locale) {
+        return num;
+    }
+
+    if text.starts_with("10^") || text.starts_with("10\\^") {
+        return parse_tower(text, had_op, consts, locale, prec);
+    }
+
+    parse_num_simple(text, had_op, consts, locale, prec)
+}
+
+/// A hyperoperation result, either exact, as 10^10^...^top (depth tens)
+/// or between 10↑...↑height and 10↑...↑(height+1) (at least three arrows)
+enum Hyper {
+    Exact(Integer),
+    Tower(Integer, Float),
+    Arrows(u32, Integer),
+}
+
+/// Parses hyperoperations (a↑↑b, a^^b, a↑↑↑b, ...) and power towers (a^b^c, other than 10^...) of natural numbers.
+/// Returns None if there is none, and Some(None) if it can't be used (like with a postfix op in the exponent).
+fn parse_hyperoperation(
+    text: &mut &str,
+    consts: &Consts,
+    locale: &NumFormat,
+) -> Option<Option<Number>> {
+    let orig_text = *text;
+    let (a, mut rest) = parse_natural_argument(text)?;
+    let a_len = text.len() - rest.len();
+    let after_a = rest;
+    let arrows = strip_hyper_arrows(&mut rest);
+    // A single ↑ is the rising factorial
+    if arrows == 0 || arrows == 1 && after_a.starts_with('↑') {
+        return None;
+    }
+    let (b, after_b) = parse_natural_argument(rest)?;
+    rest = after_b;
+    let mut tower = vec![a.clone(), b.clone()];
+    if arrows == 1 {
+        loop {
+            let mut next = rest;
+            if next.starts_with('↑') || strip_hyper_arrows(&mut next) != 1 {
+                break;
+            }
+            let Some((n, after_n)) = parse_natural_argument(next) else {
+                break;
+            };
+            tower.push(n);
+            rest = after_n;
+        }
+        // Powers of ten are towers already
+        if orig_text.starts_with("10^") || orig_text.starts_with("10\\^") {
+            return None;
+        }
+    }
+    // Postfix op (or anything else) on the last number, so it is not the whole
+    if rest.starts_with(POSTFIX_OPS)
+        || HYPER_ARROWS.iter().any(|arrow| rest.starts_with(arrow))
+        || rest.starts_with(locale.decimal)
+            && rest[locale.decimal.len_utf8()..].starts_with(|c: char| c.is_ascii_digit())
+    {
+        *text = &orig_text[a_len..];
+        return Some(None);
+    }
+    let result = match arrows {
+        1 => tower.into_iter().rev().try_fold(None, |exponent, base| {
+            Some(Some(match exponent {
+                None => Hyper::Exact(base),
+                Some(exponent) => hyper_pow(&base, exponent, consts),
+            }))
+        }),
+        ..=MAX_HYPER_ARROWS => hyperoperation(arrows, &a, &b, consts).map(Some),
+        _ => None,
+    }
+    .flatten();
+    let Some(result) = result else {
+        // Skip all of it, but one char (which is skipped on None)
+        *text = &orig_text[orig_text.len() - rest.len() - 1..];
+        return Some(None);
+    };
+    *text = rest;
+    Some(Some(match result {
+        Hyper::Exact(n) => Number::Exact(n),
+        Hyper::Tower(depth, top) => tower_to_number(depth, top, consts),
+        // The digits have the same bounds
+        Hyper::Arrows(arrows, height) => Number::ApproximateDigitsHyper(false, arrows, height),
+    }))
+}
+
+/// Strips arrows (or carets) and returns how many there were
+fn strip_hyper_arrows(text: &mut &str) -> usize {
+    let mut arrows = 0;
+    while let Some(rest) = HYPER_ARROWS
+        .iter()
+        .find_map(|arrow| text.strip_prefix(arrow))
+    {
+        *text = rest;
+        arrows += 1;
+    }
+    arrows
+}
+
+/// a↑...↑b with the given number of arrows (one is a power, two tetration, ...).
+/// None if it is not defined.
+fn hyperoperation(arrows: usize, a: &Integer, b: &Integer, consts: &Consts) -> Option<Hyper> {
+    if *a == 1 {
+        return Some(Hyper::Exact(1.into()));
+    }
+    match arrows {
+        1 => Some(hyper_pow(a, Hyper::Exact(b.clone()), consts)),
+        2 => Some(tetration(a, b, consts)),
+        _ => {
+            if *b == 0 {
+                return Some(Hyper::Exact(1.into()));
+            }
+            // 0↑↑↑b is not defined consistently
+            if *a == 0 {
+                return None;
+            }
+            let mut result = Hyper::Exact(a.clone());
+            let mut i = Integer::from(1);
+            while i < *b {
+                let Hyper::Exact(height) = &result else {
+                    // From now on, every a only adds one to the height
+                    let height = arrow_height(&result, arrows as u32) + (b - &i).complete();
+                    return Some(Hyper::Arrows(arrows as u32, height));
+                };
+                result = hyperoperation(arrows - 1, a, height, consts)?;
+                i += 1;
+            }
+            Some(result)
+        }
+    }
+}
+
+/// The height h of x with the given number of arrows (at least three, and at least those of x),
+/// so that 10↑...↑h <= x < 10↑...↑(h+1).
+///
+/// As 10↑...↑2 is 10↑...↑10 with one arrow less, anything with two arrows less is at most height 1.
+fn arrow_height(x: &Hyper, arrows: u32) -> Integer {
+    let (x_arrows, height) = match x {
+        Hyper::Exact(n) => return Integer::from(u8::from(*n >= 10)),
+        Hyper::Tower(depth, top) => (2, crate::math::tetration_height(depth.clone(), top.clone())),
+        Hyper::Arrows(x_arrows, height) => (*x_arrows, height.clone()),
+    };
+    if x_arrows == arrows {
+        height
+    } else if x_arrows + 1 == arrows {
+        // 10↑...↑height is between 10↑...↑1 and 10↑...↑2 with one more arrow, if height < 10
+        Integer::from(1 + u8::from(height >= 10))
+    } else {
+        Integer::ONE.clone()
+    }
+}
+
+/// a↑↑height, a^a^...^a with height a's
+fn tetration(a: &Integer, height: &Integer, consts: &Consts) -> Hyper {
+    // 1, 0, 1, 0, ...
+    if *a == 0 {
+        return Hyper::Exact(if height.is_even() { 1 } else { 0 }.into());
+    }
+    let mut result = Hyper::Exact(1.into());
+    let mut i = Integer::ZERO;
+    while i < *height {
+        result = hyper_pow(a, result, consts);
+        i += 1;
+        // From now on, every a only adds one ten
+        if let Hyper::Tower(depth, _) = &mut result
+            && *depth >= 3
+        {
+            *depth += (height - &i).complete();
+            break;
+        }
+    }
+    result
+}
+
+/// base^exponent, exact if it isn't too long
+fn hyper_pow(base: &Integer, exponent: Hyper, consts: &Consts) -> Hyper {
+    if *base <= 1 {
+        return Hyper::Exact(
+            if *base == 1 || matches!(&exponent, Hyper::Exact(e) if *e == 0) {
+                1.into()
+            } else {
+                0.into()
+            },
+        );
+    }
+    let (depth, top) = match exponent {
+        Hyper::Exact(exponent) => {
+            let digits = Float::with_val(consts.float_precision, base).log10() * &exponent;
+            if digits <= consts.integer_construction_limit
+                && let Some(exponent) = exponent.to_u32()
+            {
+                return Hyper::Exact(base.clone().pow(exponent));
+            }
+            (
+                Integer::ZERO,
+                Float::with_val(consts.float_precision, exponent),
+            )
+        }
+        Hyper::Tower(depth, top) => (depth, top),
+        // Far too large for the base to matter
+        Hyper::Arrows(arrows, height) => return Hyper::Arrows(arrows, height),
+    };
+    let (depth, top) = crate::math::tower_pow(base, depth, top);
+    Hyper::Tower(depth, top)
+}
+
+/// Turns 10^10^...^top (depth tens) into the most accurate number
+fn tower_to_number(depth: Integer, mut top: Float, consts: &Consts) -> Number {
+    // The bits (of the float) needed for the digits we show
+    let shown_bits =
+        (consts.number_decimals_scientific as f64 * std::f64::consts::LOG2_10) as i32 + GUARD_BITS;
+    // The mantissa needs the bits after the decimal point
+    if depth == 1 && top.get_exp().unwrap_or(0) < consts.float_precision as i32 - shown_bits {
+        let (exponent, _) = top
+            .clone()
+            .floor()
+            .to_integer_round(crate::rug::float::Round::Down)
+            .expect("Top is finite");
+        let mantissa = Float::with_val(consts.float_precision, 10).pow(top - &exponent);
+        return Number::Approximate(mantissa.into(), exponent);
+    }
+    // The tops we can still take 10^ of, without losing the digits we show
+    let max_top = (consts.float_precision as i32 - shown_bits) as f64 * std::f64::consts::LOG10_2;
+    // Towers of digits
+    let mut depth = depth - 1;
+    if depth > consts.upper_tower_depth_limit {
+        let height = crate::math::tetration_height(depth, top);
+        return Number::ApproximateDigitsHyper(false, 2, height);
+    }
+    while depth > 0 && top < max_top {
+        top = Float::with_val(consts.float_precision, 10).pow(top);
+        depth -= 1;
+    }
+    let (digits, _) = top
+        .floor()
+        .to_integer_round(crate::rug::float::Round::Down)
+        .expect("Top is finite");
+    if depth == 0 {
+        Number::ApproximateDigits(false, digits + 1)
+    } else {
+        Number::ApproximateDigitsTower(false, false, depth, digits)
+    }
+}
+
+fn parse_const(text: &mut &str, had_text: bool, prec: u32) -> Option<Number> {
+    let (n, x) = if text.starts_with("pi") {
+        ("pi".len(), PI(prec))
+    } else if text.starts_with("π") {
+        ("π".len(), PI(prec))
+    } else if text.starts_with("phi") {
+        ("phi".len(), PHI(prec))
+    } else if text.starts_with("ɸ") {
+        ("ɸ".len(), PHI(prec))
+    } else if text.starts_with("tau") {
+        ("tau".len(), TAU(prec))
+    } else if text.starts_with("τ") {
+        ("τ".len(), TAU(prec))
+    } else if text.starts_with("e") {
+        ("e".len(), E(prec))
+    } else if text.starts_with("infinity") {
+        ("infinity".len(), Number::ComplexInfinity)
+    } else if text.starts_with("inf") {
+        ("inf".len(), Number::ComplexInfinity)
+    } else if text.starts_with("∞\u{303}") {
+        ("∞\u{303}".len(), Number::ComplexInfinity)
+    } else if text.starts_with("∞") {
+        ("∞".len(), Number::ComplexInfinity)
+    } else {
+        return None;
+    };
+    if had_text || text[n..].starts_with(char::is_alphabetic) {
+        return None;
+    }
+    *text = &text[n..];
+    Some(x)
+}
+
+fn parse_tower(
+    text: &mut &str,
+    had_op: bool,
     consts: &Consts<'_>,
     locale: &NumFormat,
     prec: u32,
@@ -706,7 +1969,7 @@ fn parse_tet(text: &mut &str, locale: &NumFormat) -> Option<Number> {
         && !text[3..].starts_with(POSTFIX_OPS)
         && !text[3..].starts_with(char::is_numeric)
         // Intentionally not allowing decimal
-        && !(text[3..].starts_with(SEPARATORS) && text[4..].starts_with(char::is_numeric))
+        && (!text[3..].starts_with(SEPARATORS) || !text[4..].starts_with(char::is_numeric))
     {
         *text = &text[3..];
         let part = part.replace(SEPARATORS, "");
@@ -756,8 +2019,8 @@ fn parse_num_simple(
     if divisor == 0 {
         return Some(Number::ComplexInfinity);
     }
-    let integer_part = integer_part.replace(SEPARATORS, "");
-    let decimal_part = decimal_part.replace(SEPARATORS, "");
+    let integer_part = remove_separators(integer_part);
+    let decimal_part = remove_separators(decimal_part);
     parse_final_number(
         consts,
         prec,
@@ -864,8 +2127,12 @@ fn get_parts<'a>(
 }
 
 fn parse_integer(part: &str) -> Option<Integer> {
-    let part = part.replace(SEPARATORS, "");
-    part.parse::<Integer>().ok()
+    remove_separators(part).parse::<Integer>().ok()
+}
+
+/// Removes the separators (including the locale's group separator) from a string of digits
+fn remove_separators(part: &str) -> String {
+    part.replace(|c: char| !c.is_numeric(), "")
 }
 
 fn get_tower_str<'a>(
@@ -927,9 +2194,16 @@ fn get_exponent_str<'a>(text: &mut &'a str, locale: &NumFormat) -> (&'a str, boo
 }
 
 fn get_integer_str<'a>(text: &mut &'a str, locale: &NumFormat) -> &'a str {
+    let group = locale.group_separator();
     let end = text
-        .find(|c: char| (!c.is_numeric() && !SEPARATORS.contains(&c)) || c == locale.decimal)
-        .unwrap_or(text.len());
+        .char_indices()
+        .find(|(i, c)| {
+            // The group separator only counts between digits, as it might be a space
+            let is_group =
+                Some(*c) == group && text[i + c.len_utf8()..].starts_with(|c: char| c.is_numeric());
+            (!c.is_numeric() && !SEPARATORS.contains(c) && !is_group) || *c == locale.decimal
+        })
+        .map_or(text.len(), |(i, _)| i);
     let part = &text[..end];
     *text = &text[end..];
     part
@@ -950,25 +2224,21 @@ mod test {
             "just some words of encouragement!",
             true,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert_eq!(jobs, []);
     }
     #[test]
     fn test_factorial() {
         let consts = Consts::default();
-        let jobs = parse(
-            "a factorial 15!",
-            true,
-            &consts,
-            &NumFormat { decimal: '.' },
-        );
+        let jobs = parse("a factorial 15!", true, &consts, &NumFormat::default());
         assert_eq!(
             jobs,
             [CalculationJob {
                 base: CalculationBase::Num(15.into()),
-                level: 1,
-                negative: 0
+                operation: Operation::Factorial(1),
+                negative: 0,
+                span: Span::new(12, 15),
             }]
         );
     }
@@ -979,14 +2249,15 @@ mod test {
             "a factorial 15!!! actually a multi",
             true,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert_eq!(
             jobs,
             [CalculationJob {
                 base: CalculationBase::Num(15.into()),
-                level: 3,
-                negative: 0
+                operation: Operation::Factorial(3),
+                negative: 0,
+                span: Span::new(12, 17),
             }]
         );
     }
@@ -997,14 +2268,15 @@ mod test {
             "a factorial !15 actually a sub",
             true,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert_eq!(
             jobs,
             [CalculationJob {
                 base: CalculationBase::Num(15.into()),
-                level: 0,
-                negative: 0
+                operation: Operation::Factorial(0),
+                negative: 0,
+                span: Span::new(12, 15),
             }]
         );
     }
@@ -1015,32 +2287,28 @@ mod test {
             "not well defined !!!15",
             true,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert_eq!(jobs, []);
     }
     #[test]
     fn test_termial() {
         let consts = Consts::default();
-        let jobs = parse("a termial 15?", true, &consts, &NumFormat { decimal: '.' });
+        let jobs = parse("a termial 15?", true, &consts, &NumFormat::default());
         assert_eq!(
             jobs,
             [CalculationJob {
                 base: CalculationBase::Num(15.into()),
-                level: -1,
-                negative: 0
+                operation: Operation::Factorial(-1),
+                negative: 0,
+                span: Span::new(10, 13),
             }]
         );
     }
     #[test]
     fn test_no_termial() {
         let consts = Consts::default();
-        let jobs = parse(
-            "not enabled 15?",
-            false,
-            &consts,
-            &NumFormat { decimal: '.' },
-        );
+        let jobs = parse("not enabled 15?", false, &consts, &NumFormat::default());
         assert_eq!(jobs, []);
     }
     #[test]
@@ -1050,14 +2318,15 @@ mod test {
             "a termial 15??? actually a multi",
             true,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert_eq!(
             jobs,
             [CalculationJob {
                 base: CalculationBase::Num(15.into()),
-                level: -3,
-                negative: 0
+                operation: Operation::Factorial(-3),
+                negative: 0,
+                span: Span::new(10, 15),
             }]
         );
     }
@@ -1068,181 +2337,969 @@ mod test {
             "a termial ?15 actually a sub",
             true,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert_eq!(jobs, []);
     }
     #[test]
-    fn test_chain() {
+    fn test_exponential_factorial() {
         let consts = Consts::default();
         let jobs = parse(
-            "a factorialchain (15!)!",
+            "an exponential factorial 5$ and (4$)$",
             true,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert_eq!(
             jobs,
-            [CalculationJob {
-                base: CalculationBase::Calc(Box::new(CalculationJob {
-                    base: CalculationBase::Num(15.into()),
-                    level: 1,
-                    negative: 0
-                })),
-                level: 1,
-                negative: 0
-            }]
+            [
+                CalculationJob {
+                    base: CalculationBase::Num(5.into()),
+                    operation: Operation::ExponentialFactorial,
+                    negative: 0,
+                    span: Span::new(25, 27),
+                },
+                CalculationJob {
+                    base: CalculationBase::Calc(Box::new(CalculationJob {
+                        base: CalculationBase::Num(4.into()),
+                        operation: Operation::ExponentialFactorial,
+                        negative: 0,
+                        span: Span::new(33, 35),
+                    })),
+                    operation: Operation::ExponentialFactorial,
+                    negative: 0,
+                    span: Span::new(32, 37),
+                }
+            ]
         );
     }
     #[test]
-    fn test_mixed_chain() {
+    fn test_no_exponential_factorial() {
         let consts = Consts::default();
         let jobs = parse(
-            "a factorialchain !(15!)",
+            "it only costs 5$, not 5$$ or 1.5$",
+            false,
+            &consts,
+            &NumFormat::default(),
+        );
+        assert_eq!(jobs, []);
+        let jobs = parse("not 5$$ or 1.5$", true, &consts, &NumFormat::default());
+        assert_eq!(jobs, []);
+    }
+    #[test]
+    fn test_compositorial() {
+        let consts = Consts::default();
+        let jobs = parse(
+            "a compositorial 10!/10# but not 10!/11# or 1.5!/1.5#",
             true,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert_eq!(
             jobs,
-            [CalculationJob {
-                base: CalculationBase::Calc(Box::new(CalculationJob {
-                    base: CalculationBase::Num(15.into()),
-                    level: 1,
-                    negative: 0
-                })),
-                level: 0,
-                negative: 0
-            }]
+            [
+                CalculationJob {
+                    base: CalculationBase::Num(10.into()),
+                    operation: Operation::Factorial(1),
+                    negative: 0,
+                    span: Span::new(32, 35),
+                },
+                CalculationJob {
+                    base: CalculationBase::Num(10.into()),
+                    operation: Operation::Compositorial,
+                    negative: 0,
+                    span: Span::new(16, 23),
+                },
+            ]
         );
     }
     #[test]
-    fn test_postfix_chain() {
+    fn test_rising_factorial() {
         let consts = Consts::default();
         let jobs = parse(
-            "a factorialchain -15!?",
+            "rising 5^(3) and -1.5↑2 and 5\\^(3)! but not ^(3)10",
             true,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
+        );
+        assert_eq!(
+            jobs,
+            [
+                CalculationJob {
+                    base: CalculationBase::Num(5.into()),
+                    operation: Operation::RisingFactorial(3.into()),
+                    negative: 0,
+                    span: Span::new(7, 12),
+                },
+                CalculationJob {
+                    base: CalculationBase::Num(Number::Float(
+                        Float::with_val(FLOAT_PRECISION, 1.5).into()
+                    )),
+                    operation: Operation::RisingFactorial(2.into()),
+                    negative: 1,
+                    span: Span::new(18, 25),
+                },
+                CalculationJob {
+                    base: CalculationBase::Calc(Box::new(CalculationJob {
+                        base: CalculationBase::Num(5.into()),
+                        operation: Operation::RisingFactorial(3.into()),
+                        negative: 0,
+                        span: Span::new(30, 36),
+                    })),
+                    operation: Operation::Factorial(1),
+                    negative: 0,
+                    span: Span::new(30, 37),
+                },
+            ]
+        );
+    }
+    #[test]
+    fn test_no_rising_factorial() {
+        let consts = Consts::default();
+        let jobs = parse(
+            "powers 5^(3) and 2^(10)",
+            false,
+            &consts,
+            &NumFormat::default(),
+        );
+        assert_eq!(jobs, []);
+        let jobs = parse(
+            "In python x_1 = (5)_2 and more, or (5)\\_2 and (5)_(3)",
+            false,
+            &consts,
+            &NumFormat::default(),
+        );
+        assert_eq!(jobs, []);
+    }
+    #[test]
+    fn test_falling_factorial() {
+        let consts = Consts::default();
+        let jobs = parse(
+            "falling (5)_3 and -(0.5)\\_2 and (5)_3! but not (a)_3 or (5)_a",
+            true,
+            &consts,
+            &NumFormat::default(),
+        );
+        assert_eq!(
+            jobs,
+            [
+                CalculationJob {
+                    base: CalculationBase::Num(5.into()),
+                    operation: Operation::FallingFactorial(3.into()),
+                    negative: 0,
+                    span: Span::new(8, 13),
+                },
+                CalculationJob {
+                    base: CalculationBase::Num(Number::Float(
+                        Float::with_val(FLOAT_PRECISION, 0.5).into()
+                    )),
+                    operation: Operation::FallingFactorial(2.into()),
+                    negative: 1,
+                    span: Span::new(19, 27),
+                },
+                CalculationJob {
+                    base: CalculationBase::Calc(Box::new(CalculationJob {
+                        base: CalculationBase::Num(5.into()),
+                        operation: Operation::FallingFactorial(3.into()),
+                        negative: 0,
+                        span: Span::new(32, 37),
+                    })),
+                    operation: Operation::Factorial(1),
+                    negative: 0,
+                    span: Span::new(32, 38),
+                },
+            ]
+        );
+    }
+    #[test]
+    fn test_factorial_modulo() {
+        let consts = Consts::default();
+        let jobs = parse(
+            "100000000000! mod 1000000007 and 5! % 7 and 6!\\%8 and -9! mod 4, but not 5!! mod 3 or 4.5! mod 3 or 7! mod 2.5 or 3! mod 0 or 8! modest",
+            true,
+            &consts,
+            &NumFormat::default(),
+        );
+        assert_eq!(
+            jobs,
+            [
+                CalculationJob {
+                    base: CalculationBase::Num(3.into()),
+                    operation: Operation::Factorial(1),
+                    negative: 0,
+                    span: Span::new(114, 116),
+                },
+                CalculationJob {
+                    base: CalculationBase::Num(5.into()),
+                    operation: Operation::Factorial(2),
+                    negative: 0,
+                    span: Span::new(73, 76),
+                },
+                CalculationJob {
+                    base: CalculationBase::Num(5.into()),
+                    operation: Operation::FactorialModulo(7.into()),
+                    negative: 0,
+                    span: Span::new(33, 39),
+                },
+                CalculationJob {
+                    base: CalculationBase::Num(6.into()),
+                    operation: Operation::FactorialModulo(8.into()),
+                    negative: 0,
+                    span: Span::new(44, 49),
+                },
+                CalculationJob {
+                    base: CalculationBase::Num(7.into()),
+                    operation: Operation::Factorial(1),
+                    negative: 0,
+                    span: Span::new(100, 102),
+                },
+                CalculationJob {
+                    base: CalculationBase::Num(8.into()),
+                    operation: Operation::Factorial(1),
+                    negative: 0,
+                    span: Span::new(126, 128),
+                },
+                CalculationJob {
+                    base: CalculationBase::Num(9.into()),
+                    operation: Operation::FactorialModulo(4.into()),
+                    negative: 1,
+                    span: Span::new(55, 63),
+                },
+                CalculationJob {
+                    base: CalculationBase::Num(Integer::from(100000000000u64).into()),
+                    operation: Operation::FactorialModulo(1000000007.into()),
+                    negative: 0,
+                    span: Span::new(0, 28),
+                },
+                CalculationJob {
+                    base: CalculationBase::Num(Float::with_val(FLOAT_PRECISION, 4.5).into()),
+                    operation: Operation::Factorial(1),
+                    negative: 0,
+                    span: Span::new(86, 90),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nested_factorial_modulo() {
+        let consts = Consts::default();
+        let jobs = parse(
+            "(4!)! mod 7 and (3)! % 4, but not 5! mod 7! or (5! mod 7)! or 6! mod (2)",
+            true,
+            &consts,
+            &NumFormat::default(),
+        );
+        assert_eq!(
+            jobs,
+            [
+                CalculationJob {
+                    base: CalculationBase::Num(3.into()),
+                    operation: Operation::FactorialModulo(4.into()),
+                    negative: 0,
+                    span: Span::new(16, 24),
+                },
+                CalculationJob {
+                    base: CalculationBase::Calc(Box::new(CalculationJob {
+                        base: CalculationBase::Num(4.into()),
+                        operation: Operation::Factorial(1),
+                        negative: 0,
+                        span: Span::new(1, 3),
+                    })),
+                    operation: Operation::FactorialModulo(7.into()),
+                    negative: 0,
+                    span: Span::new(0, 11),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_combinatorial_functions() {
+        let consts = Consts::default();
+        let parse = |text: &str, functions: bool| {
+            parse_with_diagnostics(
+                text,
+                true,
+                None,
+                false,
+                false,
+                functions,
+                &consts,
+                &NumFormat::default(),
+            )
+            .0
+        };
+        let jobs = parse(
+            "C(5) and S(10, 3) and s(5,2) and -D(5, 2) and D(5) and B(3)! but not items(3, 2) or S(5) or C(5, 2) or B(1.5) or C(a)",
+            true,
+        );
+        assert_eq!(
+            jobs,
+            [
+                CalculationJob {
+                    base: CalculationBase::Num(5.into()),
+                    operation: Operation::Factorial(0),
+                    negative: 0,
+                    span: Span::new(46, 50),
+                },
+                CalculationJob {
+                    base: CalculationBase::Num(5.into()),
+                    operation: Operation::Rencontres(2.into()),
+                    negative: 1,
+                    span: Span::new(34, 41),
+                },
+                CalculationJob {
+                    base: CalculationBase::Num(5.into()),
+                    operation: Operation::StirlingFirst(2.into()),
+                    negative: 0,
+                    span: Span::new(22, 28),
+                },
+                CalculationJob {
+                    base: CalculationBase::Num(5.into()),
+                    operation: Operation::Catalan,
+                    negative: 0,
+                    span: Span::new(0, 4),
+                },
+                CalculationJob {
+                    base: CalculationBase::Num(10.into()),
+                    operation: Operation::StirlingSecond(3.into()),
+                    negative: 0,
+                    span: Span::new(9, 17),
+                },
+                CalculationJob {
+                    base: CalculationBase::Calc(Box::new(CalculationJob {
+                        base: CalculationBase::Num(3.into()),
+                        operation: Operation::Bell,
+                        negative: 0,
+                        span: Span::new(55, 59),
+                    })),
+                    operation: Operation::Factorial(1),
+                    negative: 0,
+                    span: Span::new(55, 60),
+                },
+            ]
+        );
+        // Prose, that happens to look like a function
+        for text in [
+            "See section B(2) of the contract.",
+            "Take vitamin D(3) daily!",
+            "Plan C(1) is better!",
+            "Grade B(12) students!",
+            "My rating: S(5, 4)",
+        ] {
+            assert_eq!(parse(text, false), [], "{text}");
+        }
+        assert_eq!(
+            parse("Check article D(4)!", false),
+            [CalculationJob {
+                base: CalculationBase::Num(4.into()),
+                operation: Operation::Factorial(1),
+                negative: 0,
+                span: Span::new(15, 19),
+            }]
+        );
+    }
+    #[test]
+    fn test_nested_function_arguments() {
+        let consts = Consts::default();
+        let (jobs, _) = parse_with_diagnostics(
+            "C(3!) and (3!)_2 but not S(3!, 2) or s(5, 3!) or (5)_(3!) or C(-3)",
+            true,
+            None,
+            false,
+            false,
+            true,
+            &consts,
+            &NumFormat::default(),
+        );
+        let factorial = |start| {
+            CalculationBase::Calc(Box::new(CalculationJob {
+                base: CalculationBase::Num(3.into()),
+                operation: Operation::Factorial(1),
+                negative: 0,
+                span: Span::new(start, start + 2),
+            }))
+        };
+        assert_eq!(
+            jobs,
+            [
+                CalculationJob {
+                    base: factorial(2),
+                    operation: Operation::Catalan,
+                    negative: 0,
+                    span: Span::new(0, 5),
+                },
+                CalculationJob {
+                    base: factorial(11),
+                    operation: Operation::FallingFactorial(2.into()),
+                    negative: 0,
+                    span: Span::new(10, 16),
+                },
+            ]
+        );
+    }
+    #[test]
+    fn test_bare_numbers() {
+        let consts = Consts::default();
+        let jobs = parse_with_bare_numbers(
+            "145 and -40585 and 5! but not 1.5 or (169)",
+            false,
+            Some(Operation::DigitFactorialSum),
+            &consts,
+            &NumFormat::default(),
+        );
+        assert_eq!(
+            jobs,
+            [
+                CalculationJob {
+                    base: CalculationBase::Num(5.into()),
+                    operation: Operation::Factorial(1),
+                    negative: 0,
+                    span: Span::new(19, 21),
+                },
+                CalculationJob {
+                    base: CalculationBase::Num(145.into()),
+                    operation: Operation::DigitFactorialSum,
+                    negative: 0,
+                    span: Span::new(0, 3),
+                },
+                CalculationJob {
+                    base: CalculationBase::Num(40585.into()),
+                    operation: Operation::DigitFactorialSum,
+                    negative: 1,
+                    span: Span::new(9, 14),
+                },
+            ]
+        );
+        let jobs = parse("145 and 40585", false, &consts, &NumFormat::default());
+        assert_eq!(jobs, []);
+    }
+    #[test]
+    fn test_chain() {
+        let consts = Consts::default();
+        let jobs = parse(
+            "a factorialchain (15!)!",
+            true,
+            &consts,
+            &NumFormat::default(),
+        );
+        assert_eq!(
+            jobs,
+            [CalculationJob {
+                base: CalculationBase::Calc(Box::new(CalculationJob {
+                    base: CalculationBase::Num(15.into()),
+                    operation: Operation::Factorial(1),
+                    negative: 0,
+                    span: Span::new(18, 21),
+                })),
+                operation: Operation::Factorial(1),
+                negative: 0,
+                span: Span::new(17, 23),
+            }]
+        );
+    }
+    #[test]
+    fn test_mixed_chain() {
+        let consts = Consts::default();
+        let jobs = parse(
+            "a factorialchain !(15!)",
+            true,
+            &consts,
+            &NumFormat::default(),
+        );
+        assert_eq!(
+            jobs,
+            [CalculationJob {
+                base: CalculationBase::Calc(Box::new(CalculationJob {
+                    base: CalculationBase::Num(15.into()),
+                    operation: Operation::Factorial(1),
+                    negative: 0,
+                    span: Span::new(19, 22),
+                })),
+                operation: Operation::Factorial(0),
+                negative: 0,
+                span: Span::new(17, 23),
+            }]
+        );
+    }
+    #[test]
+    fn test_postfix_chain() {
+        let consts = Consts::default();
+        let jobs = parse(
+            "a factorialchain -15!?",
+            true,
+            &consts,
+            &NumFormat::default(),
+        );
+        assert_eq!(
+            jobs,
+            [CalculationJob {
+                base: CalculationBase::Calc(Box::new(CalculationJob {
+                    base: CalculationBase::Num(15.into()),
+                    operation: Operation::Factorial(1),
+                    negative: 0,
+                    span: Span::new(18, 21),
+                })),
+                operation: Operation::Factorial(-1),
+                negative: 1,
+                span: Span::new(18, 22),
+            }]
+        );
+    }
+    #[test]
+    fn test_negative() {
+        let consts = Consts::default();
+        let jobs = parse("a factorial ---15!", true, &consts, &NumFormat::default());
+        assert_eq!(
+            jobs,
+            [CalculationJob {
+                base: CalculationBase::Num(15.into()),
+                operation: Operation::Factorial(1),
+                negative: 3,
+                span: Span::new(15, 18),
+            }]
+        );
+    }
+    #[test]
+    fn test_negative_gap() {
+        let consts = Consts::default();
+        let jobs = parse("a factorial --- 15!", true, &consts, &NumFormat::default());
+        assert_eq!(
+            jobs,
+            [CalculationJob {
+                base: CalculationBase::Num(15.into()),
+                operation: Operation::Factorial(1),
+                negative: 0,
+                span: Span::new(16, 19),
+            }]
+        );
+    }
+    #[test]
+    fn test_paren() {
+        let consts = Consts::default();
+        let jobs = parse("a factorial (15)!", true, &consts, &NumFormat::default());
+        assert_eq!(
+            jobs,
+            [CalculationJob {
+                base: CalculationBase::Num(15.into()),
+                operation: Operation::Factorial(1),
+                negative: 0,
+                span: Span::new(12, 17),
+            }]
+        );
+    }
+    #[test]
+    fn test_in_paren() {
+        let consts = Consts::default();
+        let jobs = parse("a factorial (15!)", true, &consts, &NumFormat::default());
+        assert_eq!(
+            jobs,
+            [CalculationJob {
+                base: CalculationBase::Num(15.into()),
+                operation: Operation::Factorial(1),
+                negative: 0,
+                span: Span::new(13, 16),
+            }]
+        );
+    }
+    #[test]
+    fn test_spans() {
+        let consts = Consts::default();
+        // The snippets of a job and its nested steps, outermost first
+        let snippets = |text: &'static str| {
+            parse_with_diagnostics(
+                text,
+                true,
+                None,
+                false,
+                false,
+                true,
+                &consts,
+                &NumFormat::default(),
+            )
+            .0
+            .into_iter()
+            .map(|job| {
+                let mut snippets = vec![job.span.slice(text).unwrap()];
+                let mut base = &job.base;
+                while let CalculationBase::Calc(inner) = base {
+                    snippets.push(inner.span.slice(text).unwrap());
+                    base = &inner.base;
+                }
+                snippets
+            })
+            .collect::<Vec<_>>()
+        };
+        assert_eq!(snippets("so (3!)! in here"), [["(3!)!", "3!"]]);
+        assert_eq!(snippets("a !(5!) b"), [["!(5!)", "5!"]]);
+        assert_eq!(snippets("it's 5!!? now"), [["5!!?", "5!!"]]);
+        assert_eq!(snippets("-!4!."), [["!4!", "!4"]]);
+        assert_eq!(snippets("ok 7! mod 5"), [["7! mod 5"]]);
+        assert_eq!(snippets("then C(5)!"), [["C(5)!", "C(5)"]]);
+        assert_eq!(snippets("a (5)_3 b"), [["(5)_3"]]);
+        assert_eq!(snippets("ü 3! and ö 4!"), [["3!"], ["4!"]]);
+    }
+    // Code, that should not be calculated with the code filter
+    const CODE_CORPUS: &[&str] = &[
+        "if (5!=x) {",
+        "if (5 != x && 3!==y) return;",
+        "return !0;",
+        "return !1",
+        "x = !1;",
+        "const debug = !0, verbose = !1;",
+        "if (ready && !0) {",
+        "flag = cond ? !0 : !1;",
+        "int x = fact(5)!;",
+        "y = factorial(4)! + 1",
+        "echo $((5!))",
+        "for (int i = 0; i != 10; i++) { sum += 3!; }",
+        "def f(n):\n    return n * f(n - 1)\nprint(f(5)!)",
+        "#define TRUE !0\nint x = TRUE;",
+    ];
+    // Code, that is not even parsed as a calculation
+    const UNPARSED_CODE_CORPUS: &[&str] = &[
+        "a != b",
+        "!isValid",
+        "x!= 0",
+        "foo()!",
+        "n!",
+        "if (x != 5) {",
+        "while (i!=0) i--;",
+        "let y = x!;",
+        "let v = arr[3]!",
+        "let v = list[0]!.name",
+        "val x = map[\"a\"]!!",
+        "if (a == b && !c)",
+        "assert!(x == 5);",
+        "println!(\"{}\", 5);",
+        "let s = format!(\"{x}\");",
+        "vec![1, 2, 3]!",
+        "!(a || b)",
+        "return n * fact(n - 1)!",
+        "func()!.value",
+        "let a = b!",
+        "if (count! = 0)",
+    ];
+    // Math, that should still be calculated with the code filter
+    const MATH_CORPUS: &[&str] = &[
+        "5!",
+        "What is 10!?",
+        "5! is 120",
+        "I think (3!)! is big",
+        "!5 is the subfactorial",
+        "3!! and 4!!!",
+        "the answer is 6! = 720",
+        "x = 3!; y = 4!",
+        "C(5)!",
+        "so 5!, 6! and 7!.",
+        "I'd say 4! > 3!",
+        "5!=120",
+        "3!=6 and 4!=24",
+    ];
+
+    #[test]
+    fn test_code_filter() {
+        let consts = Consts::default();
+        let parse = |text: &str, filter_code: bool| {
+            parse_with_diagnostics(
+                text,
+                true,
+                None,
+                filter_code,
+                false,
+                true,
+                &consts,
+                &NumFormat::default(),
+            )
+            .0
+        };
+        for code in CODE_CORPUS {
+            assert!(!parse(code, false).is_empty(), "{code}");
+            assert_eq!(parse(code, true), [], "{code}");
+        }
+        for code in UNPARSED_CODE_CORPUS {
+            assert_eq!(parse(code, false), [], "{code}");
+        }
+        for math in MATH_CORPUS {
+            let jobs = parse(math, false);
+            assert!(!jobs.is_empty(), "{math}");
+            assert_eq!(parse(math, true), jobs, "{math}");
+        }
+        let (_, diagnostics) = parse_with_diagnostics(
+            "if (5!=x) {",
+            true,
+            None,
+            true,
+            false,
+            false,
+            &consts,
+            &NumFormat::default(),
+        );
+        assert_eq!(diagnostics[0].kind, DiagnosticKind::LooksLikeCode);
+        assert_eq!(diagnostics[0].text, "5!");
+    }
+
+    #[test]
+    fn test_other_digits() {
+        let consts = Consts::default();
+        let parse = |text: &str| parse(text, true, &consts, &NumFormat::default());
+        // Spans are in bytes of the original text
+        let factorial = |n: u32, end: usize| CalculationJob {
+            base: CalculationBase::Num(Number::Exact(n.into())),
+            operation: Operation::Factorial(1),
+            negative: 0,
+            span: Span::new(0, end),
+        };
+        assert_eq!(parse("５！"), [factorial(5, 6)]);
+        assert_eq!(parse("１２!"), [factorial(12, 7)]);
+        assert_eq!(parse("٥!"), [factorial(5, 3)]);
+        assert_eq!(parse("۱۰!"), [factorial(10, 5)]);
+        assert_eq!(parse("१२!"), [factorial(12, 7)]);
+        assert_eq!(parse("（３！）！")[0].operation, Operation::Factorial(1));
+        assert_eq!(parse("ｎ！"), []);
+        assert_eq!(normalize_digits("५！ und ٣?"), "5! und 3?");
+        assert!(matches!(normalize_digits("5! only"), Cow::Borrowed(_)));
+        assert_eq!(normalize_digits("٣٫٥! و ١٬٠٠٠!"), "3.5! و 1_000!");
+        assert_eq!(parse("١٬٠٠٠!"), [factorial(1000, 11)]);
+        let decimal = parse("٣٫٥!");
+        assert_eq!(decimal[0].span, Span::new(0, 7));
+        assert_eq!(decimal[0].base, parse("3.5!")[0].base);
+        // The Arabic decimal separator is the decimal of any locale
+        let de = &consts.locales.get("de").unwrap().format.number_format;
+        assert_eq!(
+            super::parse("٣٫٥!", true, &consts, de)[0].base,
+            decimal[0].base
+        );
+    }
+    #[test]
+    fn test_other_digits_spans() {
+        let consts = Consts::default();
+        let text = "これは５！と（３！）！です";
+        let jobs = parse(text, true, &consts, &NumFormat::default());
+        let snippets = jobs
+            .iter()
+            .map(|job| job.span.slice(text).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(snippets, ["５！", "（３！）！"]);
+        let CalculationBase::Calc(inner) = &jobs[1].base else {
+            panic!("{jobs:?}")
+        };
+        assert_eq!(inner.span.slice(text), Some("３！"));
+        let text = "और १.५$ ";
+        let (_, diagnostics) = parse_with_diagnostics(
+            text,
+            true,
+            None,
+            false,
+            false,
+            false,
+            &consts,
+            &NumFormat::default(),
+        );
+        assert_eq!(diagnostics[0].kind, DiagnosticKind::IntegerOnlyOp);
+        assert_eq!(diagnostics[0].text, "१.५$");
+    }
+
+    #[test]
+    fn test_numerals() {
+        let consts = Consts::default();
+        let parse = |text: &str, numerals: bool| {
+            parse_with_diagnostics(
+                text,
+                true,
+                None,
+                false,
+                numerals,
+                false,
+                &consts,
+                &NumFormat::default(),
+            )
+            .0
+            .into_iter()
+            .map(|job| match job.base {
+                CalculationBase::Num(Number::Exact(n)) => (n, job.operation),
+                base => panic!("{base:?}"),
+            })
+            .collect::<Vec<_>>()
+        };
+        let n = |n: u32, level: i32| vec![(Integer::from(n), Operation::Factorial(level))];
+        assert_eq!(parse("XII!", false), []);
+        assert_eq!(parse("XII!", true), n(12, 1));
+        assert_eq!(parse("what is MMXXV!! then", true), n(2025, 2));
+        assert_eq!(parse("then IV?", true), n(4, -1));
+        assert_eq!(parse("十!", true), n(10, 1));
+        assert_eq!(parse("これは二十五!です", true), n(25, 1));
+        assert_eq!(parse("一百零五!", true), n(105, 1));
+        assert_eq!(parse("三千二百!", true), n(3200, 1));
+        assert_eq!(parse("二〇二五!", true), n(2025, 1));
+        assert_eq!(parse("一万二千!", true), n(12000, 1));
+        assert_eq!(parse("十！", true), n(10, 1));
+        // Not directly followed by an op, or part of a word
+        assert_eq!(parse("XII !", true), []);
+        assert_eq!(parse("MAX!", true), []);
+        assert_eq!(parse("AXII!", true), []);
+        assert_eq!(parse("mix!", true), []);
+        assert_eq!(parse("十分", true), []);
+        // Not canonical
+        assert_eq!(parse("IIII!", true), []);
+        assert_eq!(parse("IC!", true), []);
+        assert_eq!(parse("CIVIL!", true), []);
+        assert_eq!(parse("VVVX!", true), []);
+        assert_eq!(parse("IIIIIIV!", true), []);
+        assert_eq!(parse(&format!("{}!", "M".repeat(100)), true), []);
+        assert_eq!(parse("MMMM!", true), []);
+        assert_eq!(parse("MMMCMXCIX!", true), n(3999, 1));
+        // Words, that are also numerals
+        assert_eq!(parse("Not I!", true), []);
+        assert_eq!(parse("MIX!", true), []);
+        assert_eq!(parse("Say CD!", true), []);
+        assert_eq!(parse("二三!", true), n(23, 1));
+        assert_eq!(parse("二三十!", true), []);
+        assert!(might_have_numeral("is it XII! or"));
+        assert!(!might_have_numeral("is it 12! or"));
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        let consts = Consts::default();
+        let diagnostics = |text: &str| {
+            parse_with_diagnostics(
+                text,
+                true,
+                None,
+                false,
+                false,
+                false,
+                &consts,
+                &NumFormat::default(),
+            )
+            .1
+            .into_iter()
+            .map(|diagnostic| (diagnostic.kind, diagnostic.text))
+            .collect::<Vec<_>>()
+        };
+        let diagnostic = |kind, text: &str| vec![(kind, text.to_owned())];
+        assert_eq!(diagnostics("5! and (3!)! are fine"), []);
+        assert_eq!(
+            diagnostics("see https://example.com/5! here"),
+            diagnostic(DiagnosticKind::InUri, "https://example.com/5!")
+        );
+        assert_eq!(diagnostics("or https://example.com/abc!"), []);
+        assert_eq!(
+            diagnostics("that's 5\\! only"),
+            diagnostic(DiagnosticKind::Escaped, "5\\!")
+        );
+        assert_eq!(
+            diagnostics("a !1.5 b"),
+            diagnostic(DiagnosticKind::IntegerOnlyOp, "!1.5")
         );
         assert_eq!(
-            jobs,
-            [CalculationJob {
-                base: CalculationBase::Calc(Box::new(CalculationJob {
-                    base: CalculationBase::Num(15.into()),
-                    level: 1,
-                    negative: 0
-                })),
-                level: -1,
-                negative: 1
-            }]
+            diagnostics("and 1.5$ or"),
+            diagnostic(DiagnosticKind::IntegerOnlyOp, "1.5$")
         );
-    }
-    #[test]
-    fn test_negative() {
-        let consts = Consts::default();
-        let jobs = parse(
-            "a factorial ---15!",
-            true,
-            &consts,
-            &NumFormat { decimal: '.' },
+        assert_eq!(
+            diagnostics("a !(2.5) b"),
+            diagnostic(DiagnosticKind::IntegerOnlyOp, "!(2.5)")
         );
         assert_eq!(
-            jobs,
-            [CalculationJob {
-                base: CalculationBase::Num(15.into()),
-                level: 1,
-                negative: 3
-            }]
+            diagnostics("x 1e300000000$ y"),
+            diagnostic(DiagnosticKind::TooBig, "1e300000000$")
+        );
+        assert_eq!(
+            diagnostics("oops 5)! there"),
+            diagnostic(DiagnosticKind::UnbalancedParen, ")!")
+        );
+        assert_eq!(
+            diagnostics("so !(5 now"),
+            diagnostic(DiagnosticKind::UnbalancedParen, "!(5")
         );
     }
     #[test]
-    fn test_negative_gap() {
+    fn test_decimal() {
         let consts = Consts::default();
-        let jobs = parse(
-            "a factorial --- 15!",
-            true,
-            &consts,
-            &NumFormat { decimal: '.' },
-        );
+        let jobs = parse("a factorial 1.5!", true, &consts, &NumFormat::default());
         assert_eq!(
             jobs,
             [CalculationJob {
-                base: CalculationBase::Num(15.into()),
-                level: 1,
-                negative: 0
+                base: CalculationBase::Num(Float::with_val(FLOAT_PRECISION, 1.5).into()),
+                operation: Operation::Factorial(1),
+                negative: 0,
+                span: Span::new(12, 16),
             }]
         );
     }
     #[test]
-    fn test_paren() {
+    fn test_grouped_decimal_comma() {
         let consts = Consts::default();
         let jobs = parse(
-            "a factorial (15)!",
+            "a factorial 1.000.000! and 1.000,5!",
             true,
             &consts,
-            &NumFormat { decimal: '.' },
+            &consts.locales.get("de").unwrap().format.number_format,
         );
         assert_eq!(
             jobs,
-            [CalculationJob {
-                base: CalculationBase::Num(15.into()),
-                level: 1,
-                negative: 0
-            }]
+            [
+                CalculationJob {
+                    base: CalculationBase::Num(1000000.into()),
+                    operation: Operation::Factorial(1),
+                    negative: 0,
+                    span: Span::new(12, 22),
+                },
+                CalculationJob {
+                    base: CalculationBase::Num(Float::with_val(FLOAT_PRECISION, 1000.5).into()),
+                    operation: Operation::Factorial(1),
+                    negative: 0,
+                    span: Span::new(27, 35),
+                }
+            ]
         );
     }
     #[test]
-    fn test_in_paren() {
+    fn test_grouped_space() {
         let consts = Consts::default();
-        let jobs = parse(
-            "a factorial (15!)",
-            true,
-            &consts,
-            &NumFormat { decimal: '.' },
-        );
+        let locale = NumFormat {
+            group: Some(' '),
+            group_sizes: Some(vec![3, 2]),
+            ..Default::default()
+        };
+        let jobs = parse("a factorial 1 00 000! 3 !", true, &consts, &locale);
         assert_eq!(
             jobs,
             [CalculationJob {
-                base: CalculationBase::Num(15.into()),
-                level: 1,
-                negative: 0
+                base: CalculationBase::Num(100000.into()),
+                operation: Operation::Factorial(1),
+                negative: 0,
+                span: Span::new(12, 21),
             }]
         );
-    }
-    #[test]
-    fn test_decimal() {
-        let consts = Consts::default();
-        let jobs = parse(
-            "a factorial 1.5!",
-            true,
-            &consts,
-            &NumFormat { decimal: '.' },
-        );
+        let locale = NumFormat {
+            narrow_no_break_space: Some(true),
+            ..Default::default()
+        };
+        let jobs = parse("a factorial 10\u{202F}000!", true, &consts, &locale);
         assert_eq!(
             jobs,
             [CalculationJob {
-                base: CalculationBase::Num(Float::with_val(FLOAT_PRECISION, 1.5).into()),
-                level: 1,
-                negative: 0
+                base: CalculationBase::Num(10000.into()),
+                operation: Operation::Factorial(1),
+                negative: 0,
+                span: Span::new(12, 21),
             }]
         );
     }
     #[test]
     fn test_negative_decimal() {
         let consts = Consts::default();
-        let jobs = parse(
-            "a factorial (-1.5)!",
-            true,
-            &consts,
-            &NumFormat { decimal: '.' },
-        );
+        let jobs = parse("a factorial (-1.5)!", true, &consts, &NumFormat::default());
         assert_eq!(
             jobs,
             [CalculationJob {
                 base: CalculationBase::Num(Float::with_val(FLOAT_PRECISION, -1.5).into()),
-                level: 1,
-                negative: 0
+                operation: Operation::Factorial(1),
+                negative: 0,
+                span: Span::new(12, 19),
             }]
         );
     }
@@ -1253,18 +3310,20 @@ mod test {
             "a factorial -(--(-(-(-3))!))!",
             true,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert_eq!(
             jobs,
             [CalculationJob {
                 base: CalculationBase::Calc(Box::new(CalculationJob {
                     base: CalculationBase::Num(3.into()),
-                    level: 1,
-                    negative: 3
+                    operation: Operation::Factorial(1),
+                    negative: 3,
+                    span: Span::new(18, 26),
                 })),
-                level: 1,
-                negative: 1
+                operation: Operation::Factorial(1),
+                negative: 1,
+                span: Span::new(13, 29),
             }]
         );
     }
@@ -1275,31 +3334,28 @@ mod test {
             ">!5 a factorial 15! !<",
             true,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert_eq!(jobs, []);
     }
     #[test]
     fn test_incomplete_tag() {
         let consts = Consts::default();
-        let jobs = parse(
-            ">!5 a factorial 15!",
-            true,
-            &consts,
-            &NumFormat { decimal: '.' },
-        );
+        let jobs = parse(">!5 a factorial 15!", true, &consts, &NumFormat::default());
         assert_eq!(
             jobs,
             [
                 CalculationJob {
                     base: CalculationBase::Num(5.into()),
-                    level: 0,
-                    negative: 0
+                    operation: Operation::Factorial(0),
+                    negative: 0,
+                    span: Span::new(1, 3),
                 },
                 CalculationJob {
                     base: CalculationBase::Num(15.into()),
-                    level: 1,
-                    negative: 0
+                    operation: Operation::Factorial(1),
+                    negative: 0,
+                    span: Span::new(16, 19),
                 }
             ]
         );
@@ -1311,20 +3367,22 @@ mod test {
             "\\>!5 a factorial 15! !<",
             true,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert_eq!(
             jobs,
             [
                 CalculationJob {
                     base: CalculationBase::Num(5.into()),
-                    level: 0,
-                    negative: 0
+                    operation: Operation::Factorial(0),
+                    negative: 0,
+                    span: Span::new(2, 4),
                 },
                 CalculationJob {
                     base: CalculationBase::Num(15.into()),
-                    level: 1,
-                    negative: 0
+                    operation: Operation::Factorial(1),
+                    negative: 0,
+                    span: Span::new(17, 20),
                 }
             ]
         );
@@ -1336,20 +3394,22 @@ mod test {
             ">!5 a factorial 15! \\!<",
             true,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert_eq!(
             jobs,
             [
                 CalculationJob {
                     base: CalculationBase::Num(5.into()),
-                    level: 0,
-                    negative: 0
+                    operation: Operation::Factorial(0),
+                    negative: 0,
+                    span: Span::new(1, 3),
                 },
                 CalculationJob {
                     base: CalculationBase::Num(15.into()),
-                    level: 1,
-                    negative: 0
+                    operation: Operation::Factorial(1),
+                    negative: 0,
+                    span: Span::new(16, 19),
                 }
             ]
         );
@@ -1362,7 +3422,7 @@ mod test {
             "https://something.somewhere/with/path/and?tag=siufgiufgia3873844hi8743!hfsf",
             true,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert_eq!(jobs, []);
     }
@@ -1370,13 +3430,14 @@ mod test {
     #[test]
     fn test_uri_poi_doesnt_cause_infinite_loop() {
         let consts = Consts::default();
-        let jobs = parse("84!:", true, &consts, &NumFormat { decimal: '.' });
+        let jobs = parse("84!:", true, &consts, &NumFormat::default());
         assert_eq!(
             jobs,
             [CalculationJob {
                 base: Num(84.into()),
-                level: 1,
-                negative: 0
+                operation: Operation::Factorial(1),
+                negative: 0,
+                span: Span::new(0, 3),
             }]
         );
     }
@@ -1387,14 +3448,15 @@ mod test {
             "\\://something.somewhere/with/path/and?tag=siufgiufgia3873844hi8743!hfsf",
             true,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert_eq!(
             jobs,
             [CalculationJob {
                 base: CalculationBase::Num(8743.into()),
-                level: 1,
-                negative: 0
+                operation: Operation::Factorial(1),
+                negative: 0,
+                span: Span::new(62, 67),
             }]
         );
     }
@@ -1406,7 +3468,7 @@ mod test {
             "(x-2)! (2 word)! ((x/k)-3)! (,x-4)!",
             true,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert_eq!(jobs, []);
     }
@@ -1414,7 +3476,7 @@ mod test {
     #[test]
     fn test_multi_number_paren() {
         let consts = Consts::default();
-        let jobs = parse("(5-2)!", true, &consts, &NumFormat { decimal: '.' });
+        let jobs = parse("(5-2)!", true, &consts, &NumFormat::default());
         assert_eq!(jobs, []);
     }
     #[test]
@@ -1422,7 +3484,7 @@ mod test {
         let consts = Consts::default();
         arbtest(|u| {
             let text: &str = u.arbitrary()?;
-            let _ = parse(text, u.arbitrary()?, &consts, &NumFormat { decimal: '.' });
+            let _ = parse(text, u.arbitrary()?, &consts, &NumFormat::default());
             Ok(())
         });
     }
@@ -1430,7 +3492,7 @@ mod test {
     #[test]
     fn test_constant() {
         let consts = Consts::default();
-        let jobs = parse("!espi!", true, &consts, &NumFormat { decimal: '.' });
+        let jobs = parse("!espi!", true, &consts, &NumFormat::default());
         assert_eq!(jobs, []);
         let jobs = parse(
             "some. pi!",
@@ -1445,8 +3507,9 @@ mod test {
                     Float::with_val(FLOAT_PRECISION, factorion_math::rug::float::Constant::Pi)
                         .into()
                 )),
-                level: 1,
-                negative: 0
+                operation: Operation::Factorial(1),
+                negative: 0,
+                span: Span::new(6, 9),
             }]
         );
     }
@@ -1454,38 +3517,42 @@ mod test {
     #[test]
     fn test_fraction() {
         let consts = Consts::default();
-        let jobs = parse("!5/6!", true, &consts, &NumFormat { decimal: '.' });
+        let jobs = parse("!5/6!", true, &consts, &NumFormat::default());
         assert_eq!(
             jobs,
             [
                 CalculationJob {
                     base: CalculationBase::Num(Number::Exact(5.into())),
-                    level: 0,
-                    negative: 0
+                    operation: Operation::Factorial(0),
+                    negative: 0,
+                    span: Span::new(0, 2),
                 },
                 CalculationJob {
                     base: CalculationBase::Num(Number::Exact(6.into())),
-                    level: 1,
-                    negative: 0
+                    operation: Operation::Factorial(1),
+                    negative: 0,
+                    span: Span::new(3, 5),
                 }
             ]
         );
-        let jobs = parse("5/6!", true, &consts, &NumFormat { decimal: '.' });
+        let jobs = parse("5/6!", true, &consts, &NumFormat::default());
         assert_eq!(
             jobs,
             [CalculationJob {
                 base: CalculationBase::Num(Number::Exact(6.into())),
-                level: 1,
-                negative: 0
+                operation: Operation::Factorial(1),
+                negative: 0,
+                span: Span::new(0, 4),
             }]
         );
-        let jobs = parse("(10/2)!", true, &consts, &NumFormat { decimal: '.' });
+        let jobs = parse("(10/2)!", true, &consts, &NumFormat::default());
         assert_eq!(
             jobs,
             [CalculationJob {
                 base: CalculationBase::Num(Number::Exact(5.into())),
-                level: 1,
-                negative: 0
+                operation: Operation::Factorial(1),
+                negative: 0,
+                span: Span::new(0, 7),
             },]
         );
     }
@@ -1498,7 +3565,7 @@ mod test {
             false,
             false,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert_eq!(
             num,
@@ -1509,7 +3576,10 @@ mod test {
             false,
             false,
             &consts,
-            &NumFormat { decimal: ',' },
+            &NumFormat {
+                decimal: ',',
+                ..Default::default()
+            },
         );
         assert_eq!(
             num,
@@ -1520,26 +3590,20 @@ mod test {
             false,
             false,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert_eq!(
             num,
             Some(Number::Float(Float::with_val(FLOAT_PRECISION, 0.5).into()))
         );
-        let num = parse_num(
-            &mut "1more !",
-            false,
-            true,
-            &consts,
-            &NumFormat { decimal: '.' },
-        );
+        let num = parse_num(&mut "1more !", false, true, &consts, &NumFormat::default());
         assert_eq!(num, Some(1.into()));
         let num = parse_num(
             &mut "1_000more !",
             false,
             true,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert_eq!(num, Some(1000.into()));
         let num = parse_num(
@@ -1547,7 +3611,7 @@ mod test {
             false,
             true,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert_eq!(num, Some(1000.into()));
         let num = parse_num(
@@ -1555,7 +3619,7 @@ mod test {
             false,
             true,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert_eq!(num, Some(1000.into()));
         let num = parse_num(
@@ -1563,7 +3627,10 @@ mod test {
             false,
             true,
             &consts,
-            &NumFormat { decimal: ',' },
+            &NumFormat {
+                decimal: ',',
+                ..Default::default()
+            },
         );
         assert_eq!(num, Some(1000.into()));
         let num = parse_num(
@@ -1571,7 +3638,7 @@ mod test {
             false,
             true,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert_eq!(num, Some(1.into()));
         let num = parse_num(
@@ -1579,7 +3646,7 @@ mod test {
             true,
             false,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert_eq!(num, Some(1.into()));
         let num = parse_num(
@@ -1587,7 +3654,7 @@ mod test {
             false,
             false,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert_eq!(num, Some(150.into()));
         let num = parse_num(
@@ -1595,7 +3662,7 @@ mod test {
             false,
             false,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert_eq!(num, Some(150.into()));
         let num = parse_num(
@@ -1603,7 +3670,7 @@ mod test {
             false,
             false,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert_eq!(num, Some(100.into()));
         let num = parse_num(
@@ -1611,7 +3678,7 @@ mod test {
             false,
             false,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert_eq!(num, Some(100.into()));
         let num = parse_num(
@@ -1619,7 +3686,7 @@ mod test {
             false,
             false,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         let Some(Number::Float(f)) = num else {
             panic!("Not a float")
@@ -1630,126 +3697,48 @@ mod test {
             false,
             false,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert_eq!(
             num,
             Some(Number::Float(Float::with_val(FLOAT_PRECISION, 0.5).into()))
         );
-        let num = parse_num(
-            &mut "e2more !",
-            true,
-            false,
-            &consts,
-            &NumFormat { decimal: '.' },
-        );
+        let num = parse_num(&mut "e2more !", true, false, &consts, &NumFormat::default());
         assert_eq!(num, None);
-        let num = parse_num(
-            &mut "es !",
-            false,
-            false,
-            &consts,
-            &NumFormat { decimal: '.' },
-        );
+        let num = parse_num(&mut "es !", false, false, &consts, &NumFormat::default());
         assert_eq!(num, None);
-        let num = parse_num(
-            &mut "e !",
-            false,
-            false,
-            &consts,
-            &NumFormat { decimal: '.' },
-        );
+        let num = parse_num(&mut "e !", false, false, &consts, &NumFormat::default());
         assert_eq!(num, Some(E(FLOAT_PRECISION)));
-        let num = parse_num(
-            &mut "pi !",
-            false,
-            false,
-            &consts,
-            &NumFormat { decimal: '.' },
-        );
+        let num = parse_num(&mut "pi !", false, false, &consts, &NumFormat::default());
         assert_eq!(num, Some(PI(FLOAT_PRECISION)));
-        let num = parse_num(
-            &mut "π !",
-            false,
-            false,
-            &consts,
-            &NumFormat { decimal: '.' },
-        );
+        let num = parse_num(&mut "π !", false, false, &consts, &NumFormat::default());
         assert_eq!(num, Some(PI(FLOAT_PRECISION)));
-        let num = parse_num(
-            &mut "phi !",
-            false,
-            false,
-            &consts,
-            &NumFormat { decimal: '.' },
-        );
+        let num = parse_num(&mut "phi !", false, false, &consts, &NumFormat::default());
         assert_eq!(num, Some(PHI(FLOAT_PRECISION)));
-        let num = parse_num(
-            &mut "ɸ !",
-            false,
-            false,
-            &consts,
-            &NumFormat { decimal: '.' },
-        );
+        let num = parse_num(&mut "ɸ !", false, false, &consts, &NumFormat::default());
         assert_eq!(num, Some(PHI(FLOAT_PRECISION)));
-        let num = parse_num(
-            &mut "tau !",
-            false,
-            false,
-            &consts,
-            &NumFormat { decimal: '.' },
-        );
+        let num = parse_num(&mut "tau !", false, false, &consts, &NumFormat::default());
         assert_eq!(num, Some(TAU(FLOAT_PRECISION)));
-        let num = parse_num(
-            &mut "τ !",
-            false,
-            false,
-            &consts,
-            &NumFormat { decimal: '.' },
-        );
+        let num = parse_num(&mut "τ !", false, false, &consts, &NumFormat::default());
         assert_eq!(num, Some(TAU(FLOAT_PRECISION)));
         let num = parse_num(
             &mut "∞\u{0303} !",
             false,
             false,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert_eq!(num, Some(Number::ComplexInfinity));
-        let num = parse_num(
-            &mut "∞ !",
-            false,
-            false,
-            &consts,
-            &NumFormat { decimal: '.' },
-        );
+        let num = parse_num(&mut "∞ !", false, false, &consts, &NumFormat::default());
         assert_eq!(num, Some(Number::ComplexInfinity));
-        let num = parse_num(
-            &mut "1/2 !",
-            false,
-            false,
-            &consts,
-            &NumFormat { decimal: '.' },
-        );
+        let num = parse_num(&mut "1/2 !", false, false, &consts, &NumFormat::default());
         assert_eq!(
             num,
             Some(Number::Float(Float::with_val(FLOAT_PRECISION, 0.5).into()))
         );
-        let num = parse_num(
-            &mut "10/2 !",
-            false,
-            false,
-            &consts,
-            &NumFormat { decimal: '.' },
-        );
+        let num = parse_num(&mut "10/2 !", false, false, &consts, &NumFormat::default());
         assert_eq!(num, Some(Number::Exact(5.into())));
-        let num = parse_num(
-            &mut "1.5/2 !",
-            false,
-            false,
-            &consts,
-            &NumFormat { decimal: '.' },
-        );
+        let num = parse_num(&mut "1.5/2 !", false, false, &consts, &NumFormat::default());
         assert_eq!(
             num,
             Some(Number::Float(Float::with_val(FLOAT_PRECISION, 0.75).into()))
@@ -1759,7 +3748,7 @@ mod test {
             false,
             false,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert_eq!(
             num,
@@ -1768,28 +3757,16 @@ mod test {
                 10000000000u64.into()
             ))
         );
-        let num = parse_num(
-            &mut "10/2 !",
-            false,
-            true,
-            &consts,
-            &NumFormat { decimal: '.' },
-        );
+        let num = parse_num(&mut "10/2 !", false, true, &consts, &NumFormat::default());
         assert_eq!(num, Some(Number::Exact(10.into())));
-        let num = parse_num(
-            &mut "10/2!",
-            false,
-            false,
-            &consts,
-            &NumFormat { decimal: '.' },
-        );
+        let num = parse_num(&mut "10/2!", false, false, &consts, &NumFormat::default());
         assert_eq!(num, Some(Number::Exact(2.into())));
         let num = parse_num(
             &mut "^(11)10!",
             false,
             false,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert_eq!(num, None);
         let num = parse_num(
@@ -1797,7 +3774,7 @@ mod test {
             false,
             false,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert_eq!(num, None);
         let num = parse_num(
@@ -1805,7 +3782,7 @@ mod test {
             false,
             false,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert_eq!(num, None);
         let num = parse_num(
@@ -1813,16 +3790,10 @@ mod test {
             false,
             false,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert_eq!(num, None);
-        let num = parse_num(
-            &mut "^(11)10",
-            false,
-            false,
-            &consts,
-            &NumFormat { decimal: '.' },
-        );
+        let num = parse_num(&mut "^(11)10", false, false, &consts, &NumFormat::default());
         assert_eq!(
             num,
             Some(Number::ApproximateDigitsTower(
@@ -1837,7 +3808,7 @@ mod test {
             false,
             false,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert_eq!(
             num,
@@ -1853,7 +3824,7 @@ mod test {
             false,
             false,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert_eq!(num, None);
         let num = parse_num(
@@ -1861,7 +3832,7 @@ mod test {
             false,
             false,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert_eq!(
             num,
@@ -1877,7 +3848,7 @@ mod test {
             false,
             false,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert_eq!(
             num,
@@ -1893,99 +3864,198 @@ mod test {
             false,
             false,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert_eq!(
             num,
             Some(Number::ApproximateDigits(false, 50000000000u64.into()))
         );
-        let num = parse_num(
-            &mut "10^5!",
-            false,
-            false,
-            &consts,
-            &NumFormat { decimal: '.' },
-        );
+        let num = parse_num(&mut "10^5!", false, false, &consts, &NumFormat::default());
         assert_eq!(num, None);
-        let num = parse_num(
-            &mut "10^5",
-            false,
-            false,
-            &consts,
-            &NumFormat { decimal: '.' },
-        );
+        let num = parse_num(&mut "10^5", false, false, &consts, &NumFormat::default());
         assert_eq!(num, Some(Number::Exact(100000.into())));
-        let num = parse_num(
-            &mut "10^5",
-            false,
+        let num = parse_num(&mut "10^5", false, true, &consts, &NumFormat::default());
+        assert_eq!(num, Some(Number::Exact(10.into())));
+    }
+    #[test]
+    fn test_parse_hyperoperation() {
+        let consts = Consts::default();
+        fn parse<'a>(text: &'a str, consts: &Consts) -> (Option<Number>, &'a str) {
+            let mut text = text;
+            let num = parse_num(&mut text, false, false, consts, &NumFormat::default());
+            (num, text)
+        }
+        let parse = |text| parse(text, &consts);
+        assert_eq!(parse("2^^4"), (Some(Number::Exact(65536.into())), ""));
+        assert_eq!(parse("2↑↑↑3 "), (Some(Number::Exact(65536.into())), " "));
+        assert_eq!(
+            parse("3↑↑3"),
+            (Some(Number::Exact(7625597484987u64.into())), "")
+        );
+        assert_eq!(
+            parse("1↑↑↑↑99999999999"),
+            (Some(Number::Exact(1.into())), "")
+        );
+        assert_eq!(parse("0↑↑5"), (Some(Number::Exact(0.into())), ""));
+        assert_eq!(
+            parse("2^100"),
+            (Some(Number::Exact(Integer::from(1) << 100)), "")
+        );
+        let three_tower = Some(Number::Approximate(
+            Float::with_val(
+                FLOAT_PRECISION,
+                Float::parse("1.258014290627491317860390698203").unwrap(),
+            )
+            .into(),
+            3638334640024u64.into(),
+        ));
+        for text in ["3^3^3^3", "3↑↑4", "3\\^\\^4"] {
+            let (num, rest) = parse(text);
+            assert_eq!(rest, "");
+            let (Some(Number::Approximate(x, e)), Some(Number::Approximate(y, f))) =
+                (num, three_tower.clone())
+            else {
+                panic!("{text} is not approximate");
+            };
+            assert_eq!(e, f);
+            assert!((x.as_float().clone() - y.as_float()).abs() < 1e-29f64);
+        }
+        assert_eq!(
+            parse("9^9^9")
+                .0
+                .map(|n| matches!(n, Number::Approximate(_, e) if e == 369693099)),
+            Some(true)
+        );
+        assert_eq!(
+            parse("10↑↑4"),
+            (
+                Some(Number::ApproximateDigitsTower(
+                    false,
+                    false,
+                    1.into(),
+                    10000000000u64.into()
+                )),
+                ""
+            )
+        );
+        // Too tall for a tower
+        assert_eq!(
+            parse("3↑↑↑3"),
+            (
+                Some(Number::ApproximateDigitsHyper(
+                    false,
+                    2,
+                    7625597484985u64.into()
+                )),
+                ""
+            )
+        );
+        // Heights that are towers themselves
+        assert_eq!(
+            parse("3↑↑↑↑3"),
+            (Some(Number::ApproximateDigitsHyper(false, 4, 2.into())), "")
+        );
+        assert_eq!(
+            parse("10↑↑↑10"),
+            (
+                Some(Number::ApproximateDigitsHyper(false, 3, 10.into())),
+                ""
+            )
+        );
+        assert_eq!(
+            parse("2↑↑↑↑↑4"),
+            (Some(Number::ApproximateDigitsHyper(false, 5, 2.into())), "")
+        );
+        // Powers of ten stay towers
+        assert_eq!(
+            parse("10^10^5"),
+            (
+                Some(Number::ApproximateDigitsTower(
+                    false,
+                    false,
+                    1.into(),
+                    5.into()
+                )),
+                ""
+            )
+        );
+        // Revert to after the base, as the op is on the exponent
+        assert_eq!(parse("2^3!"), (None, "^3!"));
+        assert_eq!(parse("3↑↑4.5"), (None, "↑↑4.5"));
+        assert_eq!(parse("3↑↑4↑↑2"), (None, "↑↑4↑↑2"));
+        // Too many arrows, skip all but one char
+        let too_many = format!("2{}4", "↑".repeat(33));
+        assert_eq!(parse(&too_many), (None, "4"));
+        // No hyperoperation
+        assert_eq!(parse("5^(3)"), (Some(Number::Exact(5.into())), "^(3)"));
+        assert_eq!(parse("5↑3"), (Some(Number::Exact(5.into())), "↑3"));
+        assert_eq!(parse("5^3↑2"), (None, "^3↑2"));
+    }
+    #[test]
+    fn test_parse_hyperoperation_jobs() {
+        let consts = Consts::default();
+        let jobs = parse(
+            "(2^^3)! and (2^2^2)? and 3↑↑2! and 2↑↑↑↑↑↑↑↑↑↑↑↑↑↑↑↑↑↑↑↑↑↑↑↑↑↑↑↑↑↑↑↑↑4!",
             true,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
+        );
+        assert_eq!(
+            jobs,
+            [
+                CalculationJob {
+                    base: CalculationBase::Num(2.into()),
+                    operation: Operation::Factorial(1),
+                    negative: 0,
+                    span: Span::new(32, 34),
+                },
+                CalculationJob {
+                    base: CalculationBase::Num(4.into()),
+                    operation: Operation::Factorial(1),
+                    negative: 0,
+                    span: Span::new(139, 141),
+                },
+                CalculationJob {
+                    base: CalculationBase::Num(16.into()),
+                    operation: Operation::Factorial(-1),
+                    negative: 0,
+                    span: Span::new(12, 20),
+                },
+                CalculationJob {
+                    base: CalculationBase::Num(16.into()),
+                    operation: Operation::Factorial(1),
+                    negative: 0,
+                    span: Span::new(0, 7),
+                },
+            ]
         );
-        assert_eq!(num, Some(Number::Exact(10.into())));
     }
     #[test]
     fn test_parse_num_revert() {
         // Note that we want one extra character when we get None, as in such a situation a char will always be skipped
         let consts = Consts::default();
         let mut text = "1 ⨉ 10^(5!)";
-        let num = parse_num(
-            &mut text,
-            false,
-            false,
-            &consts,
-            &NumFormat { decimal: '.' },
-        );
+        let num = parse_num(&mut text, false, false, &consts, &NumFormat::default());
         assert_eq!(num, None);
         assert_eq!(text, "^(5!)");
         let mut text = "^(10 10";
-        let num = parse_num(
-            &mut text,
-            false,
-            false,
-            &consts,
-            &NumFormat { decimal: '.' },
-        );
+        let num = parse_num(&mut text, false, false, &consts, &NumFormat::default());
         assert_eq!(num, None);
         assert_eq!(text, "^(10 10");
         let mut text = "^(10)1";
-        let num = parse_num(
-            &mut text,
-            false,
-            false,
-            &consts,
-            &NumFormat { decimal: '.' },
-        );
+        let num = parse_num(&mut text, false, false, &consts, &NumFormat::default());
         assert_eq!(num, None);
         assert_eq!(text, "^(10)1");
         let mut text = "10^(10^10^\\(5\\)";
-        let num = parse_num(
-            &mut text,
-            false,
-            false,
-            &consts,
-            &NumFormat { decimal: '.' },
-        );
+        let num = parse_num(&mut text, false, false, &consts, &NumFormat::default());
         assert_eq!(num, None);
         assert_eq!(text, "^(10^10^\\(5\\)");
         let mut text = "10^10^10^\\(5\\)!";
-        let num = parse_num(
-            &mut text,
-            false,
-            false,
-            &consts,
-            &NumFormat { decimal: '.' },
-        );
+        let num = parse_num(&mut text, false, false, &consts, &NumFormat::default());
         assert_eq!(num, None);
         assert_eq!(text, "^10^\\(5\\)!");
         let mut text = "10^30!";
-        let num = parse_num(
-            &mut text,
-            false,
-            false,
-            &consts,
-            &NumFormat { decimal: '.' },
-        );
+        let num = parse_num(&mut text, false, false, &consts, &NumFormat::default());
         assert_eq!(num, None);
         assert_eq!(text, "^30!");
     }
@@ -1999,7 +4069,7 @@ mod test {
             true,
             false,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert!(matches!(num, Some(Number::Approximate(_, _))));
         let num = parse_num(
@@ -2007,7 +4077,7 @@ mod test {
             false,
             false,
             &consts,
-            &NumFormat { decimal: '.' },
+            &NumFormat::default(),
         );
         assert!(num.is_some());
     }
@@ -2,7 +2,7 @@ use factorion_lib::rug::{Float, Integer};
 
 use factorion_lib::{
     calculation_results::{Calculation, CalculationResult, Number},
-    calculation_tasks::{CalculationBase, CalculationJob},
+    calculation_tasks::{CalculationBase, CalculationJob, Operation, Span},
     recommended::FLOAT_PRECISION,
 };
 use factorion_math as math;
@@ -46,19 +46,333 @@ fn test_comment_new() {
         vec![
             Calculation {
                 value: 5.into(),
-                steps: vec![(1, false)],
+                steps: vec![(Operation::Factorial(1), false)],
                 result: CalculationResult::Exact(Integer::from(120)),
+                span: Span::new(43, 45),
             },
             Calculation {
                 value: 6.into(),
-                steps: vec![(1, false)],
+                steps: vec![(Operation::Factorial(1), false)],
                 result: CalculationResult::Exact(Integer::from(720)),
+                span: Span::new(50, 52),
             },
         ],
     );
     assert_eq!(comment.status, Status::FACTORIALS_FOUND);
 }
 
+#[test]
+fn test_comment_too_low_precision() {
+    let consts = Consts::default();
+    let comment = Comment::new(
+        "[precision 0] 1.5! [precision 2] 10.5!",
+        (),
+        Commands::NONE,
+        MAX_LENGTH,
+        "en",
+    )
+    .extract(&consts)
+    .calc(&consts);
+    assert_eq!(comment.commands.precision, None);
+    assert_eq!(
+        comment.get_reply(&consts),
+        "I can't use the command `[precision 0]` like that, so I ignored it.\n\nI can't use the command `[precision 2]` like that, so I ignored it.\n\nFactorial of 1.5 is approximately 1.329340388179137020473625612506 \n\nFactorial of 10.5 is approximately 11899423.083962248457013028738683370993 \n\n\n*^(This action was performed by a bot | [Source code](http://f.r0.fyi))*"
+    );
+}
+
+#[test]
+fn test_comment_spans() {
+    let consts = Consts::default();
+    let text = "So (3!)! is bigger than 3!, [steps]";
+    let comment = Comment::new(text, (), Commands::NONE, MAX_LENGTH, "en")
+        .extract(&consts)
+        .calc(&consts);
+    let snippets = comment
+        .calculation_list
+        .iter()
+        .map(|calc| calc.span.slice(text).unwrap())
+        .collect::<Vec<_>>();
+    // 3! is only calculated once, from where it was first written
+    assert_eq!(snippets, ["3!", "(3!)!"]);
+}
+
+#[test]
+fn test_command_why() {
+    let consts = Consts::default();
+    let comment = Comment::new(
+        "I meant 5\\! and !1.5, see https://example.com/3! [why]",
+        (),
+        Commands::NONE,
+        MAX_LENGTH,
+        "en",
+    )
+    .extract(&consts)
+    .calc(&consts);
+    assert!(comment.status.no_factorial);
+    let reply = comment.get_reply(&consts);
+    assert_eq!(
+        reply,
+        "I didn't calculate anything, because:\n\n- The op in `5\\!` is escaped with a backslash.\n- `!1.5` is only defined for whole numbers.\n- `https://example.com/3!` is part of a link, so I left it alone.\n\n\n*^(This action was performed by a bot | [Source code](http://f.r0.fyi))*"
+    );
+}
+
+#[test]
+fn test_command_why_nothing_de() {
+    let consts = Consts::default();
+    let comment = Comment::new("Warum nicht? !why", (), Commands::NONE, MAX_LENGTH, "de")
+        .extract(&consts)
+        .calc(&consts);
+    let reply = comment.get_reply(&consts);
+    assert!(
+        reply.starts_with("Ich habe nichts gefunden, das wie eine Berechnung aussieht.\n\n"),
+        "{reply}"
+    );
+}
+
+#[test]
+fn test_comment_skip_code_and_quotes() {
+    let consts = Consts::default();
+    let text = "> 3! is what they said\n\nbut `x != 5` and\n\n```\n6!\n```\n\nstill 4!";
+    let results = |commands| {
+        Comment::new(text, (), commands, MAX_LENGTH, "en")
+            .extract(&consts)
+            .calc(&consts)
+            .calculation_list
+            .into_iter()
+            .map(|calc| calc.span.slice(text).unwrap().to_owned())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(results(Commands::NONE), ["3!", "4!", "6!"]);
+    assert_eq!(results(Commands::SKIP_CODE), ["3!", "4!"]);
+    assert_eq!(results(Commands::SKIP_CODE | Commands::SKIP_QUOTES), ["4!"]);
+    // Can be turned off per comment
+    let text = "`5!` [no_skip_code]";
+    let comment = Comment::new(text, (), Commands::SKIP_CODE, MAX_LENGTH, "en")
+        .extract(&consts)
+        .calc(&consts);
+    assert_eq!(comment.calculation_list.len(), 1);
+}
+
+#[test]
+fn test_comment_filter_code() {
+    let consts = Consts::default();
+    let text = "if (5!=x) { return !0; } but 4! is 24 [why]";
+    let comment = Comment::new(text, (), Commands::FILTER_CODE, MAX_LENGTH, "en")
+        .extract(&consts)
+        .calc(&consts);
+    let snippets = comment
+        .calculation_list
+        .iter()
+        .map(|calc| calc.span.slice(text).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(snippets, ["4!"]);
+    let comment = Comment::new(
+        "if (5!=x) { [why]",
+        (),
+        Commands::FILTER_CODE,
+        MAX_LENGTH,
+        "en",
+    )
+    .extract(&consts)
+    .calc(&consts);
+    assert_eq!(
+        comment.get_reply(&consts),
+        "I didn't calculate anything, because:\n\n- `5!` looks like code, so I left it alone.\n\n\n*^(This action was performed by a bot | [Source code](http://f.r0.fyi))*"
+    );
+}
+
+#[test]
+fn test_comment_fullwidth_and_devanagari() {
+    let consts = Consts::default();
+    let comment = Comment::new("５！は？ और १०!", (), Commands::NONE, MAX_LENGTH, "en")
+        .extract(&consts)
+        .calc(&consts);
+    let reply = comment.get_reply(&consts);
+    assert_eq!(
+        reply,
+        "Factorial of 5 is 120 \n\nFactorial of 10 is 3628800 \n\n\n*^(This action was performed by a bot | [Source code](http://f.r0.fyi))*"
+    );
+}
+
+#[test]
+fn test_comment_numerals() {
+    let consts = Consts::default();
+    let text = "Chapter XII! and 十! but not MAX!";
+    let comment = Comment::new(text, (), Commands::NONE, MAX_LENGTH, "en")
+        .extract(&consts)
+        .calc(&consts);
+    assert!(comment.calculation_list.is_empty());
+    let comment = Comment::new(text, (), Commands::NUMERALS, MAX_LENGTH, "en")
+        .extract(&consts)
+        .calc(&consts);
+    let reply = comment.get_reply(&consts);
+    assert_eq!(
+        reply,
+        "Factorial of 10 is 3628800 \n\nFactorial of 12 is 479001600 \n\n\n*^(This action was performed by a bot | [Source code](http://f.r0.fyi))*"
+    );
+}
+
+#[test]
+fn test_comment_base_and_unknown_command() {
+    let consts = Consts::default();
+    let comment = Comment::new(
+        "[BASE 16] 5! [shortn]",
+        (),
+        Commands::NONE,
+        MAX_LENGTH,
+        "en",
+    )
+    .extract(&consts)
+    .calc(&consts);
+    let reply = comment.get_reply(&consts);
+    assert_eq!(
+        reply,
+        "I don't know the command `[shortn]`, did you mean `short`?\n\nFactorial of 5₁₆ is 78₁₆ \n\n\n*^(This action was performed by a bot | [Source code](http://f.r0.fyi))*"
+    );
+}
+
+#[test]
+fn test_comment_words_in_brackets() {
+    let consts = Consts::default();
+    let comment = Comment::new("[best] 5!", (), Commands::NONE, MAX_LENGTH, "en")
+        .extract(&consts)
+        .calc(&consts);
+    let reply = comment.get_reply(&consts);
+    assert_eq!(
+        reply,
+        "Factorial of 5 is 120 \n\n\n*^(This action was performed by a bot | [Source code](http://f.r0.fyi))*"
+    );
+    let comment = Comment::new("[sort] [steps] 5!", (), Commands::NONE, MAX_LENGTH, "en")
+        .extract(&consts)
+        .calc(&consts);
+    let reply = comment.get_reply(&consts);
+    assert!(reply.starts_with("I don't know the command `[sort]`, did you mean `short`?"));
+}
+
+#[test]
+fn test_comment_base_not_mixed() {
+    let consts = Consts::default();
+    let reply = |text: &str| {
+        Comment::new(text, (), Commands::NONE, MAX_LENGTH, "en")
+            .extract(&consts)
+            .calc(&consts)
+            .get_reply(&consts)
+    };
+    assert_eq!(
+        reply("[base 16] 10! mod 7"),
+        "Factorial of A₁₆ modulo 7₁₆ is 0₁₆ \n\n\n*^(This action was performed by a bot | [Source code](http://f.r0.fyi))*"
+    );
+    // Approximate results are in decimal, so the input is too
+    assert_eq!(
+        reply("[base 16] 1e7!"),
+        "That is so large, that I can't calculate it, so I'll have to approximate.\n\nFactorial of 10000000 is approximately 1.202423400515903456140153487944 × 10^65657059 \n\n\n*^(This action was performed by a bot | [Source code](http://f.r0.fyi))*"
+    );
+}
+
+#[test]
+fn test_comment_invalid_command() {
+    let consts = Consts::default();
+    let comment = Comment::new(
+        "[digits 50] [precision 2] 5!",
+        (),
+        Commands::NONE,
+        MAX_LENGTH,
+        "en",
+    )
+    .extract(&consts)
+    .calc(&consts);
+    assert_eq!(comment.commands.precision, None);
+    let reply = comment.get_reply(&consts);
+    assert_eq!(
+        reply,
+        "I can't use the command `[digits 50]` like that, so I ignored it.\n\nI can't use the command `[precision 2]` like that, so I ignored it.\n\nFactorial of 5 is 120 \n\n\n*^(This action was performed by a bot | [Source code](http://f.r0.fyi))*"
+    );
+}
+
+#[test]
+fn test_comment_requested_locale() {
+    let consts = Consts::default();
+    let comment = Comment::new("[LANG DE] 2,5!", (), Commands::NONE, MAX_LENGTH, "en")
+        .extract(&consts)
+        .calc(&consts);
+    assert_eq!(comment.locale, "de");
+    assert_eq!(
+        comment.get_reply(&consts),
+        "Fakultät von 2,5 ist ungefähr 3,323350970447842551184064031265 \n\n\n*^(Dieser Kommentar wurde automatisch geschrieben | [Quelltext](http://f.r0.fyi))*"
+    );
+    let comment = Comment::new("!locale xx 5!", (), Commands::NONE, MAX_LENGTH, "de")
+        .extract(&consts)
+        .calc(&consts);
+    assert_eq!(comment.locale, "de");
+    assert_eq!(
+        comment.get_reply(&consts),
+        "Sorry, I currently don't speak xx. Maybe you could [teach me](https://github.com/tolik518/factorion-bot/blob/master/CONTRIBUTING.md#translation)? \n\nFakultät von 5 ist 120 \n\n\n*^(Dieser Kommentar wurde automatisch geschrieben | [Quelltext](http://f.r0.fyi))*"
+    );
+}
+
+#[test]
+fn test_comment_detect_language() {
+    let consts = Consts::default();
+    let locale_of = |text: &str, commands: Commands| {
+        Comment::new(text, (), commands, MAX_LENGTH, "en")
+            .extract(&consts)
+            .calc(&consts)
+            .locale
+    };
+    let german = "Was ist eigentlich die Fakultät von 5!, ich vergesse das immer wieder.";
+    assert_eq!(locale_of(german, Commands::NONE), "en");
+    assert_eq!(locale_of(german, Commands::DETECT_LANGUAGE), "de");
+    assert_eq!(
+        locale_of("Ik heb echt geen idee wat 5! is", Commands::DETECT_LANGUAGE),
+        "nl"
+    );
+    // Too short to tell
+    assert_eq!(locale_of("5! lol", Commands::DETECT_LANGUAGE), "en");
+    // Asking for a locale wins
+    assert_eq!(
+        locale_of(&format!("{german} [lang fr]"), Commands::DETECT_LANGUAGE),
+        "fr"
+    );
+    assert_eq!(
+        locale_of(
+            &format!("{german} [no_detect_language]"),
+            Commands::DETECT_LANGUAGE
+        ),
+        "en"
+    );
+}
+
+#[test]
+fn test_comment_locale_variants() {
+    let consts = Consts::default();
+    // A missing variant falls back to the locale it is based on, without a note
+    let comment = Comment::new("5!", (), Commands::NONE, MAX_LENGTH, "de_AT")
+        .extract(&consts)
+        .calc(&consts);
+    assert_eq!(
+        comment.get_reply(&consts),
+        "Fakultät von 5 ist 120 \n\n\n*^(Dieser Kommentar wurde automatisch geschrieben | [Quelltext](http://f.r0.fyi))*"
+    );
+    let comment = Comment::new(
+        "[lang de-ch] 2.5! 10!",
+        (),
+        Commands::NONE,
+        MAX_LENGTH,
+        "en",
+    )
+    .extract(&consts)
+    .calc(&consts);
+    assert_eq!(comment.locale, "de_CH");
+    assert_eq!(
+        comment.get_reply(&consts),
+        "Fakultät von 10 ist 3'628'800 \n\nFakultät von 2.5 ist ungefähr 3.323350970447842551184064031265 \n\n\n*^(Dieser Kommentar wurde automatisch geschrieben | [Quelltext](http://f.r0.fyi))*"
+    );
+    let comment = Comment::new("!lang de_AT 5!", (), Commands::NONE, MAX_LENGTH, "en")
+        .extract(&consts)
+        .calc(&consts);
+    assert_eq!(comment.locale, "de");
+}
+
 #[test]
 fn test_comment_new_double_factorial() {
     let consts = Consts::default();
@@ -75,8 +389,9 @@ fn test_comment_new_double_factorial() {
         comment.calculation_list,
         vec![Calculation {
             value: 6.into(),
-            steps: vec![(2, false)],
+            steps: vec![(Operation::Factorial(2), false)],
             result: CalculationResult::Exact(Integer::from(48)),
+            span: Span::new(43, 46),
         }]
     );
     assert_eq!(comment.status, Status::FACTORIALS_FOUND);
@@ -98,8 +413,9 @@ fn test_comment_new_triple_factorial() {
         comment.calculation_list,
         vec![Calculation {
             value: 6.into(),
-            steps: vec![(3, false)],
+            steps: vec![(Operation::Factorial(3), false)],
             result: CalculationResult::Exact(Integer::from(18)),
+            span: Span::new(43, 47),
         }]
     );
     assert_eq!(comment.status, Status::FACTORIALS_FOUND);
@@ -154,8 +470,9 @@ fn test_comment_new_subfactorial() {
         comment.calculation_list,
         vec![Calculation {
             value: 5.into(),
-            steps: vec![(0, false)],
+            steps: vec![(Operation::Factorial(0), false)],
             result: CalculationResult::Exact(Integer::from(44)),
+            span: Span::new(26, 28),
         }]
     );
 }
@@ -176,8 +493,9 @@ fn test_comment_new_termial() {
         comment.calculation_list,
         vec![Calculation {
             value: 5.into(),
-            steps: vec![(-1, false)],
+            steps: vec![(Operation::Factorial(-1), false)],
             result: CalculationResult::Exact(Integer::from(15)),
+            span: Span::new(26, 28),
         }]
     );
 }
@@ -198,12 +516,90 @@ fn test_comment_new_tripletermial() {
         comment.calculation_list,
         vec![Calculation {
             value: 5.into(),
-            steps: vec![(-3, false)],
+            steps: vec![(Operation::Factorial(-3), false)],
             result: CalculationResult::Exact(Integer::from(7)),
+            span: Span::new(26, 30),
+        }]
+    );
+}
+#[test]
+fn test_comment_new_exponential_factorial() {
+    let consts = Consts::default();
+    let comment = Comment::new(
+        "This is a spoiler comment 4$ for $5",
+        (),
+        Commands::TERMIAL,
+        MAX_LENGTH,
+        "en",
+    )
+    .extract(&consts)
+    .calc(&consts);
+
+    assert_eq!(
+        comment.calculation_list,
+        vec![Calculation {
+            value: 4.into(),
+            steps: vec![(Operation::ExponentialFactorial, false)],
+            result: CalculationResult::Exact(Integer::from(262144)),
+            span: Span::new(26, 28),
         }]
     );
 }
 #[test]
+fn test_comment_new_compositorial() {
+    let consts = Consts::default();
+    let comment = Comment::new(
+        "This is a spoiler comment 10!/10#",
+        (),
+        Commands::NONE,
+        MAX_LENGTH,
+        "en",
+    )
+    .extract(&consts)
+    .calc(&consts);
+
+    assert_eq!(
+        comment.calculation_list,
+        vec![Calculation {
+            value: 10.into(),
+            steps: vec![(Operation::Compositorial, false)],
+            result: CalculationResult::Exact(Integer::from(17280)),
+            span: Span::new(26, 33),
+        }]
+    );
+}
+#[test]
+fn test_comment_new_rising_falling_factorial() {
+    let consts = Consts::default();
+    let comment = Comment::new(
+        "This is a spoiler comment 5↑3 and (5)_3",
+        (),
+        Commands::TERMIAL,
+        MAX_LENGTH,
+        "en",
+    )
+    .extract(&consts)
+    .calc(&consts);
+
+    assert_eq!(
+        comment.calculation_list,
+        vec![
+            Calculation {
+                value: 5.into(),
+                steps: vec![(Operation::FallingFactorial(3.into()), false)],
+                result: CalculationResult::Exact(Integer::from(60)),
+                span: Span::new(36, 41),
+            },
+            Calculation {
+                value: 5.into(),
+                steps: vec![(Operation::RisingFactorial(3.into()), false)],
+                result: CalculationResult::Exact(Integer::from(210)),
+                span: Span::new(26, 31),
+            }
+        ]
+    );
+}
+#[test]
 fn test_comment_new_negative() {
     let consts = Consts::default();
     let comment = Comment::new(
@@ -221,23 +617,27 @@ fn test_comment_new_negative() {
         vec![
             Calculation {
                 value: Number::Exact(5.into()),
-                steps: vec![(-1, true)],
-                result: CalculationResult::Exact((-15).into())
+                steps: vec![(Operation::Factorial(-1), true)],
+                result: CalculationResult::Exact((-15).into()),
+                span: Span::new(27, 29),
             },
             Calculation {
                 value: Number::Exact(5.into()),
-                steps: vec![(0, true)],
-                result: CalculationResult::Exact((-44).into())
+                steps: vec![(Operation::Factorial(0), true)],
+                result: CalculationResult::Exact((-44).into()),
+                span: Span::new(35, 37),
             },
             Calculation {
                 value: Number::Exact(5.into()),
-                steps: vec![(1, true)],
-                result: CalculationResult::Exact((-120).into())
+                steps: vec![(Operation::Factorial(1), true)],
+                result: CalculationResult::Exact((-120).into()),
+                span: Span::new(31, 33),
             },
             Calculation {
                 value: Number::Exact(10.into()),
-                steps: vec![(1, false)],
-                result: CalculationResult::Exact(3628800.into())
+                steps: vec![(Operation::Factorial(1), false)],
+                result: CalculationResult::Exact(3628800.into()),
+                span: Span::new(40, 45),
             }
         ]
     );
@@ -260,25 +660,28 @@ fn test_comment_new_of_negative() {
         vec![
             Calculation {
                 value: (-5).into(),
-                steps: vec![(1, false)],
+                steps: vec![(Operation::Factorial(1), false)],
                 result: CalculationResult::ComplexInfinity,
+                span: Span::new(26, 31),
             },
             Calculation {
                 value: (-5).into(),
-                steps: vec![(2, false)],
-                result: CalculationResult::Float(
-                    Float::with_val(FLOAT_PRECISION, 3).recip().into()
-                ),
+                steps: vec![(Operation::Factorial(2), false)],
+                // Calculated with only the precision needed for the shown decimals
+                result: CalculationResult::Float(Float::with_val(171, 3).recip().into()),
+                span: Span::new(32, 38),
             },
             Calculation {
                 value: (-5).into(),
-                steps: vec![(4, false)],
+                steps: vec![(Operation::Factorial(4), false)],
                 result: CalculationResult::Exact((-1).into()),
+                span: Span::new(39, 47),
             },
             Calculation {
                 value: (-5).into(),
-                steps: vec![(5, false)],
+                steps: vec![(Operation::Factorial(5), false)],
                 result: CalculationResult::ComplexInfinity,
+                span: Span::new(48, 57),
             }
         ]
     );
@@ -337,6 +740,7 @@ fn test_comment_new_decimals() {
                     value: Number::Float(number),
                     steps: _,
                     result: CalculationResult::Float(gamma),
+                    span: _,
                 } => (number.as_float().to_f64(), gamma.as_float().to_f64()),
                 _ => unreachable!("No normal factorial included"),
             })
@@ -361,8 +765,9 @@ fn test_comment_new_decimal_integer() {
         comment.calculation_list,
         vec![Calculation {
             value: Number::Exact(0.into()),
-            steps: vec![(1, false)],
-            result: CalculationResult::Exact(1.into())
+            steps: vec![(Operation::Factorial(1), false)],
+            result: CalculationResult::Exact(1.into()),
+            span: Span::new(43, 47),
         }]
     );
     assert_eq!(comment.status, Status::FACTORIALS_FOUND);
@@ -388,6 +793,7 @@ fn test_comment_new_decimals_multifactorial() {
                     value: Number::Float(number),
                     steps: _,
                     result: CalculationResult::Float(gamma),
+                    span: _,
                 } => (number.as_float().to_f64(), gamma.as_float().to_f64()),
                 _ => unreachable!("No normal factorial included"),
             })
@@ -417,6 +823,7 @@ fn test_comment_new_decimals_termial() {
                     value: Number::Float(number),
                     steps: _,
                     result: CalculationResult::Float(gamma),
+                    span: _,
                 } => (number.as_float().to_f64(), gamma.as_float().to_f64()),
                 _ => unreachable!("No normal factorial included"),
             })
@@ -471,8 +878,9 @@ fn test_comment_new_decimals_incomplete() {
         comment.calculation_list,
         [CalculationJob {
             base: CalculationBase::Num(Number::Float(Float::with_val(FLOAT_PRECISION, 0.5).into())),
-            level: 1,
+            operation: Operation::Factorial(1),
             negative: 0,
+            span: Span::new(43, 46),
         }]
     );
 }
@@ -491,8 +899,9 @@ fn test_comment_new_comma_decimals() {
         comment.calculation_list,
         [CalculationJob {
             base: CalculationBase::Num(Number::Float(Float::with_val(FLOAT_PRECISION, 0.5).into())),
-            level: 1,
+            operation: Operation::Factorial(1),
             negative: 0,
+            span: Span::new(43, 47),
         }]
     );
 }
@@ -518,6 +927,7 @@ fn test_comment_new_exponentials() {
                     value: Number::Float(number),
                     steps: _,
                     result: CalculationResult::Float(gamma),
+                    span: _,
                 } => (number.as_float().to_f64(), gamma.as_float().to_f64()),
                 _ => unreachable!("No normal factorial included"),
             })
@@ -539,20 +949,33 @@ fn test_comment_new_exponentials_positive() {
     .extract(&consts)
     .calc(&consts);
     assert_eq!(
-            comment.calculation_list,
-            [
-                Calculation {
-                    value: Number::Exact(10.into()),
-                    steps: vec![(1, true)],
-                    result: CalculationResult::Exact((-3628800).into())
-                },
-                Calculation {
-                    value: Number::Float(Float::with_val(FLOAT_PRECISION, Float::parse("112.342").unwrap()).into()),
-                    steps: vec![(1, false)],
-                    result: CalculationResult::Float(Float::with_val(FLOAT_PRECISION, Float::parse("993525073229285436539807503113271988267318728609930136156505804196109258655775654879896155361191576205057992198378530500089998766548809286881281158234109518671597164775130317741632313.7252607309857759503328865475739439663463350416381893570704080831760770928994102217701454569735908025174055229200345933253782907").unwrap()).into())
-                },
-            ]
-        );
+        comment.calculation_list,
+        [
+            Calculation {
+                value: Number::Exact(10.into()),
+                steps: vec![(Operation::Factorial(1), true)],
+                result: CalculationResult::Exact((-3628800).into()),
+                span: Span::new(44, 51),
+            },
+            Calculation {
+                value: Number::Float(
+                    Float::with_val(FLOAT_PRECISION, Float::parse("112.342").unwrap()).into()
+                ),
+                steps: vec![(Operation::Factorial(1), false)],
+                result: CalculationResult::Float(
+                    Float::with_val(
+                        177,
+                        Float::parse(
+                            "9.935250732292854365398075031132719882673187286099301108e182"
+                        )
+                        .unwrap()
+                    )
+                    .into()
+                ),
+                span: Span::new(52, 63),
+            },
+        ]
+    );
     assert_eq!(comment.status, Status::FACTORIALS_FOUND);
 }
 
@@ -578,6 +1001,7 @@ fn test_comment_new_constants() {
                     value: Number::Float(number),
                     steps: _,
                     result: CalculationResult::Float(gamma),
+                    span: _,
                 } => (number.as_float().to_f64(), gamma.as_float().to_f64()),
                 _ => unreachable!("No normal factorial included"),
             })
@@ -609,8 +1033,9 @@ fn test_comment_new_big_number_and_normal_number() {
         comment.calculation_list,
         vec![Calculation {
             value: 6.into(),
-            steps: vec![(1, false)],
-            result: CalculationResult::Exact(Integer::from(720))
+            steps: vec![(Operation::Factorial(1), false)],
+            result: CalculationResult::Exact(Integer::from(720)),
+            span: Default::default(),
         }]
     );
     assert_eq!(
@@ -794,14 +1219,19 @@ fn test_get_reply_for_multifactorial() {
         meta: (),
         calculation_list: vec![Calculation {
             value: 10.into(),
-            steps: vec![(3, false)],
+            steps: vec![(Operation::Factorial(3), false)],
             result: CalculationResult::Exact(Integer::from(280)),
+            span: Default::default(),
         }],
         notify: None,
         status: Status::FACTORIALS_FOUND,
         commands: Default::default(),
         max_length: MAX_LENGTH,
         locale: "en".to_owned(),
+        diagnostics: Vec::new(),
+        unknown_commands: Vec::new(),
+        invalid_commands: Vec::new(),
+        requested_locale: None,
     };
 
     let reply = comment.get_reply(&consts);
@@ -818,14 +1248,19 @@ fn test_get_reply_for_subfactorial() {
         meta: (),
         calculation_list: vec![Calculation {
             value: 5.into(),
-            steps: vec![(0, false)],
+            steps: vec![(Operation::Factorial(0), false)],
             result: CalculationResult::Exact(Integer::from(44)),
+            span: Default::default(),
         }],
         notify: None,
         status: Status::FACTORIALS_FOUND,
         commands: Default::default(),
         max_length: MAX_LENGTH,
         locale: "en".to_owned(),
+        diagnostics: Vec::new(),
+        unknown_commands: Vec::new(),
+        invalid_commands: Vec::new(),
+        requested_locale: None,
     };
 
     let reply = comment.get_reply(&consts);
@@ -841,14 +1276,19 @@ fn test_get_reply_for_termial() {
         meta: (),
         calculation_list: vec![Calculation {
             value: 5.into(),
-            steps: vec![(-1, false)],
+            steps: vec![(Operation::Factorial(-1), false)],
             result: CalculationResult::Exact(Integer::from(15)),
+            span: Default::default(),
         }],
         notify: None,
         status: Status::FACTORIALS_FOUND,
         commands: Default::default(),
         max_length: MAX_LENGTH,
         locale: "en".to_owned(),
+        diagnostics: Vec::new(),
+        unknown_commands: Vec::new(),
+        invalid_commands: Vec::new(),
+        requested_locale: None,
     };
 
     let reply = comment.get_reply(&consts);
@@ -864,14 +1304,19 @@ fn test_get_reply_for_multitermial() {
         meta: (),
         calculation_list: vec![Calculation {
             value: 5.into(),
-            steps: vec![(-2, false)],
+            steps: vec![(Operation::Factorial(-2), false)],
             result: CalculationResult::Exact(Integer::from(9)),
+            span: Default::default(),
         }],
         notify: None,
         status: Status::FACTORIALS_FOUND,
         commands: Default::default(),
         max_length: MAX_LENGTH,
         locale: "en".to_owned(),
+        diagnostics: Vec::new(),
+        unknown_commands: Vec::new(),
+        invalid_commands: Vec::new(),
+        requested_locale: None,
     };
 
     let reply = comment.get_reply(&consts);
@@ -881,20 +1326,209 @@ fn test_get_reply_for_multitermial() {
     );
 }
 #[test]
+fn test_get_reply_for_exponential_factorial() {
+    let consts = Consts::default();
+    let comment = Comment::new("7$ and 4$", (), Commands::TERMIAL, MAX_LENGTH, "en")
+        .extract(&consts)
+        .calc(&consts);
+
+    let reply = comment.get_reply(&consts);
+    assert_eq!(
+        reply,
+        "Some of these are so large, that I can't even give the number of digits of them, so I have to make a power of ten tower.\n\nExponential factorial of 4 is 262144 \n\nExponential factorial of 7 has on the order of 10^(4.82926103604822698735859355551 × 10^183230) digits \n\n\n*^(This action was performed by a bot | [Source code](http://f.r0.fyi))*"
+    );
+}
+#[test]
+fn test_get_reply_for_compositorial() {
+    let consts = Consts::default();
+    let comment = Comment::new("10!/10#", (), Commands::NONE, MAX_LENGTH, "de")
+        .extract(&consts)
+        .calc(&consts);
+
+    let reply = comment.get_reply(&consts);
+    assert_eq!(
+        reply,
+        "Kompositorial von 10 ist 17280 \n\n\n*^(Dieser Kommentar wurde automatisch geschrieben | [Quelltext](http://f.r0.fyi))*"
+    );
+}
+#[test]
+fn test_get_reply_for_rising_falling_factorial() {
+    let consts = Consts::default();
+    let comment = Comment::new(
+        "[termial] 0.5^(3) and (5)_3!",
+        (),
+        Commands::NONE,
+        MAX_LENGTH,
+        "en",
+    )
+    .extract(&consts)
+    .calc(&consts);
+
+    let reply = comment.get_reply(&consts);
+    assert_eq!(
+        reply,
+        "Rising factorial of order 3 of 0.5 is approximately 1.875 \n\nFactorial of falling factorial of order 3 of 5 is 8320987112741390144276341183223364380754172606361245952449277696409600000000000000 \n\n\n*^(This action was performed by a bot | [Source code](http://f.r0.fyi))*"
+    );
+}
+#[test]
+fn test_get_reply_for_combinatorial_functions() {
+    let consts = Consts::default();
+    let comment = Comment::new(
+        "C(10), S(10, 3), s(5, 2), D(5, 2)! and B(3000)",
+        (),
+        Commands::FUNCTIONS,
+        MAX_LENGTH,
+        "en",
+    )
+    .extract(&consts)
+    .calc(&consts);
+
+    let reply = comment.get_reply(&consts);
+    assert_eq!(
+        reply,
+        "Some of these are so large, that I can't even approximate them well, so I can only give you an approximation on the number of digits.\n\nStirling number of the first kind (k = 2) of 5 is -50 \n\nStirling number of the second kind (k = 3) of 10 is 9330 \n\nCatalan number of 10 is 16796 \n\nBell number of 3000 has approximately 6965 digits \n\nFactorial of rencontres number (k = 2) of 5 is 2432902008176640000 \n\n\n*^(This action was performed by a bot | [Source code](http://f.r0.fyi))*"
+    );
+}
+#[test]
+fn test_get_reply_for_factorion() {
+    let consts = Consts::default();
+    let comment = Comment::new(
+        "[factorion] 169, 2 and 40585!",
+        (),
+        Commands::NONE,
+        MAX_LENGTH,
+        "en",
+    )
+    .extract(&consts)
+    .calc(&consts);
+
+    let reply = comment.get_reply(&consts);
+    assert_eq!(
+        reply,
+        "2 is a factorion, it is the sum of the factorials of its digits!\n\n40585 is a factorion, it is the sum of the factorials of its digits!\n\nIf I post the whole numbers, the comment would get too long. So I had to turn them into scientific notation.\n\nSum of the factorials of the digits of 2 is 2 → 2 \n\nSum of the factorials of the digits of 169 is 363601 → 1454 → 169 → 363601 \n\nFactorial of 40585 is roughly 2.385528502396721078700743039349 × 10^169407 \n\n\n*^(This action was performed by a bot | [Source code](http://f.r0.fyi))*"
+    );
+    // 1 and 2 only with the command
+    let comment = Comment::new("145! and 2!", (), Commands::NONE, MAX_LENGTH, "en")
+        .extract(&consts)
+        .calc(&consts);
+
+    let reply = comment.get_reply(&consts);
+    assert_eq!(
+        reply,
+        "145 is a factorion, it is the sum of the factorials of its digits!\n\nFactorial of 2 is 2 \n\nFactorial of 145 is 804792605747199194484902925779806277109997439007500616344745281047115412373646521410850481879839649227439298230298915019813108221651663659572441609408556917739149315905992811411866635786075524601835815642793302504243200000000000000000000000000000000000 \n\n\n*^(This action was performed by a bot | [Source code](http://f.r0.fyi))*"
+    );
+}
+#[test]
+fn test_get_reply_for_digit_stats() {
+    let consts = Consts::default();
+    let comment = Comment::new(
+        "[digits] 10!, 100000! and 2000000!",
+        (),
+        Commands::NONE,
+        MAX_LENGTH,
+        "en",
+    )
+    .extract(&consts)
+    .calc(&consts);
+
+    let reply = comment.get_reply(&consts);
+    assert_eq!(
+        reply,
+        "Some of those are so large, that I can't calculate them, so I'll have to approximate.\n\nFactorial of 10 is 3628800 \n\nFactorial of 100000 is roughly 2.824229407960347874293421578025 × 10^456573 \n\nFactorial of 2000000 is approximately 3.776821057691410334409502641243 × 10^11733474 \n\nThe digits of the factorial of 10 sum to 27 with digital root 9, it ends in 2 zeros and has the digits 0×2, 1×0, 2×1, 3×1, 4×0, 5×0, 6×1, 7×0, 8×2, 9×0 \n\nThe digits of the factorial of 100000 sum to 1938780 with digital root 9, it ends in 24999 zeros and has the digits 0×68620, 1×43238, 2×43275, 3×43003, 4×42990, 5×42935, 6×43184, 7×43470, 8×42777, 9×43082 \n\nThe factorial of 2000000 ends in 499999 zeros and has digital root 9 \n\n\n*^(This action was performed by a bot | [Source code](http://f.r0.fyi))*"
+    );
+    // The statistics are dropped before anything gets shortened
+    let comment = Comment::new("[digits] 1000!", (), Commands::NONE, 2700, "en")
+        .extract(&consts)
+        .calc(&consts);
+
+    let reply = comment.get_reply(&consts);
+    assert!(reply.starts_with("Factorial of 1000 is 402387260077093773543702433923"));
+    assert!(reply.contains("000 \n\n\n*^("));
+    assert!(!reply.contains("The digits of"));
+}
+#[test]
+fn test_get_reply_for_factorial_modulo() {
+    let consts = Consts::default();
+    let comment = Comment::new(
+        "1000000000! mod 1000000007, 100000000000! % 1000000007 and -5! mod 7",
+        (),
+        Commands::NONE,
+        MAX_LENGTH,
+        "en",
+    )
+    .extract(&consts)
+    .calc(&consts);
+
+    let reply = comment.get_reply(&consts);
+    assert_eq!(
+        reply,
+        "Negative factorial of 5 modulo 7 is 6 \n\nFactorial of 1000000000 modulo 1000000007 is 698611116 \n\nFactorial of 100000000000 modulo 1000000007 is 0 \n\n\n*^(This action was performed by a bot | [Source code](http://f.r0.fyi))*"
+    );
+}
+full_reply_tests!(test_get_reply_for_nested_factorial_modulo: (
+    "(3!)! mod 1000000007, but not 5! mod 7! or (5! mod 7)!",
+    Commands::NONE,
+    "en",
+    "Factorial of factorial of 3 modulo 1000000007 is 720 \n\n\n*^(This action was performed by a bot | [Source code](http://f.r0.fyi))*",
+));
+#[test]
+fn test_get_reply_for_hyperoperation() {
+    let consts = Consts::default();
+    let comment = Comment::new(
+        "What about (2^^3)!, (3↑↑4)! or even (2↑↑↑4)!",
+        (),
+        Commands::NONE,
+        MAX_LENGTH,
+        "en",
+    )
+    .extract(&consts)
+    .calc(&consts);
+
+    let reply = comment.get_reply(&consts);
+    assert_eq!(
+        reply,
+        "Some of these are so large, that not even a power of ten tower fits, so I can only give bounds with up-arrows.\n\nFactorial of 16 is 20922789888000 \n\nFactorial of 1.258014290627491317860390698203 × 10^3638334640024 has on the order of 10^(3638334640037) digits \n\nFactorial of 10↑↑65533 has between 10↑↑65533 and 10↑↑65534 digits \n\n\n*^(This action was performed by a bot | [Source code](http://f.r0.fyi))*"
+    );
+}
+#[test]
+fn test_get_reply_for_hyper_tower() {
+    let consts = Consts::default();
+    let comment = Comment::new(
+        "(^(100000)10)! (3↑↑↑↑3)? and (2↑↑↑↑↑4)!",
+        (),
+        Commands::TERMIAL,
+        MAX_LENGTH,
+        "en",
+    )
+    .extract(&consts)
+    .calc(&consts);
+
+    let reply = comment.get_reply(&consts);
+    assert_eq!(
+        reply,
+        "Some of these are so large, that not even a power of ten tower fits, so I can only give bounds with up-arrows.\n\nFactorial of ^(100000)10 has between 10↑↑100000 and 10↑↑100001 digits \n\nTermial of 10↑↑↑↑2 has between 10↑↑↑↑2 and 10↑↑↑↑3 digits \n\nFactorial of 10→2→5 has between 10→2→5 and 10→3→5 digits \n\n\n*^(This action was performed by a bot | [Source code](http://f.r0.fyi))*"
+    );
+}
+#[test]
 fn test_get_reply_for_big_subfactorial() {
     let consts = Consts::default();
     let comment = Comment {
         meta: (),
         calculation_list: vec![Calculation {
             value: 5000.into(),
-            steps: vec![(0, false)],
+            steps: vec![(Operation::Factorial(0), false)],
             result: CalculationResult::Exact(math::subfactorial(5000)),
+            span: Default::default(),
         }],
         notify: None,
         status: Status::FACTORIALS_FOUND,
         commands: Default::default(),
         max_length: MAX_LENGTH,
         locale: "en".to_owned(),
+        diagnostics: Vec::new(),
+        unknown_commands: Vec::new(),
+        invalid_commands: Vec::new(),
+        requested_locale: None,
     };
 
     let reply = comment.get_reply(&consts);
@@ -911,14 +1545,19 @@ fn test_get_reply_for_high_multifactorial() {
         meta: (),
         calculation_list: vec![Calculation {
             value: 10.into(),
-            steps: vec![(1234567, false)],
+            steps: vec![(Operation::Factorial(1234567), false)],
             result: CalculationResult::Exact(Integer::from(10)),
+            span: Default::default(),
         }],
         notify: None,
         status: Status::FACTORIALS_FOUND,
         commands: Default::default(),
         max_length: MAX_LENGTH,
         locale: "en".to_owned(),
+        diagnostics: Vec::new(),
+        unknown_commands: Vec::new(),
+        invalid_commands: Vec::new(),
+        requested_locale: None,
     };
 
     let reply = comment.get_reply(&consts);
@@ -936,13 +1575,15 @@ fn test_get_reply_for_multiple() {
         calculation_list: vec![
             Calculation {
                 value: 5.into(),
-                steps: vec![(1, false)],
+                steps: vec![(Operation::Factorial(1), false)],
                 result: CalculationResult::Exact(Integer::from(120)),
+                span: Default::default(),
             },
             Calculation {
                 value: 6.into(),
-                steps: vec![(1, false)],
+                steps: vec![(Operation::Factorial(1), false)],
                 result: CalculationResult::Exact(Integer::from(720)),
+                span: Default::default(),
             },
         ],
         notify: None,
@@ -950,6 +1591,10 @@ fn test_get_reply_for_multiple() {
         commands: Default::default(),
         max_length: MAX_LENGTH,
         locale: "en".to_owned(),
+        diagnostics: Vec::new(),
+        unknown_commands: Vec::new(),
+        invalid_commands: Vec::new(),
+        requested_locale: None,
     };
 
     let reply = comment.get_reply(&consts);
@@ -967,18 +1612,21 @@ fn test_get_reply_too_long_with_multiple_numbers() {
         calculation_list: vec![
             Calculation {
                 value: 5.into(),
-                steps: vec![(2, false)],
+                steps: vec![(Operation::Factorial(2), false)],
                 result: CalculationResult::Exact(Integer::from(60)),
+                span: Default::default(),
             },
             Calculation {
                 value: 6.into(),
-                steps: vec![(1, false)],
+                steps: vec![(Operation::Factorial(1), false)],
                 result: CalculationResult::Exact(Integer::from(720)),
+                span: Default::default(),
             },
             Calculation {
                 value: 3249.into(),
-                steps: vec![(1, false)],
+                steps: vec![(Operation::Factorial(1), false)],
                 result: CalculationResult::Exact(math::factorial(3249, 1)),
+                span: Default::default(),
             },
         ],
         notify: None,
@@ -986,6 +1634,10 @@ fn test_get_reply_too_long_with_multiple_numbers() {
         commands: Default::default(),
         max_length: MAX_LENGTH,
         locale: "en".to_owned(),
+        diagnostics: Vec::new(),
+        unknown_commands: Vec::new(),
+        invalid_commands: Vec::new(),
+        requested_locale: None,
     };
 
     let reply = comment.get_reply(&consts);
@@ -1128,7 +1780,7 @@ full_reply_tests!(
         "9e99!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!?!",
         Commands::TERMIAL,
         "en",
-    "That is so large, I can't even fit it in a comment with a power of 10 tower, so I'll have to use tetration!\n\nAll that of 9 × 10^99 has on the order of ^(20000)10 digits \n\n\n*^(This action was performed by a bot | [Source code](http://f.r0.fyi))*",
+    "That is so large, I can't even fit it in a comment with a power of 10 tower, so I'll have to use tetration!\n\nAll that of 9 × 10^99 has on the order of 10↑↑20000 digits \n\n\n*^(This action was performed by a bot | [Source code](http://f.r0.fyi))*",
     ),
     test_ridiculous_input: (
         "9e999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999!",
@@ -1146,30 +1798,34 @@ fn test_get_reply_approximate_digits_from_mixed_types() {
         calculation_list: vec![
             Calculation {
                 value: 8.into(),
-                steps: vec![(2, false)],
+                steps: vec![(Operation::Factorial(2), false)],
                 result: CalculationResult::Exact(Integer::from(384)),
+                span: Default::default(),
             },
             Calculation {
                 value: 10000.into(),
-                steps: vec![(1, false)],
+                steps: vec![(Operation::Factorial(1), false)],
                 result: CalculationResult::Exact(math::factorial(10000, 1)),
+                span: Default::default(),
             },
             Calculation {
                 value: 37923648.into(),
-                steps: vec![(1, false)],
+                steps: vec![(Operation::Factorial(1), false)],
                 result: {
                     let (base, exponent) =
                         math::approximate_factorial(37923648.into(), FLOAT_PRECISION);
                     CalculationResult::Approximate(base.into(), exponent)
                 },
+                span: Default::default(),
             },
             Calculation {
                 value: 283462.into(),
-                steps: vec![(2, false)],
+                steps: vec![(Operation::Factorial(2), false)],
                 result: CalculationResult::ApproximateDigits(
                     false,
                     math::approximate_multifactorial_digits(283462.into(), 2, FLOAT_PRECISION),
                 ),
+                span: Default::default(),
             },
         ],
         notify: None,
@@ -1177,6 +1833,10 @@ fn test_get_reply_approximate_digits_from_mixed_types() {
         commands: Default::default(),
         max_length: MAX_LENGTH,
         locale: "en".to_owned(),
+        diagnostics: Vec::new(),
+        unknown_commands: Vec::new(),
+        invalid_commands: Vec::new(),
+        requested_locale: None,
     };
 
     let reply = comment.get_reply(&consts);
@@ -1253,11 +1913,59 @@ full_reply_tests!(
         "en",
         "Factorial of sixty seven novemnonagintillion eight hundred thirty nine octononagintillion one hundred twenty seven septennonagintillion eight hundred thirty seven sexnonagintillion four hundred forty two quinnonagintillion eight hundred seventy three quattuornonagintillion has approximately twenty centillion four hundred forty six novemnonagintillion five hundred twenty two octononagintillion two hundred fifteen septennonagintillion five hundred sixty four sexnonagintillion two hundred thirty six quinnonagintillion digits \n\n\n*^(This action was performed by a bot | [Source code](http://f.r0.fyi))*",
     ),
+    test_write_out_minus_one_factorial_fr: (
+        "(-1)! [write_out]",
+        Commands::NONE,
+        "fr",
+        "La factorielle de moins un est infini complexe \n\n\n*^(Cette action a été effectuée par un bot | [Code source](http://f.r0.fyi))*",
+    ),
+    test_write_out_factorial_ru: (
+        "7! [write_out]",
+        Commands::NONE,
+        "ru",
+        "факториал от семь равен пять тысяч сорок \n\n\n*^(Это действие выполнено ботом | [Исходный код](http://f.r0.fyi))*",
+    ),
+    test_command_write_out_long_scale: (
+        "13! [write_out long]",
+        Commands::NONE,
+        "en",
+        "Factorial of thirteen is six milliard two hundred twenty seven million twenty thousand eight hundred \n\n\n*^(This action was performed by a bot | [Source code](http://f.r0.fyi))*",
+    ),
+    test_command_scientific_e_notation: (
+        "10000000000! [scientific e]",
+        Commands::NONE,
+        "en",
+        "That is so large, that I can't calculate it, so I'll have to approximate.\n\nFactorial of 10000000000 is approximately 2.32579620567308336510494471995e95657055186 \n\n\n*^(This action was performed by a bot | [Source code](http://f.r0.fyi))*",
+    ),
+    test_command_scientific_superscript_de: (
+        "10000000000! [sci superscript]",
+        Commands::NONE,
+        "de",
+        "Das ist so groß, dass ich es nicht errechnen kann. Also muss ich eine Annäherung geben.\n\nFakultät von 10000000000 ist ungefähr 2,32579620567308336510494471995 × 10⁹⁵⁶⁵⁷⁰⁵⁵¹⁸⁶ \n\n\n*^(Dieser Kommentar wurde automatisch geschrieben | [Quelltext](http://f.r0.fyi))*",
+    ),
+    test_command_scientific_engineering: (
+        "3000! !scientific engineering [short]",
+        Commands::NONE,
+        "en",
+        "Factorial of 3000 is roughly 41.49359603437854085556867093087 × 10^9129 \n\n\n*^(This action was performed by a bot | [Source code](http://f.r0.fyi))*",
+    ),
+    test_command_scientific_si: (
+        "[scientific si] 25!",
+        Commands::NONE,
+        "en",
+        "Factorial of 25 is 15.511210043330985984 Y \n\n\n*^(This action was performed by a bot | [Source code](http://f.r0.fyi))*",
+    ),
+    test_command_scientific_si_short: (
+        "[short] [scientific si] 25! and 3000!",
+        Commands::NONE,
+        "en",
+        "Factorial of 25 is 15.511210043330985984 Y \n\nFactorial of 3000 is roughly 41.49359603437854085556867093087 × 10^9129 \n\n\n*^(This action was performed by a bot | [Source code](http://f.r0.fyi))*",
+    ),
     test_command_write_out_non_en: (
         "176902! !write_out",
         Commands::NONE,
         "de",
-        "I can only write out numbers in english, so I will do that.\n\nFakultät von one hundred seventy six thousand nine hundred two ist seventy five quintriginoctingentrilloctogintillducentillillion five hundred forty five quattuortriginoctingentrilloctogintillducentillillion seven hundred sixty six tretriginoctingentrilloctogintillducentillillion three hundred five duotriginoctingentrilloctogintillducentillillion forty seven untriginoctingentrilloctogintillducentillillion one hundred forty three triginoctingentrilloctogintillducentillillion \n\n\n*^(Dieser Kommentar wurde automatisch geschrieben | [Quelltext](http://f.r0.fyi))*",
+        "Fakultät von einhundertsechsundsiebzigtausendneunhundertzwei ist fünfundsiebzig Oktodeznongenmillquadragintillcentillillionen fünfhundertfünfundvierzig Septendeznongenmillquadragintillcentillilliarden siebenhundertsechsundsechzig Septendeznongenmillquadragintillcentillillionen dreihundertfünf Sexdeznongenmillquadragintillcentillilliarden siebenundvierzig Sexdeznongenmillquadragintillcentillillionen einhundertdreiundvierzig Quindeznongenmillquadragintillcentillilliarden \n\n\n*^(Dieser Kommentar wurde automatisch geschrieben | [Quelltext](http://f.r0.fyi))*",
     ),
 );
 
@@ -0,0 +1,81 @@
+//! Compares the parallel and rounding exact calculations against the single threaded ones.
+//!
+//! Run with `cargo bench -p factorion-math`.
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use factorion_math::rug::integer::IntegerExt64;
+use factorion_math::rug::{Complete, Integer};
+use factorion_math::{parallel_factorial, parallel_subfactorial, subfactorial};
+
+const RUNS: u32 = 3;
+
+fn time(f: impl Fn()) -> Duration {
+    (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+fn compare(name: &str, baseline: impl Fn(), candidate: impl Fn()) {
+    let baseline = time(baseline);
+    let candidate = time(candidate);
+    println!(
+        "{name:<24} baseline: {baseline:>10.2?}  candidate: {candidate:>10.2?}  speedup: {:.2}x",
+        baseline.as_secs_f64() / candidate.as_secs_f64()
+    );
+}
+
+fn main() {
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    println!("threads: {threads}");
+    for n in [100_000, 1_000_000, 3_000_000, 10_000_000] {
+        compare(
+            &format!("{n}!"),
+            || {
+                black_box(Integer::factorial_64(n).complete());
+            },
+            || {
+                black_box(parallel_factorial(n, 1, threads));
+            },
+        );
+    }
+    for (n, k) in [(1_000_000, 2), (3_000_000, 3), (10_000_000, 7)] {
+        compare(
+            &format!("{n}{}", "!".repeat(k as usize)),
+            || {
+                black_box(Integer::factorial_m_64(n, k).complete());
+            },
+            || {
+                black_box(parallel_factorial(n, k as u32, threads));
+            },
+        );
+    }
+    for n in [10_000, 100_000, 300_000] {
+        compare(
+            &format!("!{n}"),
+            || {
+                black_box(parallel_subfactorial(n, 1, 1));
+            },
+            || {
+                black_box(parallel_subfactorial(n, 1, threads));
+            },
+        );
+    }
+    for n in [10_000, 100_000, 300_000, 1_000_000] {
+        // Only the first run includes calculating e, it is cached afterwards
+        compare(
+            &format!("!{n} (rounding)"),
+            || {
+                black_box(parallel_subfactorial(n, 1, threads));
+            },
+            || {
+                black_box(subfactorial(n));
+            },
+        );
+    }
+}
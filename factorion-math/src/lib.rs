@@ -4,14 +4,273 @@ use rug::ops::*;
 use rug::{Complete, Float, Integer};
 use std::ops::Mul;
 use std::ops::Rem;
+use std::sync::{Mutex, OnceLock};
 
 pub use rug;
 
 /// The k-factorial of n
+///
+/// This is the single threaded GMP calculation. [parallel_factorial] is only faster on enough cores,
+/// so it is up to the caller to use it (the benchmark compares both on the machine it runs on).
 pub fn factorial(n: u64, k: u32) -> Integer {
     Integer::factorial_m_64(n, k as u64).complete()
 }
 
+/// Below this many factors, products are multiplied up one by one
+const PRODUCT_TREE_LEAF: u64 = 32;
+
+fn available_threads() -> usize {
+    std::thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// Runs a and b, on separate threads if more than one is given, splitting the threads between them.
+fn join<A: Send, B: Send>(
+    threads: usize,
+    a: impl FnOnce(usize) -> A + Send,
+    b: impl FnOnce(usize) -> B,
+) -> (A, B) {
+    if threads < 2 {
+        return (a(1), b(1));
+    }
+    std::thread::scope(|s| {
+        let a = s.spawn(|| a(threads / 2));
+        let b = b(threads - threads / 2);
+        (a.join().expect("Product thread panicked"), b)
+    })
+}
+
+/// The product of few factors, packing as many as fit into a u64 before multiplying
+fn leaf_product(factors: impl Iterator<Item = u64>) -> Integer {
+    let mut res = Integer::ONE.clone();
+    let mut packed = 1u64;
+    for factor in factors {
+        packed = match packed.checked_mul(factor) {
+            Some(packed) => packed,
+            None => {
+                res *= packed;
+                factor
+            }
+        };
+    }
+    res * packed
+}
+
+/// The product of first, first - step, ..., (count factors) using binary splitting
+fn progression_product(first: u64, step: u64, count: u64, threads: usize) -> Integer {
+    if count <= PRODUCT_TREE_LEAF {
+        return leaf_product((0..count).map(|i| first - i * step));
+    }
+    let half = count / 2;
+    let (a, b) = join(
+        threads,
+        |threads| progression_product(first, step, half, threads),
+        |threads| progression_product(first - half * step, step, count - half, threads),
+    );
+    a * b
+}
+
+/// The product of the factors using binary splitting
+fn slice_product(factors: &[u64], threads: usize) -> Integer {
+    if factors.len() as u64 <= PRODUCT_TREE_LEAF {
+        return leaf_product(factors.iter().copied());
+    }
+    let (left, right) = factors.split_at(factors.len() / 2);
+    let (a, b) = join(
+        threads,
+        |threads| slice_product(left, threads),
+        |threads| slice_product(right, threads),
+    );
+    a * b
+}
+
+/// The odd primes up to n
+fn odd_primes(n: u64) -> Vec<u64> {
+    // sieve[i] is whether 2i + 1 is composite
+    let mut sieve = vec![false; (n / 2 + 1) as usize];
+    let mut primes = Vec::new();
+    let mut p = 3;
+    while p <= n {
+        if !sieve[(p / 2) as usize] {
+            primes.push(p);
+            let mut multiple = p * p;
+            while multiple <= n {
+                sieve[(multiple / 2) as usize] = true;
+                multiple += 2 * p;
+            }
+        }
+        p += 2;
+    }
+    primes
+}
+
+/// The k-factorial of n, calculated on up to threads threads.
+///
+/// Factorials are calculated from their prime factorization
+/// (n! = 2^e_2 * (prod of p with bit j of e_p set)^(2^j) over all j),
+/// multifactorials by multiplying the factors with binary splitting.
+/// Either way the products are split across the threads.
+pub fn parallel_factorial(n: u64, k: u32, threads: usize) -> Integer {
+    let k = k as u64;
+    if n == 0 || k == 0 {
+        return Integer::factorial_m_64(n, k).complete();
+    }
+    // All factors share gcd(n, k), so n!k = gcd^count * (n / gcd)!(k / gcd)
+    let gcd = Integer::from(n).gcd(&Integer::from(k)).to_u64_wrapping();
+    if gcd > 1 {
+        let count = (n - 1) / k + 1;
+        let shared = Integer::u64_pow_u64(gcd, count).complete();
+        return parallel_factorial(n / gcd, (k / gcd) as u32, threads) * shared;
+    }
+    if k > 1 {
+        return progression_product(n, k, (n - 1) / k + 1, threads);
+    }
+    // Legendre: exponent of p in n! is sum of n / p^i
+    let exponent = |p: u64| {
+        let mut e = 0;
+        let mut q = n;
+        while q >= p {
+            q /= p;
+            e += q;
+        }
+        e
+    };
+    let primes = odd_primes(n);
+    let mut bit_groups: Vec<Vec<u64>> = Vec::new();
+    for &p in &primes {
+        let mut e = exponent(p);
+        let mut j = 0;
+        while e > 0 {
+            if e & 1 == 1 {
+                if bit_groups.len() <= j {
+                    bit_groups.resize_with(j + 1, Vec::new);
+                }
+                bit_groups[j].push(p);
+            }
+            e >>= 1;
+            j += 1;
+        }
+    }
+    let mut res = Integer::ONE.clone();
+    for group in bit_groups.iter().rev() {
+        res.square_mut();
+        res *= slice_product(group, threads);
+    }
+    res << exponent(2) as u32
+}
+
+/// The k-subfactorial of n, calculated on up to threads threads.
+///
+/// The recurrence !n_k = n * !(n - k)_k + (-1)^ceil(n / k) is an affine map,
+/// so the maps for all factors are combined with binary splitting, the result is applied to 1.
+pub fn parallel_subfactorial(n: u64, k: u32, threads: usize) -> Integer {
+    let k = k.max(1) as u64;
+    let count = n.div_ceil(k);
+    let smallest = n - count.saturating_sub(1) * k;
+    /// (a, b), such that !(factor of step hi) = a * !(factor of step lo) + b
+    fn affine_product(
+        lo: u64,
+        hi: u64,
+        factor: &(impl Fn(u64) -> u64 + Sync),
+        threads: usize,
+    ) -> (Integer, Integer) {
+        if hi - lo <= PRODUCT_TREE_LEAF {
+            let mut a = Integer::ONE.clone();
+            let mut b = Integer::ZERO;
+            for i in lo + 1..=hi {
+                let factor = factor(i);
+                a *= factor;
+                b *= factor;
+                if i.is_multiple_of(2) {
+                    b += 1;
+                } else {
+                    b -= 1;
+                }
+            }
+            return (a, b);
+        }
+        let mid = lo + (hi - lo) / 2;
+        let ((a_lo, b_lo), (a_hi, b_hi)) = join(
+            threads,
+            |threads| affine_product(lo, mid, factor, threads),
+            |threads| affine_product(mid, hi, factor, threads),
+        );
+        let b = &a_hi * b_lo + b_hi;
+        (a_hi * a_lo, b)
+    }
+    let (a, b) = affine_product(0, count, &|i| smallest + (i - 1) * k, threads);
+    a + b
+}
+
+/// Above this, subfactorials are calculated by rounding, instead of with the recurrence
+const SUBFACTORIAL_ROUNDING_LIMIT: u64 = 1_000;
+
+/// The already calculated values of e^(-1/k) by k, to not recalculate them for every subfactorial
+static INVERSE_ROOTS_OF_E: Mutex<Vec<(u32, Float)>> = Mutex::new(Vec::new());
+
+/// e^(-1/k) with at least prec bits
+///
+/// Calculated as sum of (-1)^i / (k^i * i!) with binary splitting and cached.
+/// The cache is not locked during the calculation, so other subfactorials don't have to wait for it.
+fn inverse_root_of_e(k: u32, prec: u32) -> Float {
+    let lock_cache = || {
+        INVERSE_ROOTS_OF_E
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    };
+    if let Some((_, value)) = lock_cache()
+        .iter()
+        .find(|(key, value)| *key == k && value.prec() >= prec)
+    {
+        return Float::with_val(prec, value);
+    }
+    /// (p, q), such that p / q = sum of (-1)^i / (k^(i - lo) * (lo + 1) * ... * i) for i in lo + 1..=hi
+    fn alternating_series(lo: u64, hi: u64, k: u64, threads: usize) -> (Integer, Integer) {
+        if hi - lo == 1 {
+            return (
+                if hi.is_multiple_of(2) { 1 } else { -1 }.into(),
+                (k * hi).into(),
+            );
+        }
+        let mid = lo + (hi - lo) / 2;
+        let ((p_lo, q_lo), (p_hi, q_hi)) = join(
+            threads,
+            |threads| alternating_series(lo, mid, k, threads),
+            |threads| alternating_series(mid, hi, k, threads),
+        );
+        (p_lo * &q_hi + p_hi, q_lo * q_hi)
+    }
+    // The error is below the first left out term 1 / (k^terms * terms!)
+    let mut terms = 1;
+    let mut bits = 0.0;
+    while bits < prec as f64 + 2.0 {
+        terms += 1;
+        bits += ((k as u64 * terms) as f64).log2();
+    }
+    let (p, q) = alternating_series(0, terms, k as u64, available_threads());
+    let value = Float::with_val(prec, p) / q + 1u32;
+    let mut cache = lock_cache();
+    // Another thread might have calculated it more precisely in the meantime
+    if !cache
+        .iter()
+        .any(|(key, cached)| *key == k && cached.prec() >= prec)
+    {
+        cache.retain(|(key, _)| *key != k);
+        cache.push((k, value.clone()));
+    }
+    value
+}
+
+/// The k-subfactorial of n with n a positive multiple of k, as round(n!_k / e^(1/k))
+fn rounded_subfactorial(n: u64, k: u32) -> Integer {
+    let factorial = factorial(n, k);
+    // !n_k differs from n!_k / e^(1/k) by less than 1 / (n + k), so a few extra bits are enough
+    let prec = factorial.significant_bits() + 64;
+    (Float::with_val(prec, factorial) * inverse_root_of_e(k, prec))
+        .round()
+        .to_integer()
+        .expect("Rounded subfactorial is finite")
+}
+
 /// The k-factorial of -n is the factorial of n-k times this factor (inf if None)
 pub fn negative_multifacorial_factor(n: Integer, k: i32) -> Option<Integer> {
     let n = -n;
@@ -28,7 +287,12 @@ pub fn negative_multifacorial_factor(n: Integer, k: i32) -> Option<Integer> {
 }
 
 /// The subfactorial of n
+///
+/// Large ones are calculated as round(n!/e).
 pub fn subfactorial(n: u64) -> Integer {
+    if n >= SUBFACTORIAL_ROUNDING_LIMIT {
+        return rounded_subfactorial(n, 1);
+    }
     let mut f = Integer::ONE.clone();
     let mut b = true;
     for i in 1..=n {
@@ -43,6 +307,258 @@ pub fn subfactorial(n: u64) -> Integer {
     f
 }
 
+/// The k-subfactorial of n, !n_k = n * !(n - k)_k + (-1)^ceil(n / k), with !n_k = 1 for n <= 0
+///
+/// The generalization of the subfactorial, that keeps !n_k = round(n!_k / e^(1/k)) for positive multiples of k.
+/// Those are calculated that way if large, all others with binary splitting of the recurrence.
+pub fn multi_subfactorial(n: u64, k: u32) -> Integer {
+    if k <= 1 {
+        subfactorial(n)
+    } else if n >= SUBFACTORIAL_ROUNDING_LIMIT && n.is_multiple_of(k as u64) {
+        rounded_subfactorial(n, k)
+    } else {
+        parallel_subfactorial(n, k, available_threads())
+    }
+}
+
+/// The exponential factorial of n, n^(n-1)^...^2^1
+///
+/// Returns None if n > 5, as the exponential factorial of 6 already has more than 10^183230 digits.
+/// Use [approximate_exponential_factorial_digits] for those.
+pub fn exponential_factorial(n: u64) -> Option<Integer> {
+    (1..=n).try_fold(Integer::ONE.clone(), |acc, i| {
+        Some(Integer::from(i).pow(acc.to_u32()?))
+    })
+}
+
+/// The compositorial of n (the product of all composite numbers up to n), n! / n#
+pub fn compositorial(n: u64) -> Integer {
+    factorial(n, 1).div_exact(&Integer::primorial_64(n).complete())
+}
+
+/// The rising factorial (Pochhammer symbol) of x with order n, x^(n) = x(x+1)...(x+n-1)
+pub fn rising_factorial(x: Integer, n: u32) -> Integer {
+    falling_factorial(x + n - 1u32, n)
+}
+
+/// The falling factorial of x with order n, (x)_n = x(x-1)...(x-n+1)
+///
+/// Calculated as the binomial coefficient times n!, which also works for negative x.
+pub fn falling_factorial(x: Integer, n: u32) -> Integer {
+    x.binomial(n) * factorial(n as u64, 1)
+}
+
+/// The n-th Catalan number, C(n) = binomial(2n, n) / (n+1)
+pub fn catalan(n: u32) -> Integer {
+    Integer::from(2 * n as u64)
+        .binomial(n)
+        .div_exact(&Integer::from(n as u64 + 1))
+}
+
+/// The n-th Bell number (the number of partitions of a set of n elements)
+///
+/// Calculated with n! B(n) = sum over k of binomial(n, k) k^n !(n-k),
+/// which only needs n exponentiations, instead of the n^2 additions of the Bell triangle.
+pub fn bell(n: u32) -> Integer {
+    let mut sum = Integer::new();
+    // binomial(n, k) for k = n - m
+    let mut binomial = Integer::ONE.clone();
+    // !m
+    let mut subfactorial = Integer::ONE.clone();
+    for m in 0..=n {
+        let k = n - m;
+        sum += Integer::from(k).pow(n) * &binomial * &subfactorial;
+        subfactorial = subfactorial * (m + 1) + if m % 2 == 0 { -1 } else { 1 };
+        binomial = (binomial * k).div_exact(&Integer::from(m + 1));
+    }
+    sum.div_exact(&factorial(n as u64, 1))
+}
+
+/// The Stirling number of the second kind S(n, k) (the number of partitions of n elements into k non-empty subsets)
+///
+/// Calculated with k! S(n, k) = sum over j of (-1)^j binomial(k, j) (k-j)^n
+pub fn stirling_second(n: u32, k: u32) -> Integer {
+    if k > n {
+        return Integer::ZERO;
+    }
+    let mut sum = Integer::new();
+    let mut binomial = Integer::ONE.clone();
+    for j in 0..=k {
+        let term = Integer::from(k - j).pow(n) * &binomial;
+        if j % 2 == 0 {
+            sum += term;
+        } else {
+            sum -= term;
+        }
+        binomial = (binomial * (k - j)).div_exact(&Integer::from(j + 1));
+    }
+    sum.div_exact(&factorial(k as u64, 1))
+}
+
+/// The (signed) Stirling number of the first kind s(n, k)
+///
+/// Calculated with the recurrence s(m+1, j) = s(m, j-1) - m s(m, j),
+/// only keeping the columns needed to reach s(n, k).
+pub fn stirling_first(n: u32, k: u32) -> Integer {
+    if k > n {
+        return Integer::ZERO;
+    }
+    let mut row = vec![Integer::ZERO; k as usize + 1];
+    row[0] = Integer::ONE.clone();
+    for m in 0..n {
+        let low = k.saturating_sub(n - m - 1);
+        let high = k.min(m + 1);
+        for j in (low..=high).rev() {
+            let j = j as usize;
+            row[j] *= m;
+            row[j].neg_assign();
+            if j > 0 {
+                let (left, right) = row.split_at_mut(j);
+                right[0] += &left[j - 1];
+            }
+        }
+    }
+    row.swap_remove(k as usize)
+}
+
+/// The rencontres number D(n, k) (the number of permutations of n elements with exactly k fixed points), binomial(n, k) !(n-k)
+pub fn rencontres(n: u32, k: u32) -> Integer {
+    if k > n {
+        return Integer::ZERO;
+    }
+    Integer::from(n).binomial(k) * subfactorial((n - k) as u64)
+}
+
+/// The factorials of the decimal digits
+const DIGIT_FACTORIALS: [u64; 10] = [1, 1, 2, 6, 24, 120, 720, 5040, 40320, 362880];
+/// The (base 10) factorions, the only numbers that are the sum of the factorials of their digits
+pub const FACTORIONS: [u32; 4] = [1, 2, 145, 40585];
+
+/// The sum of the factorials of the decimal digits of n (ignoring the sign)
+pub fn digit_factorial_sum(n: &Integer) -> Integer {
+    n.to_string_radix(10)
+        .bytes()
+        .filter(u8::is_ascii_digit)
+        .map(|digit| DIGIT_FACTORIALS[(digit - b'0') as usize])
+        .sum::<u64>()
+        .into()
+}
+
+/// Repeats the digit factorial sum starting at n, until a number repeats (every chain ends in a cycle).
+/// Returns all numbers in order, including n and the first repetition.
+pub fn digit_factorial_chain(n: Integer) -> Vec<Integer> {
+    let mut chain = vec![n];
+    loop {
+        let next = digit_factorial_sum(chain.last().expect("Chain starts with n"));
+        let repeated = chain.contains(&next);
+        chain.push(next);
+        if repeated {
+            return chain;
+        }
+    }
+}
+
+/// Whether n is one of the [FACTORIONS]
+pub fn is_factorion(n: &Integer) -> bool {
+    FACTORIONS.iter().any(|factorion| n == factorion)
+}
+
+/// The exponent of the prime p in n! (Legendre's formula)
+pub fn factorial_prime_exponent(n: &Integer, p: &Integer) -> Integer {
+    let mut n = n.clone();
+    let mut exponent = Integer::ZERO;
+    while n > 0 {
+        n /= p;
+        exponent += &n;
+    }
+    exponent
+}
+
+/// The number of trailing zeros of n! (as there are always more factors 2 than 5)
+pub fn factorial_trailing_zeros(n: &Integer) -> Integer {
+    factorial_prime_exponent(n, &Integer::from(5))
+}
+
+/// Up to this, factors of the modulus are found by trial division
+const TRIAL_DIVISION_LIMIT: u32 = 1_000_000;
+
+/// n! mod m, without calculating n!.
+///
+/// For every prime power p^e of m, n! is either divisible by it, or calculated with Wilson's theorem ((p-1)! ≡ -1 mod p),
+/// if n is just below p. These are then combined with the chinese remainder theorem.
+/// Returns None, if that would take more than max_steps multiplications, or m can't be factored.
+pub fn factorial_mod(n: &Integer, m: &Integer, max_steps: u64) -> Option<Integer> {
+    if *m < 1 || *n < 0 {
+        return None;
+    }
+    // m! (and so every larger factorial) is divisible by m
+    if n >= m {
+        return Some(Integer::ZERO);
+    }
+    if *n <= max_steps {
+        return Some(product_mod(&Integer::from(2), n, m));
+    }
+    let mut result = Integer::ZERO;
+    let mut modulus = Integer::from(1);
+    for (p, e) in factorize(m)? {
+        let prime_power = p.clone().pow(e);
+        let residue = if factorial_prime_exponent(n, &p) >= e {
+            Integer::ZERO
+        } else if e == 1 && (&p - n).complete() <= max_steps {
+            // Here n < p, so (p-1)! = n! (n+1)...(p-1) ≡ -1
+            let rest = product_mod(&(n + 1u8).complete(), &(&p - 1u8).complete(), &p);
+            (-rest.invert(&p).ok()?).rem_euc(&p)
+        } else {
+            return None;
+        };
+        let inverse = (&modulus % &prime_power)
+            .complete()
+            .invert(&prime_power)
+            .ok()?;
+        let lift = ((residue - &result) * inverse).rem_euc(&prime_power);
+        result += &modulus * lift;
+        modulus *= prime_power;
+    }
+    Some(result)
+}
+
+/// The product of all integers from start to end (inclusive) mod m
+fn product_mod(start: &Integer, end: &Integer, m: &Integer) -> Integer {
+    let mut product = Integer::from(1) % m;
+    let mut i = start.clone();
+    while i <= *end {
+        product *= &i;
+        product %= m;
+        i += 1;
+    }
+    product
+}
+
+/// The prime factors of n with their exponents, if they are all small enough or the rest is a prime.
+fn factorize(n: &Integer) -> Option<Vec<(Integer, u32)>> {
+    let mut n = n.clone();
+    let mut factors = Vec::new();
+    let mut d = 2u32;
+    while d <= TRIAL_DIVISION_LIMIT && Integer::from(d).square() <= n {
+        if n.is_divisible_u(d) {
+            let mut e = 0;
+            while n.is_divisible_u(d) {
+                n.div_exact_u_mut(d);
+                e += 1;
+            }
+            factors.push((Integer::from(d), e));
+        }
+        d += 1;
+    }
+    if n > 1 {
+        if Integer::from(d).square() <= n && n.is_probably_prime(30) == rug::integer::IsPrime::No {
+            return None;
+        }
+        factors.push((n, 1));
+    }
+    Some(factors)
+}
+
 /// The termial of n
 pub fn termial(n: Integer) -> Integer {
     (n.clone() * (n + 1)) / 2
@@ -117,6 +633,16 @@ fn fractional_multifactorial_sum(x: Float, j: i32, _k: i32, k: Float) -> Float {
             .unwrap_or(Float::with_val(x.prec(), 1))
 }
 
+/// The rising factorial of x with order n, (x+n-1)! / (x-1)!
+pub fn fractional_rising_factorial(x: Float, n: u32) -> Float {
+    fractional_factorial(x.clone() + n - 1u32) / fractional_factorial(x - 1u32)
+}
+
+/// The falling factorial of x with order n, x! / (x-n)!
+pub fn fractional_falling_factorial(x: Float, n: u32) -> Float {
+    fractional_factorial(x.clone()) / fractional_factorial(x - n)
+}
+
 /// The termial of x
 pub fn fractional_termial(x: Float) -> Float {
     let prec = x.prec();
@@ -289,6 +815,107 @@ pub fn approximate_approx_termial((x, e): (Float, Integer), k: u32) -> (Float, I
     adjust_approximate(((a * b) / (2 * k), 2 * e))
 }
 
+/// The n-th Catalan number as a * 10^b, using the log gamma function
+///
+/// # Panic
+/// Will panic if `n` is negative.
+pub fn approximate_catalan(n: Integer, prec: u32) -> (Float, Integer) {
+    let n = Float::with_val(prec, n);
+    let ln =
+        (n.clone() * 2u8 + 1u8).ln_gamma() - (n.clone() + 1u8).ln_gamma() - (n + 2u8).ln_gamma();
+    approximate_from_ln(ln)
+}
+
+/// The n-th Bell number as a * 10^b, using B(n) ~ n^-1/2 (n/W(n))^(n+1/2) e^(n/W(n) - n - 1)
+///
+/// This asymptotic only has a relative error of about ln(n)/n, so it is only good for the number of digits.
+///
+/// # Panic
+/// Will panic if `n` is not positive.
+///
+/// Algorithm adapted from [Wikipedia](https://en.wikipedia.org/wiki/Bell_number#Growth_rate) as cc-by-sa-4.0
+pub fn approximate_bell(n: Integer, prec: u32) -> (Float, Integer) {
+    let n = Float::with_val(prec, n);
+    let w = lambert_w(n.clone());
+    let ln =
+        (n.clone() / &w).ln() * (n.clone() + 0.5) + n.clone() / w - n.clone() - 1u8 - n.ln() / 2u8;
+    approximate_from_ln(ln)
+}
+
+/// The Stirling number of the second kind S(n, k) as a * 10^b, using S(n, k) ~ k^n / k!
+///
+/// The relative error is about k ((k-1)/k)^n, so this is only good for small k.
+///
+/// # Panic
+/// Will panic if `k` is zero.
+pub fn approximate_stirling_second(n: Integer, k: u32, prec: u32) -> (Float, Integer) {
+    let n = Float::with_val(prec, n);
+    let k = Float::with_val(prec, k);
+    let ln = k.clone().ln() * n - (k + 1u8).ln_gamma();
+    approximate_from_ln(ln)
+}
+
+/// The absolute value of the Stirling number of the first kind |s(n, k)| as a * 10^b,
+/// using |s(n, k)| ~ (n-1)! (ln(n) + γ)^(k-1) / (k-1)!
+///
+/// This asymptotic is only good for very small k, and then only for the number of digits.
+///
+/// # Panic
+/// Will panic if `n` or `k` is zero.
+pub fn approximate_stirling_first(n: Integer, k: u32, prec: u32) -> (Float, Integer) {
+    let n = Float::with_val(prec, n);
+    let k = Float::with_val(prec, k);
+    let gamma = Float::with_val(prec, rug::float::Constant::Euler);
+    let ln = n.clone().ln_gamma() - k.clone().ln_gamma() + (n.ln() + gamma).ln() * (k - 1u8);
+    approximate_from_ln(ln)
+}
+
+/// The rencontres number D(n, k) as a * 10^b, using D(n, k) = n! / k! * !(n-k) / (n-k)!,
+/// where the last factor is 1/e, unless n-k is small, where it is calculated exactly.
+///
+/// # Panic
+/// Will panic if `k > n`.
+pub fn approximate_rencontres(n: Integer, k: u32, prec: u32) -> (Float, Integer) {
+    let rest = (n.clone() - k).to_u64().filter(|rest| *rest <= 32);
+    let n = Float::with_val(prec, n);
+    let k = Float::with_val(prec, k);
+    let ln = (n.clone() + 1u8).ln_gamma() - (k.clone() + 1u8).ln_gamma();
+    let ln = match rest {
+        Some(rest) => {
+            ln - (n - k + 1u8).ln_gamma() + Float::with_val(prec, subfactorial(rest)).ln()
+        }
+        None => ln - 1u8,
+    };
+    approximate_from_ln(ln)
+}
+
+/// Converts a natural logarithm to a * 10^b
+fn approximate_from_ln(ln: Float) -> (Float, Integer) {
+    let prec = ln.prec();
+    let log10 = ln / Float::with_val(prec, 10).ln();
+    let (extra, _) = log10
+        .clone()
+        .to_integer_round(rug::float::Round::Down)
+        .unwrap_or_else(|| panic!("Got non-finite number, log10 was {log10}"));
+    let x = Float::with_val(prec, 10).pow(log10 - &extra);
+    adjust_approximate((x, extra))
+}
+
+/// The principal branch of the Lambert W function for x > 1, using Newton's method (converges quadratically)
+fn lambert_w(x: Float) -> Float {
+    let prec = x.prec();
+    let mut w = x.clone().ln() - x.clone().ln().ln().max(&Float::new(prec));
+    for _ in 0..prec.ilog2() + 8 {
+        let e = w.clone().exp();
+        let next = w.clone() - (w.clone() * &e - &x) / (e * (w.clone() + 1u8));
+        if next == w {
+            break;
+        }
+        w = next;
+    }
+    w
+}
+
 /// Calculates the approximate digits of a multifactorial.
 /// This is based on the base 10 logarithm of Sterling's Approximation.
 ///
@@ -321,6 +948,42 @@ pub fn approximate_multifactorial_digits_float(k: u32, n: Float) -> Integer {
         + Integer::ONE
 }
 
+/// The number of digits of the exponential factorials of 6 and 7 (as the exponent of the tower)
+static EXPONENTIAL_FACTORIAL_DIGITS: OnceLock<(Integer, Integer)> = OnceLock::new();
+
+/// The number of digits of the exponential factorial of n as a power of ten tower (depth, exponent),
+/// meaning it has 10^10^...^exponent digits, with depth tens. If depth is 0, exponent is the number of digits.
+///
+/// Beyond 7, every exponentiation only adds a level to the tower, as the base is negligible.
+/// The ones of 6 and 7 are calculated with the precision needed to be exact once and then cached.
+pub fn approximate_exponential_factorial_digits(n: Integer, prec: u32) -> (Integer, Integer) {
+    if let Some(n @ ..=5) = n.to_u64() {
+        let factorial = exponential_factorial(n).expect("Exponential factorial up to 5 is small");
+        return (Integer::ZERO, length(&factorial, prec));
+    }
+    let (six, seven) = EXPONENTIAL_FACTORIAL_DIGITS.get_or_init(|| {
+        let five = exponential_factorial(5).expect("Exponential factorial of 5 is small");
+        let prec = five.significant_bits() + 64;
+        // log10 of the exponential factorial of 6
+        let log_six = Float::with_val(prec, 6).ln() / Float::with_val(prec, 10).ln() * five;
+        // The exponential factorial of 7 has 6^5^... * log10(7) digits, so 10^(log_six + log10(log10(7)))
+        let seven = (log_six.clone() + Float::with_val(prec, 7).log10().log10())
+            .to_integer()
+            .expect("Exponential factorial of 7 has finite digits");
+        let six = log_six
+            .to_integer_round(rug::float::Round::Down)
+            .expect("Exponential factorial of 6 has finite digits")
+            .0
+            + 1;
+        (six, seven)
+    });
+    if n == 6 {
+        (Integer::ZERO, six.clone())
+    } else {
+        (n - 6, seven.clone())
+    }
+}
+
 /// The k-termial of n as 10^b
 pub fn approximate_termial_digits(n: Integer, k: u32, prec: u32) -> Integer {
     let n = Float::with_val(prec, n);
@@ -351,6 +1014,38 @@ pub fn adjust_approximate((x, e): (Float, Integer)) -> (Float, Integer) {
     (x, total_exponent)
 }
 
+/// base^x, with x = 10^10^...^top (depth tens), as such a tower again, using logarithms to change the base.
+/// Depth 0 is just top. The base has to be at least 2.
+///
+/// From depth 3 on, the base only changes the top by less than the float can hold, so only one ten is added.
+pub fn tower_pow(base: &Integer, depth: Integer, top: Float) -> (Integer, Float) {
+    let prec = top.prec();
+    let log = Float::with_val(prec, base).log10();
+    if depth == 0 {
+        return (Integer::ONE.clone(), top * log);
+    }
+    let log_log = log.log10();
+    if depth == 1 {
+        (Integer::from(2), top + log_log)
+    } else if depth == 2 {
+        // log10(10^top + log_log)
+        let shift = (log_log / Float::with_val(prec, 10).pow(&top) + 1u8).log10();
+        (Integer::from(3), top + shift)
+    } else {
+        (depth + 1, top)
+    }
+}
+
+/// The height h of 10^10^...^top (depth tens), so that 10↑↑h <= it < 10↑↑(h+1), for a top of at least 1.
+pub fn tetration_height(depth: Integer, mut top: Float) -> Integer {
+    let mut height = depth;
+    while top >= 10 {
+        height += 1;
+        top = top.log10();
+    }
+    height
+}
+
 /// Number of digits of n (log10 of absolute, but 1 if 0)
 pub fn length(n: &Integer, prec: u32) -> Integer {
     if n == &0 {
@@ -530,6 +1225,318 @@ mod tests {
         assert_eq!(length(&result, FLOAT_PRECISION), 45660);
     }
 
+    #[test]
+    fn test_exponential_factorial() {
+        assert_eq!(exponential_factorial(0).unwrap(), 1);
+        assert_eq!(exponential_factorial(1).unwrap(), 1);
+        assert_eq!(exponential_factorial(2).unwrap(), 2);
+        assert_eq!(exponential_factorial(3).unwrap(), 9);
+        assert_eq!(exponential_factorial(4).unwrap(), 262144);
+        assert_eq!(
+            length(&exponential_factorial(5).unwrap(), FLOAT_PRECISION),
+            183231
+        );
+        assert_eq!(exponential_factorial(6), None);
+        assert_eq!(exponential_factorial(u64::MAX), None);
+    }
+
+    #[test]
+    fn test_approximate_exponential_factorial_digits() {
+        assert_eq!(
+            approximate_exponential_factorial_digits(4.into(), FLOAT_PRECISION),
+            (0.into(), 6.into())
+        );
+        assert_eq!(
+            approximate_exponential_factorial_digits(5.into(), FLOAT_PRECISION),
+            (0.into(), 183231.into())
+        );
+        let (depth, six) = approximate_exponential_factorial_digits(6.into(), FLOAT_PRECISION);
+        assert_eq!(depth, 0);
+        assert_eq!(length(&six, FLOAT_PRECISION), 183231);
+        let (depth, seven) = approximate_exponential_factorial_digits(7.into(), FLOAT_PRECISION);
+        assert_eq!(depth, 1);
+        // 10^seven digits is about the exponential factorial of 6 times log10(7)
+        assert_eq!(seven, six - 1);
+        let (depth, ten) = approximate_exponential_factorial_digits(10.into(), FLOAT_PRECISION);
+        assert_eq!(depth, 4);
+        assert_eq!(ten, seven);
+    }
+
+    #[test]
+    fn test_compositorial() {
+        assert_eq!(compositorial(0), 1);
+        assert_eq!(compositorial(1), 1);
+        assert_eq!(compositorial(3), 1);
+        assert_eq!(compositorial(4), 4);
+        assert_eq!(compositorial(6), 24);
+        assert_eq!(compositorial(10), 17280);
+        assert_eq!(
+            compositorial(20),
+            Integer::from_str("250822656000").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_rising_falling_factorial() {
+        assert_eq!(rising_factorial(5.into(), 0), 1);
+        assert_eq!(rising_factorial(5.into(), 3), 210);
+        assert_eq!(rising_factorial(1.into(), 10), factorial(10, 1));
+        assert_eq!(rising_factorial((-3).into(), 2), 6);
+        assert_eq!(rising_factorial((-3).into(), 5), 0);
+        assert_eq!(falling_factorial(5.into(), 0), 1);
+        assert_eq!(falling_factorial(5.into(), 3), 60);
+        assert_eq!(falling_factorial(10.into(), 10), factorial(10, 1));
+        assert_eq!(falling_factorial(3.into(), 5), 0);
+        assert_eq!(falling_factorial((-3).into(), 3), -60);
+    }
+
+    #[test]
+    fn test_catalan_bell() {
+        let catalan_numbers = [1, 1, 2, 5, 14, 42, 132, 429, 1430, 4862, 16796];
+        let bell_numbers = [1, 1, 2, 5, 15, 52, 203, 877, 4140, 21147, 115975];
+        for n in 0..=10 {
+            assert_eq!(catalan(n), catalan_numbers[n as usize]);
+            assert_eq!(bell(n), bell_numbers[n as usize]);
+        }
+        assert_eq!(bell(100), stirling_second_row_sum(100));
+    }
+
+    fn stirling_second_row_sum(n: u32) -> Integer {
+        (0..=n).map(|k| stirling_second(n, k)).sum()
+    }
+
+    #[test]
+    fn test_stirling() {
+        assert_eq!(stirling_second(0, 0), 1);
+        assert_eq!(stirling_second(5, 0), 0);
+        assert_eq!(stirling_second(3, 5), 0);
+        assert_eq!(stirling_second(5, 2), 15);
+        assert_eq!(stirling_second(10, 3), 9330);
+        assert_eq!(stirling_second(10, 10), 1);
+        assert_eq!(stirling_first(0, 0), 1);
+        assert_eq!(stirling_first(5, 0), 0);
+        assert_eq!(stirling_first(3, 5), 0);
+        assert_eq!(stirling_first(5, 2), -50);
+        assert_eq!(stirling_first(5, 3), 35);
+        assert_eq!(stirling_first(10, 1), -362880);
+        assert_eq!(stirling_first(10, 10), 1);
+        // The sum of a row of unsigned Stirling numbers of the first kind is n!
+        let sum: Integer = (0..=20).map(|k| stirling_first(20, k).abs()).sum();
+        assert_eq!(sum, factorial(20, 1));
+    }
+
+    #[test]
+    fn test_rencontres() {
+        assert_eq!(rencontres(5, 0), subfactorial(5));
+        assert_eq!(rencontres(5, 2), 20);
+        assert_eq!(rencontres(5, 4), 0);
+        assert_eq!(rencontres(5, 5), 1);
+        assert_eq!(rencontres(5, 6), 0);
+        let sum: Integer = (0..=12).map(|k| rencontres(12, k)).sum();
+        assert_eq!(sum, factorial(12, 1));
+    }
+
+    #[test]
+    fn test_factorial_trailing_zeros() {
+        for n in 0..200u64 {
+            let factorial = factorial(n, 1).to_string();
+            let zeros = factorial.len() - factorial.trim_end_matches('0').len();
+            assert_eq!(factorial_trailing_zeros(&n.into()), zeros, "{n}");
+        }
+        assert_eq!(factorial_trailing_zeros(&1_000_000.into()), 249998);
+    }
+
+    #[test]
+    fn test_factorial_mod() {
+        for n in 0..30u64 {
+            for m in 1..50u32 {
+                assert_eq!(
+                    factorial_mod(&n.into(), &m.into(), 100),
+                    Some(factorial(n, 1) % m),
+                    "{n}! mod {m}"
+                );
+            }
+        }
+        assert_eq!(factorial_mod(&(-1).into(), &7.into(), 100), None);
+        assert_eq!(factorial_mod(&5.into(), &0.into(), 100), None);
+        assert_eq!(
+            factorial_mod(&100_000_000_000u64.into(), &1_000_000_007.into(), 100),
+            Some(Integer::ZERO)
+        );
+        // Wilson
+        assert_eq!(
+            factorial_mod(&1_000_000_006.into(), &1_000_000_007.into(), 100),
+            Some(1_000_000_006.into())
+        );
+        assert_eq!(
+            factorial_mod(&999_990.into(), &1_000_003.into(), 100),
+            factorial_mod(&999_990.into(), &1_000_003.into(), 1_000_000)
+        );
+        // Chinese remainder theorem
+        let m = Integer::from(999_983) * 1_000_003;
+        assert_eq!(
+            factorial_mod(&999_980.into(), &m, 100),
+            factorial_mod(&999_980.into(), &m, 1_000_000)
+        );
+        // Divisible by 2^3 and 3^2, Wilson for 1_000_003
+        let m = Integer::from(72) * 1_000_003;
+        assert_eq!(
+            factorial_mod(&999_990.into(), &m, 100),
+            factorial_mod(&999_990.into(), &m, 1_000_000)
+        );
+        // Too many steps
+        assert_eq!(factorial_mod(&500_000.into(), &1_000_003.into(), 100), None);
+        // Can't be factored
+        let p = Integer::from(1_000_003);
+        assert_eq!(
+            factorial_mod(&1_000_000.into(), &(&p * &p).complete(), 100),
+            None
+        );
+    }
+
+    #[test]
+    fn test_tetration_height() {
+        let height = |depth: u32, top: f64| {
+            tetration_height(depth.into(), Float::with_val(FLOAT_PRECISION, top))
+        };
+        assert_eq!(height(0, 1.0), 0);
+        assert_eq!(height(0, 9.9), 0);
+        assert_eq!(height(0, 10.0), 1);
+        assert_eq!(height(1, 5.0), 1);
+        // 10^100 is between 10^10 and 10^10^10
+        assert_eq!(height(1, 100.0), 2);
+        assert_eq!(height(1, 1e9), 2);
+        assert_eq!(height(1, 1e10), 3);
+        assert_eq!(height(1, 1e11), 3);
+        assert_eq!(height(65533, 1.0), 65533);
+    }
+    #[test]
+    fn test_tower_pow() {
+        // 3^7625597484987 has 3638334640025 digits
+        let (depth, top) = tower_pow(
+            &3.into(),
+            Integer::ZERO,
+            Float::with_val(FLOAT_PRECISION, 7625597484987u64),
+        );
+        assert_eq!(depth, 1);
+        assert_eq!(top.floor().to_integer().unwrap() + 1, 3638334640025u64);
+        // 2^(10^100) = 10^(10^(100 + log10(log10(2))))
+        let (depth, top) = tower_pow(&2.into(), 1.into(), Float::with_val(FLOAT_PRECISION, 100));
+        assert_eq!(depth, 2);
+        assert!((top - 99.47860977f64).abs() < 1e-8f64);
+        let (depth, top) = tower_pow(&2.into(), 2.into(), Float::with_val(FLOAT_PRECISION, 0.5));
+        assert_eq!(depth, 3);
+        let expected: Float = Float::with_val(FLOAT_PRECISION, 10).pow(0.5f64)
+            + Float::with_val(FLOAT_PRECISION, 2).log10().log10();
+        assert!((top - expected.log10()).abs() < 1e-100f64);
+        let (depth, top) = tower_pow(&2.into(), 5.into(), Float::with_val(FLOAT_PRECISION, 2));
+        assert_eq!(depth, 6);
+        assert_eq!(top, 2);
+    }
+
+    #[test]
+    fn test_digit_factorial_sum() {
+        for factorion in FACTORIONS {
+            let factorion = Integer::from(factorion);
+            assert_eq!(digit_factorial_sum(&factorion), factorion);
+            assert!(is_factorion(&factorion));
+        }
+        assert_eq!(digit_factorial_sum(&0.into()), 1);
+        assert_eq!(digit_factorial_sum(&(-145).into()), 145);
+        assert_eq!(digit_factorial_sum(&169.into()), 363601);
+        assert!(!is_factorion(&169.into()));
+        assert_eq!(
+            (1..100_000u32)
+                .filter(|n| digit_factorial_sum(&(*n).into()) == *n)
+                .collect::<Vec<_>>(),
+            FACTORIONS
+        );
+        assert_eq!(
+            digit_factorial_chain(169.into()),
+            [169, 363601, 1454, 169].map(Integer::from)
+        );
+        assert_eq!(
+            digit_factorial_chain(145.into()),
+            [145, 145].map(Integer::from)
+        );
+        assert_eq!(
+            digit_factorial_chain(69.into()),
+            [69, 363600, 1454, 169, 363601, 1454].map(Integer::from)
+        );
+    }
+
+    #[test]
+    fn test_parallel_factorial() {
+        for n in [0, 1, 2, 3, 10, 33, 100, 1000, 12345] {
+            for k in [1, 2, 3, 7, 40] {
+                assert_eq!(
+                    parallel_factorial(n, k, 4),
+                    Integer::factorial_m_64(n, k as u64).complete(),
+                    "{n} {k}"
+                );
+            }
+        }
+        assert_eq!(
+            parallel_factorial(100_001, 1, 3),
+            Integer::factorial_64(100_001).complete()
+        );
+        assert_eq!(
+            parallel_factorial(100_001, 10, 1),
+            Integer::factorial_m_64(100_001, 10).complete()
+        );
+    }
+
+    #[test]
+    fn test_parallel_subfactorial() {
+        for n in [0, 1, 2, 5, 32, 33, 100, 999] {
+            assert_eq!(parallel_subfactorial(n, 1, 4), subfactorial(n), "{n}");
+        }
+        assert_eq!(parallel_subfactorial(20, 1, 2), 895014631192902121u64);
+        assert_eq!(parallel_subfactorial(4, 2, 2), 5);
+        assert_eq!(parallel_subfactorial(7, 3, 2), 6);
+    }
+
+    #[test]
+    fn test_rounded_subfactorial() {
+        for n in [1, 2, 3, 10, 100, 999, 1000, 4321] {
+            assert_eq!(
+                rounded_subfactorial(n, 1),
+                parallel_subfactorial(n, 1, 1),
+                "{n}"
+            );
+        }
+        for k in [2, 3, 7] {
+            for n in [k, 2 * k, 10 * k, 1000 * k] {
+                assert_eq!(
+                    rounded_subfactorial(n, k as u32),
+                    parallel_subfactorial(n, k as u32, 1),
+                    "{n} {k}"
+                );
+            }
+        }
+        assert_eq!(subfactorial(1000), parallel_subfactorial(1000, 1, 1));
+    }
+
+    #[test]
+    fn test_multi_subfactorial() {
+        assert_eq!(multi_subfactorial(0, 2), 1);
+        assert_eq!(multi_subfactorial(1, 2), 0);
+        assert_eq!(multi_subfactorial(2, 2), 1);
+        assert_eq!(multi_subfactorial(3, 2), 1);
+        assert_eq!(multi_subfactorial(4, 2), 5);
+        assert_eq!(multi_subfactorial(6, 2), 29);
+        assert_eq!(multi_subfactorial(6, 3), 13);
+        assert_eq!(multi_subfactorial(10, 1), subfactorial(10));
+        assert_eq!(
+            multi_subfactorial(2002, 2),
+            parallel_subfactorial(2002, 2, 1)
+        );
+        assert_eq!(
+            multi_subfactorial(2001, 2),
+            parallel_subfactorial(2001, 2, 1)
+        );
+    }
+
     // #[test]
     // fn test_calculate_upper_limit() {
     //     let num = UPPER_CALCULATION_LIMIT;
@@ -809,6 +1816,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_fractional_rising_falling_factorial() {
+        assert_eq!(
+            fractional_rising_factorial(Float::with_val(FLOAT_PRECISION, 0.5), 0).to_f64(),
+            1.0
+        );
+        assert_eq!(
+            fractional_rising_factorial(Float::with_val(FLOAT_PRECISION, 0.5), 3).to_f64(),
+            1.875
+        );
+        assert_eq!(
+            fractional_rising_factorial(Float::with_val(FLOAT_PRECISION, -2.5), 2).to_f64(),
+            3.75
+        );
+        assert_eq!(
+            fractional_falling_factorial(Float::with_val(FLOAT_PRECISION, 0.5), 3).to_f64(),
+            0.375
+        );
+        assert_eq!(
+            fractional_falling_factorial(Float::with_val(FLOAT_PRECISION, 4.5), 2).to_f64(),
+            15.75
+        );
+    }
+
     /// Formats the output of [`approximate_factorial`], by combining the 10 exponents of the number and the extra exponent.
     ///
     /// Moved here, because it only serves now as a better way to write tests (no need to write the full Float)
@@ -1102,6 +2133,56 @@ mod tests {
             "2.4030683314272798 × 10^149782852759048374086174435540541697458852801497098166716942773108417067675395564612635387525330784125840646946627616848133179160356420518046715389467668593867073936456715664835203314565170586655834418196130754742857782566661567670695743221925893825617328228282134137308218885930219847567667381695223181"
         );
     }
+    fn assert_approximates((x, e): (Float, Integer), exact: Integer, digits: i32) {
+        let (exact_x, exact_e) =
+            adjust_approximate((Float::with_val(FLOAT_PRECISION, exact), 0.into()));
+        assert_eq!(e, exact_e);
+        assert!(
+            (x.clone() - &exact_x).abs() < Float::with_val(FLOAT_PRECISION, 10).pow(-digits),
+            "{x} is not close to {exact_x}"
+        );
+    }
+    #[test]
+    fn test_approximate_combinatorial() {
+        assert_approximates(
+            approximate_catalan(1000.into(), FLOAT_PRECISION),
+            catalan(1000),
+            30,
+        );
+        assert_approximates(
+            approximate_stirling_second(2000.into(), 7, FLOAT_PRECISION),
+            stirling_second(2000, 7),
+            30,
+        );
+        assert_approximates(
+            approximate_rencontres(1000.into(), 3, FLOAT_PRECISION),
+            rencontres(1000, 3),
+            30,
+        );
+        assert_approximates(
+            approximate_rencontres(1000.into(), 990, FLOAT_PRECISION),
+            rencontres(1000, 990),
+            30,
+        );
+        // Only the digits are accurate
+        assert_approximates(
+            approximate_bell(1000.into(), FLOAT_PRECISION),
+            bell(1000),
+            -1,
+        );
+        assert_approximates(
+            approximate_stirling_first(1000.into(), 2, FLOAT_PRECISION),
+            stirling_first(1000, 2).abs(),
+            -1,
+        );
+        assert_eq!(
+            format_approximate(approximate_catalan(
+                10_000_000_000u64.into(),
+                FLOAT_PRECISION
+            )),
+            "1.0741105143828862 × 10^6020599898"
+        );
+    }
     #[test]
     fn test_approximate_subfactorial() {
         // NOTE: the last digit may not be correct